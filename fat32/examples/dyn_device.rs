@@ -0,0 +1,73 @@
+// Choisir le device à l'exécution : `cargo run --example dyn_device --features std -- [image]`
+//
+// Sans argument, le volume est formaté dans un disque en mémoire ; avec un
+// chemin, l'image existante est montée. Les deux passent par le même type,
+// `Fat32FileSystem<Box<dyn BlockDevice<Error = IoErrorKind>>>`.
+use std::convert::Infallible;
+use std::env;
+
+use fat32::devices::{CachedDevice, FileDevice};
+use fat32::{
+    BlockDevice, Fat32Error, Fat32FileSystem, IoErrorKind, MemoryDevice, ReadBlockDevice, Result,
+};
+
+const RAM_SECTORS: u32 = 69_632; // 34 MiB
+
+type DynDevice = Box<dyn BlockDevice<Error = IoErrorKind>>;
+
+/// Disque en mémoire avec l'erreur des autres devices de l'application
+struct RamDisk(MemoryDevice);
+
+/// `MemoryDevice` n'a pas d'erreur propre : ses erreurs s'élargissent telles quelles
+fn widen<T>(result: Result<T, Infallible>) -> Result<T, IoErrorKind> {
+    result.map_err(Fat32Error::widen)
+}
+
+impl ReadBlockDevice for RamDisk {
+    type Error = IoErrorKind;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), IoErrorKind> {
+        widen(self.0.read_sector(sector, buffer))
+    }
+
+    fn sector_size(&self) -> usize {
+        self.0.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.0.num_sectors()
+    }
+}
+
+impl BlockDevice for RamDisk {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), IoErrorKind> {
+        widen(self.0.write_sector(sector, buffer))
+    }
+}
+
+fn mount(image: Option<&str>) -> Result<Fat32FileSystem<DynDevice>, IoErrorKind> {
+    match image {
+        Some(path) => {
+            let file = FileDevice::open(path).map_err(Fat32Error::from)?;
+            let device: DynDevice = Box::new(CachedDevice::new(file, 64));
+            Fat32FileSystem::new(device)
+        }
+        None => {
+            let device: DynDevice = Box::new(RamDisk(MemoryDevice::new(RAM_SECTORS)));
+            let mut fs = Fat32FileSystem::format(device, RAM_SECTORS, 1)?;
+            fs.write_file("/LISEZMOI.TXT", b"disque en memoire\n")?;
+            Ok(fs)
+        }
+    }
+}
+
+fn main() -> Result<(), IoErrorKind> {
+    let image = env::args().nth(1);
+    let mut fs = mount(image.as_deref())?;
+
+    for (entry, long_name) in fs.list_dir_with_long_names(None)? {
+        println!("{:>10}  {}", entry.file_size(), long_name.unwrap_or_else(|| entry.short_name()));
+    }
+    fs.unmount().map_err(|(_, e)| e)?;
+    Ok(())
+}
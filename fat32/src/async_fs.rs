@@ -0,0 +1,414 @@
+//! Système de fichiers FAT32 asynchrone, en lecture (feature `async`)
+//!
+//! Pendant de `Fat32FileSystem` pour les exécuteurs asynchrones (embassy,
+//! tokio...). Le décodage (boot sector, entrées de répertoire, entrées de la
+//! FAT, chemins) est celui du code synchrone : seules les lectures changent.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::cache::{SectorBuf, SectorCache};
+use crate::directory::{matches_name, DirScanner, DirSlot};
+use crate::fat_table::{decode_entry, entry_position, next_from_entry, FAT_CACHE_SECTORS};
+use crate::filesystem::{EntryLocation, MAX_DIR_ENTRIES};
+use crate::path::{lookup_steps, split_parent, validate_lookup_component, Step};
+use crate::{
+    BootSector, CorruptionKind, DirectoryEntry, EntryDefect, Fat32Error, FatFile, Result,
+};
+
+/// Device asynchrone
+///
+/// Même contrat que `BlockDevice`, avec des lectures et écritures qui rendent
+/// la main à l'exécuteur au lieu de bloquer. Les futures ne sont pas tenues
+/// d'être `Send` : les exécuteurs embarqués tournent sur un seul cœur.
+#[allow(async_fn_in_trait)]
+pub trait AsyncBlockDevice {
+    type Error: core::fmt::Debug;
+
+    async fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), Self::Error>;
+    async fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), Self::Error>;
+    fn sector_size(&self) -> usize;
+}
+
+impl<D: AsyncBlockDevice> AsyncBlockDevice for &mut D {
+    type Error = D::Error;
+
+    async fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        (**self).read_sector(sector, buffer).await
+    }
+
+    async fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        (**self).write_sector(sector, buffer).await
+    }
+
+    fn sector_size(&self) -> usize {
+        (**self).sector_size()
+    }
+}
+
+/// Système de fichiers FAT32 asynchrone, en lecture seule
+///
+/// Les secteurs de la FAT lus sont gardés dans un petit cache ; les
+/// répertoires et les données sont relus à chaque accès.
+pub struct AsyncFat32FileSystem<D: AsyncBlockDevice> {
+    device: D,
+    boot_sector: BootSector,
+    current_directory: u32, // cluster du répertoire courant
+    fat_cache: SectorCache,
+}
+
+impl<D: AsyncBlockDevice> AsyncFat32FileSystem<D> {
+    /// Monter le système de fichiers
+    pub async fn new(mut device: D) -> Result<Self, D::Error> {
+        // Lire le boot sector (un secteur FAT32 fait au moins 512 octets)
+        let mut buffer = alloc::vec![0u8; device.sector_size().max(512)];
+        device.read_sector(0, &mut buffer).await?;
+
+        let boot_sector = unsafe { BootSector::from_bytes(&buffer) };
+        boot_sector.validate().map_err(Fat32Error::widen)?;
+        boot_sector.check_sector_size(device.sector_size()).map_err(Fat32Error::widen)?;
+
+        Ok(Self {
+            device,
+            current_directory: boot_sector.root_cluster(),
+            boot_sector,
+            fat_cache: SectorCache::new(FAT_CACHE_SECTORS),
+        })
+    }
+
+    /// Boot sector du volume monté
+    pub fn boot_sector(&self) -> &BootSector {
+        &self.boot_sector
+    }
+
+    /// Accéder au device sous-jacent
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+
+    /// Démonter le système de fichiers et récupérer le device
+    pub fn into_device(self) -> D {
+        self.device
+    }
+
+    /// Obtenir le cluster du répertoire courant
+    pub fn current_dir(&self) -> u32 {
+        self.current_directory
+    }
+
+    /// Changer de répertoire
+    pub async fn change_dir(&mut self, path: &str) -> Result<(), D::Error> {
+        let cluster = self.resolve_path(path).await?;
+
+        // Vérifier que c'est bien un dossier
+        let _ = self.read_directory(cluster).await?;
+
+        self.current_directory = cluster;
+        Ok(())
+    }
+
+    /// Lister les fichiers d'un répertoire
+    pub async fn list_dir(&mut self, path: Option<&str>) -> Result<Vec<DirectoryEntry>, D::Error> {
+        let entries = self.list_dir_with_long_names(path).await?;
+        Ok(entries.into_iter().map(|(entry, _)| entry).collect())
+    }
+
+    /// Lister un répertoire avec les noms longs (VFAT) des entrées
+    ///
+    /// Voir `Fat32FileSystem::list_dir_with_long_names`.
+    pub async fn list_dir_with_long_names(
+        &mut self,
+        path: Option<&str>,
+    ) -> Result<Vec<(DirectoryEntry, Option<String>)>, D::Error> {
+        let cluster = self.resolve_path(path.unwrap_or("")).await?;
+        self.read_directory(cluster).await
+    }
+
+    /// Lire le contenu d'un fichier
+    pub async fn read_file(&mut self, path: &str) -> Result<Vec<u8>, D::Error> {
+        let (location, entry) = self
+            .find_path(path)
+            .await?
+            .filter(|(_, e)| !e.attributes().is_directory())
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        let mut file = self.handle(location, &entry).await?;
+        let mut data = alloc::vec![0u8; file.size() as usize];
+        let mut read = 0;
+        while read < data.len() {
+            read += self.read_handle(&mut file, &mut data[read..]).await?;
+        }
+        Ok(data)
+    }
+
+    /// Lire un fichier texte UTF-8
+    ///
+    /// Retourne `Fat32Error::InvalidUtf8` si le contenu n'est pas de l'UTF-8
+    /// valide.
+    pub async fn read_to_string(&mut self, path: &str) -> Result<String, D::Error> {
+        let data = self.read_file(path).await?;
+        String::from_utf8(data).map_err(|_| Fat32Error::InvalidUtf8)
+    }
+
+    /// Ouvrir un fichier en lecture
+    ///
+    /// Le handle se lit par morceaux avec `read_handle`, sans charger le
+    /// fichier en entier. Un dossier donne `InvalidPath`.
+    pub async fn open_file(&mut self, path: &str) -> Result<FatFile, D::Error> {
+        let found = self.find_path(path).await?;
+        let (location, entry) = found.ok_or_else(|| Fat32Error::not_found(path))?;
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
+        self.handle(location, &entry).await
+    }
+
+    /// Lire depuis un handle à sa position courante
+    ///
+    /// Retourne le nombre d'octets lus (0 en fin de fichier). Seuls les
+    /// secteurs couverts par la lecture sont lus.
+    pub async fn read_handle(
+        &mut self,
+        file: &mut FatFile,
+        buf: &mut [u8],
+    ) -> Result<usize, D::Error> {
+        if !file.readable {
+            return Err(Fat32Error::InvalidInput);
+        }
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let bytes_per_sector = self.boot_sector.bytes_per_sector() as usize;
+        let mut sector = alloc::vec![0u8; bytes_per_sector];
+        let mut read = 0;
+
+        while read < buf.len() && !file.is_eof() {
+            let position = file.position as usize;
+            let cluster = *file
+                .clusters
+                .get(position / cluster_size)
+                .ok_or(Fat32Error::bad_entry(EntryDefect::ChainTooShort))?;
+            let offset = position % cluster_size;
+            let count = (bytes_per_sector - offset % bytes_per_sector)
+                .min(buf.len() - read)
+                .min(file.remaining() as usize);
+
+            let sector_number =
+                self.boot_sector.cluster_to_sector(cluster) + (offset / bytes_per_sector) as u32;
+            self.device.read_sector(sector_number, &mut sector).await?;
+            let start = offset % bytes_per_sector;
+            buf[read..read + count].copy_from_slice(&sector[start..start + count]);
+
+            read += count;
+            file.position += count as u32;
+        }
+
+        Ok(read)
+    }
+
+    /// Handle de lecture sur une entrée trouvée
+    async fn handle(
+        &mut self,
+        location: EntryLocation,
+        entry: &DirectoryEntry,
+    ) -> Result<FatFile, D::Error> {
+        let clusters = if entry.first_cluster() < 2 {
+            Vec::new()
+        } else {
+            self.cluster_chain(entry.first_cluster()).await?
+        };
+
+        Ok(FatFile {
+            clusters,
+            size: entry.file_size(),
+            position: 0,
+            entry: location,
+            readable: true,
+            writable: false,
+            append: false,
+        })
+    }
+
+    /// Chercher l'entrée désignée par un chemin
+    async fn find_path(
+        &mut self,
+        path: &str,
+    ) -> Result<Option<(EntryLocation, DirectoryEntry)>, D::Error> {
+        let (dir, name) = split_parent(path);
+        validate_lookup_component(name)?;
+        let dir_cluster = if dir.is_empty() {
+            self.current_directory
+        } else {
+            self.resolve_path(dir).await?
+        };
+        self.find_entry(dir_cluster, name).await
+    }
+
+    /// Résoudre un chemin (relatif au répertoire courant) vers un cluster
+    async fn resolve_path(&mut self, path: &str) -> Result<u32, D::Error> {
+        let (absolute, steps) = lookup_steps(path);
+        let mut current = if absolute {
+            self.boot_sector.root_cluster()
+        } else {
+            self.current_directory
+        };
+
+        for (index, step) in steps.enumerate() {
+            let index = index.min(u16::MAX as usize) as u16;
+            let component = match step? {
+                Step::Parent => {
+                    current = self.find_parent(current).await.map_err(|e| e.at_component(index))?;
+                    continue;
+                }
+                Step::Child(component) => component,
+            };
+
+            let (_, entry) = self
+                .find_entry(current, component)
+                .await?
+                .filter(|(_, e)| e.attributes().is_directory() && !e.is_dot() && !e.is_dot_dot())
+                .ok_or_else(|| Fat32Error::not_found_at(index, component))?;
+
+            current = entry.first_cluster();
+        }
+
+        Ok(current)
+    }
+
+    /// Trouver le dossier parent
+    async fn find_parent(&mut self, cluster: u32) -> Result<u32, D::Error> {
+        let entries = self.read_directory(cluster).await?;
+
+        let (entry, _) = entries
+            .into_iter()
+            .find(|(entry, _)| entry.is_dot_dot())
+            .ok_or_else(|| Fat32Error::not_found_at(0, ".."))?;
+        Ok(match entry.first_cluster() {
+            0 => self.boot_sector.root_cluster(),
+            parent => parent,
+        })
+    }
+
+    /// Lire un répertoire en associant à chaque entrée son nom long
+    async fn read_directory(
+        &mut self,
+        dir_cluster: u32,
+    ) -> Result<Vec<(DirectoryEntry, Option<String>)>, D::Error> {
+        let mut entries = Vec::new();
+        let mut scanner = DirScanner::default();
+
+        for cluster in self.dir_chain(dir_cluster).await? {
+            let data = self.read_cluster(cluster).await?;
+
+            for chunk in data.chunks_exact(DirectoryEntry::SIZE) {
+                match scanner.scan(chunk) {
+                    DirSlot::End => return Ok(entries),
+                    DirSlot::Entry(entry, long) => entries.push((entry, long)),
+                    DirSlot::Skip => {}
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Chercher une entrée par nom et retourner sa position
+    ///
+    /// Même comparaison des noms que `Fat32FileSystem::find_entry_in_dir`.
+    async fn find_entry(
+        &mut self,
+        dir_cluster: u32,
+        name: &str,
+    ) -> Result<Option<(EntryLocation, DirectoryEntry)>, D::Error> {
+        let mut scanner = DirScanner::default();
+
+        for cluster in self.dir_chain(dir_cluster).await? {
+            let data = self.read_cluster(cluster).await?;
+
+            for (i, chunk) in data.chunks_exact(DirectoryEntry::SIZE).enumerate() {
+                let (entry, long) = match scanner.scan(chunk) {
+                    DirSlot::End => return Ok(None),
+                    DirSlot::Entry(entry, long) => (entry, long),
+                    DirSlot::Skip => continue,
+                };
+
+                if matches_name(&entry, long.as_deref(), name) {
+                    let location = EntryLocation {
+                        cluster,
+                        offset: i * DirectoryEntry::SIZE,
+                        long_name: None,
+                    };
+                    return Ok(Some((location, entry)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Chaîne d'un répertoire, bornée à la taille maximale d'un répertoire
+    async fn dir_chain(&mut self, dir_cluster: u32) -> Result<Vec<u32>, D::Error> {
+        let max_clusters = (MAX_DIR_ENTRIES * DirectoryEntry::SIZE)
+            .div_ceil(self.boot_sector.cluster_size() as usize);
+        let chain = self.cluster_chain(dir_cluster).await?;
+        if chain.len() > max_clusters {
+            let clusters = chain.len() as u32;
+            return Err(Fat32Error::Corrupted(CorruptionKind::DirectoryTooLarge { clusters }));
+        }
+        Ok(chain)
+    }
+
+    /// Obtenir tous les clusters d'une chaîne
+    ///
+    /// Voir `FatTable::cluster_chain`.
+    async fn cluster_chain(&mut self, start_cluster: u32) -> Result<Vec<u32>, D::Error> {
+        let mut chain = Vec::new();
+        let mut current = start_cluster;
+
+        loop {
+            if chain.len() as u32 > self.boot_sector.num_clusters() {
+                return Err(Fat32Error::Corrupted(CorruptionKind::ChainCycle { at: current }));
+            }
+            chain.push(current);
+
+            match self.next_cluster(current).await {
+                Ok(next) => current = next,
+                Err(Fat32Error::EndOfChain) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(chain)
+    }
+
+    /// Obtenir le cluster suivant dans la chaîne
+    async fn next_cluster(&mut self, cluster: u32) -> Result<u32, D::Error> {
+        // Les clusters commencent à 2
+        if cluster < 2 {
+            return Err(Fat32Error::InvalidCluster);
+        }
+
+        let (fat_sector, entry_offset) = entry_position(&self.boot_sector, cluster);
+        if !self.fat_cache.contains(fat_sector) {
+            let mut buffer = alloc::vec![0u8; self.boot_sector.bytes_per_sector() as usize];
+            self.device.read_sector(fat_sector, &mut buffer).await?;
+            // Cache en lecture seule : rien à écrire en cas d'éviction
+            let _ = self.fat_cache.insert(fat_sector, SectorBuf::from_slice(&buffer), false);
+        }
+
+        match self.fat_cache.get(fat_sector) {
+            Some(data) => {
+                let max_cluster = self.boot_sector.num_clusters() + 1;
+                next_from_entry(cluster, decode_entry(data, entry_offset), max_cluster)
+            }
+            None => unreachable!(),
+        }
+    }
+
+    /// Lire un cluster complet
+    async fn read_cluster(&mut self, cluster: u32) -> Result<Vec<u8>, D::Error> {
+        let bytes_per_sector = self.boot_sector.bytes_per_sector() as usize;
+        let first_sector = self.boot_sector.cluster_to_sector(cluster);
+
+        let mut data = alloc::vec![0u8; self.boot_sector.cluster_size() as usize];
+        for (i, chunk) in data.chunks_mut(bytes_per_sector).enumerate() {
+            self.device.read_sector(first_sector + i as u32, chunk).await?;
+        }
+        Ok(data)
+    }
+}
@@ -1,8 +1,19 @@
+use alloc::string::String;
+use crate::cache::SectorBuf;
+use crate::devices::write_sector_aligned;
 use crate::Result;
-use crate::Fat32Error;
+use crate::{BlockDevice, Fat32Error};
 
 // bootsecteur pour fat32
 
+/// Valeur "fin de chaîne" écrite dans la FAT
+pub(crate) const FAT_EOC: u32 = 0x0FFFFFFF;
+
+/// Signatures du secteur FSInfo
+pub(crate) const FS_INFO_LEAD_SIG: u32 = 0x41615252;
+pub(crate) const FS_INFO_STRUC_SIG: u32 = 0x61417272;
+pub(crate) const FS_INFO_TRAIL_SIG: u32 = 0xAA550000;
+
 #[repr(C, packed)]
 #[derive(Clone, Copy, Debug)]
 pub struct BootSector {
@@ -39,15 +50,231 @@ pub struct BootSector {
 impl BootSector {
     // use unsafe pour lire depuis la memoire une structure packed
     // On s'assure que data contient au moins 512 octets avant d'appeler.
+    ///
+    /// # Safety
+    ///
+    /// `data` doit contenir au moins 90 octets (la taille de la structure).
     pub unsafe fn from_bytes(data: &[u8]) -> Self {
         unsafe { core::ptr::read_unaligned(data.as_ptr() as *const BootSector) }
     }
+    /// Sérialiser le boot sector (512 octets, signature 0x55AA incluse)
+    pub fn to_bytes(&self) -> [u8; 512] {
+        let mut data = [0u8; 512];
+        unsafe { core::ptr::write_unaligned(data.as_mut_ptr() as *mut BootSector, *self) };
+        data[510] = 0x55;
+        data[511] = 0xAA;
+        data
+    }
+
+    /// Taille minimale de la FAT (en secteurs) pour un volume donné
+    ///
+    /// Estime le nombre de clusters une fois les FATs retirées, puis arrondit
+    /// `(clusters + 2) * 4` octets au secteur supérieur (même calcul que
+    /// mkfs.fat sans alignement). Entiers seulement ; retourne 0 si la taille
+    /// des clusters ou des secteurs est nulle.
+    pub fn calculate_fat_size(
+        total_sectors: u32,
+        sectors_per_cluster: u8,
+        bytes_per_sector: u16,
+        reserved: u16,
+        num_fats: u8,
+    ) -> u32 {
+        if sectors_per_cluster == 0 || bytes_per_sector == 0 {
+            return 0;
+        }
+        let data_sectors = total_sectors.saturating_sub(reserved as u32) as u64;
+        let bytes_per_sec = bytes_per_sector as u64;
+        let num_fats = num_fats as u64;
+
+        // Chaque cluster coûte ses secteurs + 4 octets dans chaque FAT
+        let clusters = (data_sectors * bytes_per_sec + num_fats * 8)
+            / (sectors_per_cluster as u64 * bytes_per_sec + num_fats * 4);
+        ((clusters + 2) * 4).div_ceil(bytes_per_sec) as u32
+    }
+
+    /// Formater un volume FAT32 de `total_sectors` secteurs de 512 octets
+    ///
+    /// Écrit le boot sector (et sa copie en secteur 6), le secteur FSInfo
+    /// et des FATs vides. Le répertoire racine est initialisé par
+    /// `Fat32FileSystem::format`. `InvalidBootSector` si le volume aurait
+    /// moins de 65525 clusters (voir `is_fat32`).
+    pub fn format<D: BlockDevice>(
+        device: &mut D,
+        total_sectors: u32,
+        sectors_per_cluster: u8,
+    ) -> Result<Self, D::Error> {
+        if !sectors_per_cluster.is_power_of_two() {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+
+        let bytes_per_sector = 512u16;
+        let reserved = 32u16;
+        let num_fats = 2u8;
+        if total_sectors <= reserved as u32 {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+
+        let fat_size = Self::calculate_fat_size(
+            total_sectors,
+            sectors_per_cluster,
+            bytes_per_sector,
+            reserved,
+            num_fats,
+        );
+
+        let boot_sector = BootSector {
+            jmp_boot: [0xEB, 0x58, 0x90],
+            oem_name: *b"MSWIN4.1",
+            bytes_per_sector,
+            sectors_per_cluster,
+            reserved_sector_count: reserved,
+            num_fats,
+            root_entry_count: 0,
+            total_sectors_16: 0,
+            media: 0xF8,
+            fat_size_16: 0,
+            sectors_per_track: 32,
+            num_heads: 64,
+            hidden_sectors: 0,
+            total_sectors_32: total_sectors,
+            fat_size_32: fat_size,
+            ext_flags: 0,
+            fs_version: 0,
+            root_cluster: 2,
+            fs_info: 1,
+            backup_boot_sector: 6,
+            reserved: [0; 12],
+            drive_number: 0x80,
+            reserved1: 0,
+            boot_signature: 0x29,
+            volume_id: 0x1234_5678,
+            volume_label: *b"NO NAME    ",
+            fs_type: *b"FAT32   ",
+        };
+        boot_sector.validate().map_err(Fat32Error::widen)?;
+        // Moins de 65525 clusters : le volume serait lu comme FAT12/FAT16
+        if !boot_sector.is_fat32() {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+
+        // Boot sector + copie de secours
+        let bytes = boot_sector.to_bytes();
+        write_sector_aligned(device, 0, &bytes)?;
+        write_sector_aligned(device, 6, &bytes)?;
+
+        // FSInfo : tous les clusters sont libres
+        let cluster_count = boot_sector.num_clusters();
+        let mut fs_info = [0u8; 512];
+        fs_info[0..4].copy_from_slice(&FS_INFO_LEAD_SIG.to_le_bytes());
+        fs_info[484..488].copy_from_slice(&FS_INFO_STRUC_SIG.to_le_bytes());
+        fs_info[488..492].copy_from_slice(&cluster_count.to_le_bytes());
+        fs_info[492..496].copy_from_slice(&2u32.to_le_bytes());
+        fs_info[508..512].copy_from_slice(&FS_INFO_TRAIL_SIG.to_le_bytes());
+        write_sector_aligned(device, 1, &fs_info)?;
+        write_sector_aligned(device, 7, &fs_info)?;
+
+        // FATs vides, sauf les deux entrées réservées
+        let mut sector = SectorBuf::new(bytes_per_sector as usize);
+        for fat in 0..num_fats as u32 {
+            let first = boot_sector.first_fat_sector() + fat * fat_size;
+            for i in 0..fat_size {
+                if i == 0 {
+                    sector[0..4].copy_from_slice(&0x0FFFFFF8u32.to_le_bytes()); // media
+                    sector[4..8].copy_from_slice(&FAT_EOC.to_le_bytes());
+                } else {
+                    sector[0..8].fill(0);
+                }
+                write_sector_aligned(device, first + i, &sector)?;
+            }
+        }
+
+        Ok(boot_sector)
+    }
+
+    /// Octets par secteur
     pub fn bytes_per_sector(&self) -> u16 {
-        self.bytes_per_sector
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.bytes_per_sector)) }
+    }
+
+    /// Secteurs réservés avant la première FAT
+    pub fn reserved_sector_count(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.reserved_sector_count)) }
+    }
+
+    /// Entrées de la racine FAT12/16 (0 en FAT32)
+    pub fn root_entry_count(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.root_entry_count)) }
     }
+
+    /// Nombre de secteurs sur 16 bits (0 en FAT32)
+    pub fn total_sectors_16(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.total_sectors_16)) }
+    }
+
+    /// Taille de la FAT sur 16 bits (0 en FAT32)
+    pub fn fat_size_16(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.fat_size_16)) }
+    }
+
+    /// Secteurs par piste (géométrie CHS)
+    pub fn sectors_per_track(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.sectors_per_track)) }
+    }
+
+    /// Nombre de têtes (géométrie CHS)
+    pub fn num_heads(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.num_heads)) }
+    }
+
+    /// Secteurs avant la partition
+    pub fn hidden_sectors(&self) -> u32 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.hidden_sectors)) }
+    }
+
+    /// Nombre de secteurs sur 32 bits
+    pub fn total_sectors_32(&self) -> u32 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.total_sectors_32)) }
+    }
+
+    /// Taille de la FAT sur 32 bits
+    pub fn fat_size_32(&self) -> u32 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.fat_size_32)) }
+    }
+
+    /// Drapeaux de miroir des FATs
+    pub fn ext_flags(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.ext_flags)) }
+    }
+
+    /// Version du format
+    pub fn fs_version(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.fs_version)) }
+    }
+
+    /// Premier cluster de la racine
+    pub fn root_cluster(&self) -> u32 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.root_cluster)) }
+    }
+
+    /// Secteur FSInfo
+    pub fn fs_info(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.fs_info)) }
+    }
+
+    /// Secteur de la copie du boot sector
+    pub fn backup_boot_sector(&self) -> u16 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.backup_boot_sector)) }
+    }
+
+    /// Numéro de série du volume
+    pub fn volume_id(&self) -> u32 {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.volume_id)) }
+    }
+
     pub fn sectors_per_cluster(&self) -> u8 {
         self.sectors_per_cluster
     }
+
     /// Vérifier que le boot sector est valide
     pub fn validate(&self) -> Result<()> {
         // signature
@@ -56,10 +283,15 @@ impl BootSector {
         }
 
         // Vérifier bytes per sector
-        if self.bytes_per_sector != 512 
-            && self.bytes_per_sector != 1024 
-            && self.bytes_per_sector != 2048 
-            && self.bytes_per_sector != 4096 {
+        if self.bytes_per_sector() != 512 
+            && self.bytes_per_sector() != 1024 
+            && self.bytes_per_sector() != 2048 
+            && self.bytes_per_sector() != 4096 {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+
+        // Clusters d'une puissance de deux de secteurs (jamais 0)
+        if !self.sectors_per_cluster.is_power_of_two() {
             return Err(Fat32Error::InvalidBootSector);
         }
 
@@ -71,37 +303,118 @@ impl BootSector {
         Ok(())
     }
 
+    /// Vérifier que le device a des secteurs de la taille du volume
+    ///
+    /// Sinon tous les numéros de secteur calculés seraient faux.
+    pub fn check_sector_size(&self, device_sector_size: usize) -> Result<()> {
+        let volume = self.bytes_per_sector();
+        if device_sector_size != volume as usize {
+            return Err(Fat32Error::SectorSizeMismatch { device: device_sector_size, volume });
+        }
+        Ok(())
+    }
+
+    /// Nom de volume, sans les espaces ni les octets nuls de fin
+    ///
+    /// Les octets non ASCII deviennent `U+FFFD`. Une chaîne vide : pas de nom.
+    pub fn volume_label_str(&self) -> String {
+        // Copie du tableau : pas de référence dans une structure packed
+        let label = self.volume_label;
+        let len = label.iter().rposition(|&b| b != b' ' && b != 0).map_or(0, |i| i + 1);
+        label[..len]
+            .iter()
+            .map(|&b| if b.is_ascii() { b as char } else { char::REPLACEMENT_CHARACTER })
+            .collect()
+    }
+
     /// Taille d'un cluster en octets
     pub fn cluster_size(&self) -> u32 {
-        self.bytes_per_sector as u32 * self.sectors_per_cluster as u32
+        self.bytes_per_sector() as u32 * self.sectors_per_cluster as u32
     }
 
     /// Taille de la FAT
     pub fn fat_size(&self) -> u32 {
-        if self.fat_size_16 != 0 {
-            self.fat_size_16 as u32
+        if self.fat_size_16() != 0 {
+            self.fat_size_16() as u32
         } else {
-            self.fat_size_32
+            self.fat_size_32()
         }
     }
 
     /// Nombre total de secteurs
     pub fn total_sectors(&self) -> u32 {
-        if self.total_sectors_16 != 0 {
-            self.total_sectors_16 as u32
+        if self.total_sectors_16() != 0 {
+            self.total_sectors_16() as u32
         } else {
-            self.total_sectors_32
+            self.total_sectors_32()
         }
     }
 
+    /// Nombre de clusters de données (`CountofClusters` de la spécification)
+    ///
+    /// Les clusters 0 et 1 sont réservés : les numéros valides vont de 2 à
+    /// `num_clusters() + 1`.
+    pub fn num_clusters(&self) -> u32 {
+        let data_sectors = self.total_sectors().saturating_sub(self.first_data_sector());
+        data_sectors / self.sectors_per_cluster as u32
+    }
+
+    /// Volume FAT12 d'après le nombre de clusters (moins de 4085)
+    pub fn is_fat12(&self) -> bool {
+        self.num_clusters() < 4085
+    }
+
+    /// Volume FAT16 d'après le nombre de clusters (de 4085 à 65524)
+    pub fn is_fat16(&self) -> bool {
+        (4085..65525).contains(&self.num_clusters())
+    }
+
+    /// Volume FAT32 d'après le nombre de clusters (65525 ou plus)
+    ///
+    /// Seul critère de la spécification Microsoft : la chaîne `fs_type` est
+    /// indicative et ne compte pas.
+    pub fn is_fat32(&self) -> bool {
+        self.num_clusters() >= 65525
+    }
+
+    /// Champs réservés au BPB FAT12/FAT16 (racine fixe, FAT 16 bits) ?
+    pub(crate) fn has_fat16_layout(&self) -> bool {
+        self.root_entry_count() != 0 || self.fat_size_16() != 0
+    }
+
     /// Premier secteur de données
     pub fn first_data_sector(&self) -> u32 {
-        self.reserved_sector_count as u32 + (self.num_fats as u32 * self.fat_size())
+        self.reserved_sector_count() as u32 + (self.num_fats as u32 * self.fat_size())
     }
 
     /// Premier secteur de la FAT
     pub fn first_fat_sector(&self) -> u32 {
-        self.reserved_sector_count as u32
+        self.reserved_sector_count() as u32
+    }
+
+    /// Premier secteur d'un cluster de données (à partir de 2)
+    pub fn cluster_to_sector(&self, cluster: u32) -> u32 {
+        (cluster - 2) * self.sectors_per_cluster as u32 + self.first_data_sector()
+    }
+}
+
+// Résumé du volume, pas les 90 octets de la structure
+#[cfg(feature = "defmt")]
+impl defmt::Format for BootSector {
+    fn format(&self, f: defmt::Formatter) {
+        let label = self.volume_label_str();
+        defmt::write!(
+            f,
+            "BootSector {{ label: {=str}, id: {=u32:#010x}, sectors: {=u32}, \
+             bytes_per_sector: {=u16}, cluster_size: {=u32}, clusters: {=u32}, root: {=u32} }}",
+            label.as_str(),
+            self.volume_id(),
+            self.total_sectors(),
+            self.bytes_per_sector(),
+            self.cluster_size(),
+            self.num_clusters(),
+            self.root_cluster()
+        )
     }
 }
 
@@ -120,4 +433,138 @@ mod tests {
         let bs = unsafe { BootSector::from_bytes(&data) };
         assert!(bs.validate().is_ok());
     }
+
+    #[test]
+    fn test_to_bytes_roundtrip() {
+        let mut data = [0u8; 512];
+        data[66] = 0x29;
+        data[11..13].copy_from_slice(&512u16.to_le_bytes());
+        data[13] = 8;
+        data[16] = 2;
+        data[44..48].copy_from_slice(&2u32.to_le_bytes());
+
+        let bs = unsafe { BootSector::from_bytes(&data) };
+        let bytes = bs.to_bytes();
+        assert_eq!(&bytes[..90], &data[..90]);
+        assert_eq!(&bytes[510..], &[0x55, 0xAA]);
+    }
+
+    #[test]
+    fn test_calculate_fat_size() {
+        // (secteurs, secteurs/cluster, octets/secteur, réservés, FATs, attendu)
+        let cases: [(u32, u8, u16, u16, u8, u32); 7] = [
+            (1024, 1, 512, 32, 2, 8),            // 512 KiB
+            (8192, 1, 512, 32, 2, 63),           // 4 MiB
+            (8192, 1, 512, 32, 1, 64),           // 4 MiB, une seule FAT
+            (262_144, 16, 512, 32, 2, 128),      // 128 MiB, clusters de 8 KiB
+            (1_048_576, 8, 512, 32, 2, 1022),    // 512 MiB, clusters de 4 KiB
+            (67_108_864, 64, 512, 32, 2, 8191),  // 32 GiB, clusters de 32 KiB
+            (2_097_152, 1, 4096, 32, 2, 2044),   // 8 GiB, secteurs de 4 KiB
+        ];
+
+        for (total, spc, bps, reserved, fats, expected) in cases {
+            let fat_size = BootSector::calculate_fat_size(total, spc, bps, reserved, fats);
+            assert_eq!(fat_size, expected, "volume de {} secteurs", total);
+
+            // La FAT couvre tous les clusters restants
+            let clusters = (total - reserved as u32 - fats as u32 * fat_size) / spc as u32;
+            assert!((clusters as u64 + 2) * 4 <= fat_size as u64 * bps as u64);
+        }
+        assert_eq!(BootSector::calculate_fat_size(16, 1, 512, 32, 2), 1);
+    }
+
+    #[test]
+    fn test_field_accessors() {
+        // Chaque champ 16/32 bits a une valeur distincte, à un offset impair ou non
+        let mut data = [0u8; 512];
+        let fields: [(usize, u32, usize); 16] = [
+            (11, 0x0200, 2),
+            (14, 0x0020, 2),
+            (17, 0x0102, 2),
+            (19, 0x0304, 2),
+            (22, 0x0506, 2),
+            (24, 0x0708, 2),
+            (26, 0x090A, 2),
+            (28, 0x1112_1314, 4),
+            (32, 0x2122_2324, 4),
+            (36, 0x3132_3334, 4),
+            (40, 0x0B0C, 2),
+            (42, 0x0D0E, 2),
+            (44, 0x4142_4344, 4),
+            (48, 0x0F10, 2),
+            (50, 0x1718, 2),
+            (67, 0x5152_5354, 4),
+        ];
+        for (offset, value, len) in fields {
+            data[offset..offset + len].copy_from_slice(&value.to_le_bytes()[..len]);
+        }
+
+        let bs = unsafe { BootSector::from_bytes(&data[..]) };
+        let read = [
+            bs.bytes_per_sector() as u32,
+            bs.reserved_sector_count() as u32,
+            bs.root_entry_count() as u32,
+            bs.total_sectors_16() as u32,
+            bs.fat_size_16() as u32,
+            bs.sectors_per_track() as u32,
+            bs.num_heads() as u32,
+            bs.hidden_sectors(),
+            bs.total_sectors_32(),
+            bs.fat_size_32(),
+            bs.ext_flags() as u32,
+            bs.fs_version() as u32,
+            bs.root_cluster(),
+            bs.fs_info() as u32,
+            bs.backup_boot_sector() as u32,
+            bs.volume_id(),
+        ];
+        for ((offset, value, _), read) in fields.iter().zip(read) {
+            assert_eq!(read, *value, "champ à l'offset {}", offset);
+        }
+        assert_eq!(&bs.to_bytes()[..90], &data[..90]);
+    }
+
+    #[test]
+    fn test_num_clusters() {
+        // 69632 secteurs, 32 réservés, 2 FATs de 536 secteurs
+        let mut device = crate::MemoryDevice::new(69_632);
+        let bs = BootSector::format(&mut device, 69_632, 1).unwrap();
+        assert_eq!(bs.fat_size(), 536);
+        assert_eq!(bs.num_clusters(), 69_632 - 32 - 2 * 536);
+
+        // Trop peu de clusters pour FAT32
+        let result = BootSector::format(&mut device, 69_632, 2);
+        assert_eq!(result.err(), Some(Fat32Error::InvalidBootSector));
+    }
+
+    #[test]
+    fn test_fat_type_from_cluster_count() {
+        let mut device = crate::MemoryDevice::new(69_632);
+        let mut bs = BootSector::format(&mut device, 69_632, 1).unwrap();
+        // Seuils de la spécification, de part et d'autre
+        let cases = [(4084, 12), (4085, 16), (65524, 16), (65525, 32), (1 << 24, 32)];
+        for (clusters, fat_type) in cases {
+            bs.total_sectors_32 = bs.first_data_sector() + clusters;
+            assert_eq!(bs.num_clusters(), clusters);
+            let types = (bs.is_fat12(), bs.is_fat16(), bs.is_fat32());
+            assert_eq!(types, (fat_type == 12, fat_type == 16, fat_type == 32), "{}", clusters);
+        }
+        assert!(!bs.has_fat16_layout());
+    }
+
+    #[test]
+    fn test_volume_label_str() {
+        let mut device = crate::MemoryDevice::new(69_632);
+        let mut bs = BootSector::format(&mut device, 69_632, 1).unwrap();
+        assert_eq!(bs.volume_label_str(), "NO NAME");
+
+        bs.volume_label = *b"DATA\0\0\0    ";
+        assert_eq!(bs.volume_label_str(), "DATA");
+        bs.volume_label = *b"MY DISK \0  ";
+        assert_eq!(bs.volume_label_str(), "MY DISK");
+        bs.volume_label = [0; 11];
+        assert_eq!(bs.volume_label_str(), "");
+        bs.volume_label = *b"CL\xE9 USB    ";
+        assert_eq!(bs.volume_label_str(), "CL\u{FFFD} USB");
+    }
 }
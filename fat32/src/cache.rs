@@ -0,0 +1,354 @@
+//! Cache de secteurs avec suivi des écritures en attente
+
+use alloc::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+/// Taille maximale d'un secteur (spec FAT)
+pub const MAX_SECTOR_SIZE: usize = 4096;
+
+/// Alignement de `SectorBuf` : les secteurs en cache et sur la pile
+/// conviennent à tout device qui en demande au plus autant
+pub const MAX_ALIGNMENT: usize = 64;
+
+/// Contenu d'un secteur dans un tableau fixe (pas d'allocation par secteur)
+///
+/// Aligné sur `MAX_ALIGNMENT`, pour être passé tel quel à un device DMA.
+#[derive(Clone)]
+#[repr(C, align(64))]
+pub struct SectorBuf {
+    data: [u8; MAX_SECTOR_SIZE],
+    cached_size: usize,
+}
+
+impl SectorBuf {
+    /// Secteur de `size` octets à zéro (borné à `MAX_SECTOR_SIZE`)
+    pub fn new(size: usize) -> Self {
+        Self {
+            data: [0; MAX_SECTOR_SIZE],
+            cached_size: size.min(MAX_SECTOR_SIZE),
+        }
+    }
+
+    /// Copier un secteur existant
+    pub fn from_slice(data: &[u8]) -> Self {
+        let mut buf = Self::new(data.len());
+        let size = buf.cached_size;
+        buf.data[..size].copy_from_slice(&data[..size]);
+        buf
+    }
+}
+
+impl Deref for SectorBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data[..self.cached_size]
+    }
+}
+
+impl DerefMut for SectorBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.data[..self.cached_size]
+    }
+}
+
+/// Tampon sur le tas aligné pour un device (clusters, FAT)
+///
+/// Alloué à zéro avec `alloc::alloc`, à l'alignement demandé par
+/// `ReadBlockDevice::buffer_alignment`. Un tampon de plusieurs secteurs
+/// garde chaque secteur aligné tant que l'alignement divise la taille de
+/// secteur.
+pub struct AlignedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    align: usize,
+}
+
+impl AlignedBuf {
+    /// `len` octets à zéro, à une adresse multiple de `align`
+    ///
+    /// `align` est arrondi à la puissance de deux supérieure (1 au minimum).
+    pub fn zeroed(len: usize, align: usize) -> Self {
+        let align = align.max(1).next_power_of_two();
+        if len == 0 {
+            let ptr = NonNull::new(core::ptr::without_provenance_mut(align)).unwrap();
+            return Self { ptr, len, align };
+        }
+        let layout = Layout::from_size_align(len, align).expect("tampon trop grand");
+        // SAFETY: `layout` n'est pas de taille nulle
+        let ptr = unsafe { alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        Self { ptr, len, align }
+    }
+
+    /// Alignement de l'allocation
+    pub fn align(&self) -> usize {
+        self.align
+    }
+}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `len` octets initialisés (ou aucun) à partir de `ptr`
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: comme `deref`, et `self` est emprunté en exclusivité
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            // SAFETY: alloué dans `zeroed` avec ce layout
+            unsafe {
+                dealloc(self.ptr.as_ptr(), Layout::from_size_align_unchecked(self.len, self.align))
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AlignedBuf").field("len", &self.len).field("align", &self.align).finish()
+    }
+}
+
+// SAFETY: le tampon possède son allocation, comme un `Vec<u8>`
+unsafe impl Send for AlignedBuf {}
+unsafe impl Sync for AlignedBuf {}
+
+/// Ligne du cache : un secteur complet
+struct CacheLine {
+    sector: u32,
+    data: SectorBuf,
+    dirty: bool,
+    last_used: u64,
+}
+
+/// Cache LRU de secteurs
+///
+/// Les lignes modifiées (dirty) ne sont jamais jetées silencieusement :
+/// `insert` rend la ligne évincée si elle était modifiée, à charge pour
+/// l'appelant de l'écrire.
+pub struct SectorCache {
+    lines: Vec<CacheLine>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl SectorCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Vec::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    /// Nombre maximum de secteurs gardés
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changer la capacité ; les lignes propres les plus anciennes en trop sont jetées
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.lines.len() > self.capacity {
+            let lru = self
+                .lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| !line.dirty)
+                .min_by_key(|(_, line)| line.last_used)
+                .map(|(i, _)| i);
+            match lru {
+                Some(index) => {
+                    self.lines.swap_remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Nombre de secteurs gardés
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Le cache est-il vide ?
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Le secteur est-il présent ?
+    pub fn contains(&self, sector: u32) -> bool {
+        self.lines.iter().any(|line| line.sector == sector)
+    }
+
+    /// Lire un secteur du cache
+    pub fn get(&mut self, sector: u32) -> Option<&[u8]> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.lines.iter_mut().find(|line| line.sector == sector).map(|line| {
+            line.last_used = clock;
+            &line.data[..]
+        })
+    }
+
+    /// Modifier un secteur du cache (il devient dirty)
+    pub fn get_mut(&mut self, sector: u32) -> Option<&mut [u8]> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.lines.iter_mut().find(|line| line.sector == sector).map(|line| {
+            line.last_used = clock;
+            line.dirty = true;
+            &mut line.data[..]
+        })
+    }
+
+    /// Ajouter un secteur, retourne la ligne évincée si elle était modifiée
+    pub fn insert(&mut self, sector: u32, data: SectorBuf, dirty: bool) -> Option<(u32, SectorBuf)> {
+        self.clock += 1;
+        let line = CacheLine {
+            sector,
+            data,
+            dirty,
+            last_used: self.clock,
+        };
+
+        if let Some(existing) = self.lines.iter_mut().find(|l| l.sector == sector) {
+            let was_dirty = existing.dirty;
+            *existing = line;
+            existing.dirty |= was_dirty;
+            return None;
+        }
+
+        let mut evicted = None;
+        if self.lines.len() >= self.capacity {
+            let lru = self.lru_index().unwrap_or(0);
+            let old = self.lines.swap_remove(lru);
+            if old.dirty {
+                evicted = Some((old.sector, old.data));
+            }
+        }
+
+        self.lines.push(line);
+        evicted
+    }
+
+    /// Ligne que `insert` évincerait pour ajouter `sector` : (secteur, modifiée)
+    ///
+    /// `None` si `sector` est déjà présent ou s'il reste de la place. Permet
+    /// d'écrire une ligne modifiée avant de la laisser sortir du cache.
+    pub fn victim(&self, sector: u32) -> Option<(u32, bool)> {
+        if self.contains(sector) || self.lines.len() < self.capacity {
+            return None;
+        }
+        self.lru_index().map(|i| (self.lines[i].sector, self.lines[i].dirty))
+    }
+
+    /// Index de la ligne la moins récemment utilisée
+    fn lru_index(&self) -> Option<usize> {
+        self.lines
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, l)| l.last_used)
+            .map(|(i, _)| i)
+    }
+
+    /// Y a-t-il des secteurs à écrire ?
+    pub fn is_dirty(&self) -> bool {
+        self.lines.iter().any(|line| line.dirty)
+    }
+
+    /// Secteurs modifiés, triés par numéro
+    pub fn dirty_sectors(&self) -> Vec<u32> {
+        let mut sectors: Vec<u32> = self
+            .lines
+            .iter()
+            .filter(|line| line.dirty)
+            .map(|line| line.sector)
+            .collect();
+        sectors.sort_unstable();
+        sectors
+    }
+
+    /// Lire un secteur sans toucher à l'ordre LRU
+    pub fn peek(&self, sector: u32) -> Option<&[u8]> {
+        self.lines
+            .iter()
+            .find(|line| line.sector == sector)
+            .map(|line| &line.data[..])
+    }
+
+    /// Marquer un secteur comme écrit sur le disque
+    pub fn mark_clean(&mut self, sector: u32) {
+        if let Some(line) = self.lines.iter_mut().find(|line| line.sector == sector) {
+            line.dirty = false;
+        }
+    }
+
+    /// Retirer un secteur (même modifié)
+    pub fn remove(&mut self, sector: u32) {
+        self.lines.retain(|line| line.sector != sector);
+    }
+
+    /// Tout oublier, y compris les secteurs modifiés
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Oublier les secteurs non modifiés
+    pub fn clear_clean(&mut self) {
+        self.lines.retain(|line| line.dirty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction_returns_dirty_line() {
+        let mut cache = SectorCache::new(2);
+        assert!(cache.insert(1, SectorBuf::from_slice(&[1; 4]), false).is_none());
+        assert!(cache.insert(2, SectorBuf::from_slice(&[2; 4]), false).is_none());
+
+        // Le secteur 1 devient le plus récent et dirty
+        cache.get_mut(1).unwrap()[0] = 9;
+        // Le 2 est évincé : il était propre
+        assert!(cache.insert(3, SectorBuf::from_slice(&[3; 4]), false).is_none());
+        assert!(!cache.contains(2));
+
+        // Le 1 est évincé et rendu car modifié
+        cache.get(3);
+        let (sector, data) = cache.insert(4, SectorBuf::from_slice(&[4; 4]), false).unwrap();
+        assert_eq!(sector, 1);
+        assert_eq!(&data[..], &[9, 1, 1, 1]);
+        assert!(!cache.is_dirty());
+    }
+
+    #[test]
+    fn test_aligned_buffers() {
+        for align in [1, 64, 4096] {
+            let mut buf = AlignedBuf::zeroed(3 * 512, align);
+            assert!((buf.as_ptr() as usize).is_multiple_of(align));
+            assert!(buf.iter().all(|&b| b == 0));
+            buf[1535] = 1;
+        }
+        assert!((AlignedBuf::zeroed(0, 64).as_ptr() as usize).is_multiple_of(64));
+        assert_eq!(AlignedBuf::zeroed(8, 48).align(), 64);
+
+        let bufs = [SectorBuf::new(512), SectorBuf::from_slice(&[1; 512])];
+        assert!(bufs.iter().all(|b| (b.as_ptr() as usize).is_multiple_of(MAX_ALIGNMENT)));
+    }
+}
@@ -0,0 +1,276 @@
+//! Devices fournis avec la bibliothèque
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use crate::cache::AlignedBuf;
+use crate::{BlockDevice, Fat32Error, IoOp, ReadBlockDevice, Result};
+
+mod cached;
+#[cfg(feature = "test-util")]
+mod faulty;
+#[cfg(feature = "std")]
+mod file;
+mod guard;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "embedded-storage")]
+mod storage;
+mod retry;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(any(test, feature = "test-util"))]
+mod trace;
+mod translator;
+
+pub use cached::{CacheStats, CachedDevice};
+#[cfg(feature = "test-util")]
+pub use faulty::{Fault, FaultyDevice, LogEntry, Operation, Outcome};
+#[cfg(feature = "std")]
+pub use file::FileDevice;
+pub use guard::{GuardMode, WriteGuard};
+#[cfg(feature = "mmap")]
+pub use mmap::MmapDevice;
+#[cfg(feature = "embedded-storage")]
+pub use storage::StorageDevice;
+pub use retry::{Backoff, RetryDevice};
+#[cfg(feature = "std")]
+pub use stream::IoDevice;
+#[cfg(any(test, feature = "test-util"))]
+pub use trace::{TraceDevice, TraceEntry, TraceOp, TraceSummary};
+pub use translator::SectorTranslator;
+
+/// `IoError` si les secteurs `first..first + count` dépassent le device
+///
+/// `op` est l'accès prévu, repris dans l'erreur. Voir
+/// `ReadBlockDevice::num_sectors`.
+pub(crate) fn check_bounds<D: ReadBlockDevice + ?Sized>(
+    device: &D,
+    op: IoOp,
+    first: u32,
+    count: u32,
+) -> Result<(), D::Error> {
+    if first as u64 + count as u64 > device.num_sectors() {
+        return Err(Fat32Error::IoError { op, sector: first });
+    }
+    Ok(())
+}
+
+/// L'adresse de `buffer` respecte-t-elle `ReadBlockDevice::buffer_alignment` ?
+fn aligned<D: ReadBlockDevice + ?Sized>(device: &D, buffer: &[u8]) -> bool {
+    (buffer.as_ptr() as usize).is_multiple_of(device.buffer_alignment().max(1))
+}
+
+/// `read_sector` avec un tampon quelconque (copie alignée s'il le faut)
+pub(crate) fn read_sector_aligned<D: ReadBlockDevice + ?Sized>(
+    device: &mut D,
+    sector: u32,
+    buffer: &mut [u8],
+) -> Result<(), D::Error> {
+    if aligned(device, buffer) {
+        return device.read_sector(sector, buffer);
+    }
+    let mut bounce = AlignedBuf::zeroed(buffer.len(), device.buffer_alignment());
+    device.read_sector(sector, &mut bounce)?;
+    buffer.copy_from_slice(&bounce);
+    Ok(())
+}
+
+/// `read_sectors` avec un tampon quelconque (copie alignée s'il le faut)
+pub(crate) fn read_sectors_aligned<D: ReadBlockDevice + ?Sized>(
+    device: &mut D,
+    first: u32,
+    count: u32,
+    buffer: &mut [u8],
+) -> Result<(), D::Error> {
+    if aligned(device, buffer) {
+        return device.read_sectors(first, count, buffer);
+    }
+    let mut bounce = AlignedBuf::zeroed(buffer.len(), device.buffer_alignment());
+    device.read_sectors(first, count, &mut bounce)?;
+    buffer.copy_from_slice(&bounce);
+    Ok(())
+}
+
+/// `write_sector` avec un tampon quelconque (copie alignée s'il le faut)
+pub(crate) fn write_sector_aligned<D: BlockDevice + ?Sized>(
+    device: &mut D,
+    sector: u32,
+    buffer: &[u8],
+) -> Result<(), D::Error> {
+    if aligned(device, buffer) {
+        return device.write_sector(sector, buffer);
+    }
+    let mut bounce = AlignedBuf::zeroed(buffer.len(), device.buffer_alignment());
+    bounce.copy_from_slice(buffer);
+    device.write_sector(sector, &bounce)
+}
+
+/// `write_sectors` avec un tampon quelconque (copie alignée s'il le faut)
+pub(crate) fn write_sectors_aligned<D: BlockDevice + ?Sized>(
+    device: &mut D,
+    first: u32,
+    count: u32,
+    buffer: &[u8],
+) -> Result<(), D::Error> {
+    if aligned(device, buffer) {
+        return device.write_sectors(first, count, buffer);
+    }
+    let mut bounce = AlignedBuf::zeroed(buffer.len(), device.buffer_alignment());
+    bounce.copy_from_slice(buffer);
+    device.write_sectors(first, count, &bounce)
+}
+
+/// Taille de secteur par défaut
+const DEFAULT_SECTOR_SIZE: usize = 512;
+
+/// Device en mémoire (disque RAM)
+///
+/// Un accès qui dépasse la fin des données donne `IoError` au lieu de
+/// paniquer. En lecture seule, les écritures donnent `ReadOnly`. Sert aux
+/// tests, et en `no_std` à monter une image gardée en flash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryDevice {
+    data: Vec<u8>,
+    sector_size: usize,
+    read_only: bool,
+}
+
+impl MemoryDevice {
+    /// Disque de `sectors` secteurs de 512 octets à zéro
+    pub fn new(sectors: u32) -> Self {
+        Self::with_sector_size(sectors, DEFAULT_SECTOR_SIZE)
+    }
+
+    /// Disque de `sectors` secteurs de `sector_size` octets à zéro
+    pub fn with_sector_size(sectors: u32, sector_size: usize) -> Self {
+        Self::from_vec_with_sector_size(vec![0; sectors as usize * sector_size], sector_size)
+    }
+
+    /// Image existante, secteurs de 512 octets
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        Self::from_vec_with_sector_size(data, DEFAULT_SECTOR_SIZE)
+    }
+
+    /// Image existante, secteurs de `sector_size` octets (au moins 1)
+    pub fn from_vec_with_sector_size(data: Vec<u8>, sector_size: usize) -> Self {
+        Self {
+            data,
+            sector_size: sector_size.max(1),
+            read_only: false,
+        }
+    }
+
+    /// Refuser (ou de nouveau accepter) les écritures
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Les écritures sont-elles refusées ?
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Nombre de secteurs complets
+    pub fn sector_count(&self) -> u32 {
+        (self.data.len() / self.sector_size) as u32
+    }
+
+    /// Contenu du disque
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Contenu du disque, modifiable (même en lecture seule)
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Récupérer l'image
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Zone de `len` octets à partir du secteur `sector`, si elle est dans le disque
+    fn range(&self, op: IoOp, sector: u32, len: usize) -> Result<core::ops::Range<usize>> {
+        let start = (sector as usize).checked_mul(self.sector_size);
+        match start.and_then(|start| Some(start..start.checked_add(len)?)) {
+            Some(range) if range.end <= self.data.len() => Ok(range),
+            _ => Err(Fat32Error::IoError { op, sector }),
+        }
+    }
+}
+
+impl ReadBlockDevice for MemoryDevice {
+    type Error = Infallible;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
+        let range = self.range(IoOp::Read, sector, buffer.len())?;
+        buffer.copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.sector_count() as u64
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<()> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.read_sector(first, buffer)
+    }
+}
+
+impl BlockDevice for MemoryDevice {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
+        if self.read_only {
+            return Err(Fat32Error::ReadOnly);
+        }
+        let range = self.range(IoOp::Write, sector, buffer.len())?;
+        self.data[range].copy_from_slice(buffer);
+        Ok(())
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<()> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.write_sector(first, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_device_bounds_and_read_only() {
+        let mut device = MemoryDevice::with_sector_size(4, 1024);
+        assert_eq!(device.sector_count(), 4);
+
+        device.write_sector(3, &[7; 1024]).unwrap();
+        let mut buffer = [0u8; 1024];
+        device.read_sector(3, &mut buffer).unwrap();
+        assert_eq!(buffer, [7; 1024]);
+        assert_eq!(device.data()[3 * 1024], 7);
+
+        // Au-delà de la fin : erreur, pas de panique
+        let read_error = |sector| Err(Fat32Error::IoError { op: IoOp::Read, sector });
+        assert_eq!(device.read_sector(4, &mut buffer), read_error(4));
+        assert_eq!(device.read_sector(u32::MAX, &mut buffer), read_error(u32::MAX));
+        let mut two = [0u8; 2048];
+        assert_eq!(device.read_sectors(3, 2, &mut two), read_error(3));
+        assert_eq!(device.read_sectors(2, 1, &mut two), Err(Fat32Error::BufferTooSmall));
+
+        device.set_read_only(true);
+        assert_eq!(device.write_sector(0, &[1; 1024]), Err(Fat32Error::ReadOnly));
+        assert_eq!(device.data()[0], 0);
+        device.read_sectors(2, 2, &mut two).unwrap();
+        assert_eq!(device.into_inner().len(), 4096);
+    }
+}
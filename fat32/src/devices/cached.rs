@@ -0,0 +1,200 @@
+//! Cache de secteurs générique devant un device
+
+use crate::cache::{SectorBuf, SectorCache, MAX_SECTOR_SIZE};
+use crate::{BlockDevice, Fat32Error, ReadBlockDevice, Result};
+
+/// Compteurs d'un `CachedDevice`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lectures servies par le cache
+    pub hits: u64,
+    /// Lectures qui ont dû aller au device
+    pub misses: u64,
+    /// Secteurs sortis du cache pour faire de la place
+    pub evictions: u64,
+    /// Secteurs modifiés écrits sur le device (éviction ou flush)
+    pub write_backs: u64,
+}
+
+/// Device avec un cache LRU de secteurs en écriture différée
+///
+/// Les écritures d'un secteur restent en cache jusqu'à son éviction ou à
+/// `flush()`, qui écrit les secteurs modifiés par numéro croissant puis
+/// appelle le `flush` du device. Une lecture voit toujours les écritures en
+/// attente.
+///
+/// Les transferts groupés (`read_sectors`, `write_sectors`) et les tampons qui
+/// ne font pas exactement un secteur passent à côté du cache : une lecture
+/// est complétée par les secteurs en cache, une écriture va directement au
+/// device et met à jour les copies en cache. Si le device a des secteurs de
+/// plus de 4 KiB, rien n'est mis en cache.
+///
+/// Les écritures en attente sont perdues si le device est détruit sans
+/// `flush()` ; `Fat32FileSystem` flushe à son démontage.
+pub struct CachedDevice<D: BlockDevice> {
+    device: D,
+    cache: SectorCache,
+    stats: CacheStats,
+}
+
+impl<D: BlockDevice> CachedDevice<D> {
+    /// Cache de `capacity` secteurs (au moins 1) devant `device`
+    pub fn new(device: D, capacity: usize) -> Self {
+        Self {
+            device,
+            cache: SectorCache::new(capacity),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Compteurs depuis la création ou le dernier `reset_stats`
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Remettre les compteurs à zéro
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Y a-t-il des écritures en attente ?
+    pub fn is_dirty(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    /// Device sous-jacent (sans les écritures en attente)
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Écrire les secteurs en attente et récupérer le device
+    pub fn into_inner(mut self) -> Result<D, D::Error> {
+        self.flush()?;
+        Ok(self.device)
+    }
+
+    /// Le tampon fait-il exactement un secteur qu'on peut mettre en cache ?
+    fn cacheable(&self, len: usize) -> bool {
+        let size = self.device.sector_size();
+        len == size && size <= MAX_SECTOR_SIZE
+    }
+
+    /// Ajouter un secteur au cache, en écrivant la ligne évincée si besoin
+    ///
+    /// La ligne évincée modifiée est écrite avant de quitter le cache : si
+    /// l'écriture échoue, elle y reste (toujours à écrire) et `sector` n'est
+    /// pas ajouté.
+    fn insert(&mut self, sector: u32, data: SectorBuf, dirty: bool) -> Result<(), D::Error> {
+        if let Some((victim, victim_dirty)) = self.cache.victim(sector) {
+            if victim_dirty {
+                if let Some(pending) = self.cache.peek(victim) {
+                    self.device.write_sector(victim, pending)?;
+                }
+                self.cache.mark_clean(victim);
+                self.stats.write_backs += 1;
+            }
+            self.stats.evictions += 1;
+        }
+        self.cache.insert(sector, data, dirty);
+        Ok(())
+    }
+
+    /// Recouvrir `buffer` (à partir de `first`) par les secteurs en cache
+    fn overlay(&self, first: u32, buffer: &mut [u8]) {
+        let size = self.device.sector_size().max(1);
+        for (i, chunk) in buffer.chunks_mut(size).enumerate() {
+            if let Some(cached) = self.cache.peek(first + i as u32) {
+                let len = chunk.len().min(cached.len());
+                chunk[..len].copy_from_slice(&cached[..len]);
+            }
+        }
+    }
+
+    /// Mettre à jour les copies en cache après une écriture directe
+    fn refresh(&mut self, first: u32, buffer: &[u8]) {
+        let size = self.device.sector_size().max(1);
+        for (i, chunk) in buffer.chunks(size).enumerate() {
+            let sector = first + i as u32;
+            if let Some(cached) = self.cache.peek(sector) {
+                // Un secteur écrit en partie garde le reste de la copie (et son état)
+                let mut data = SectorBuf::from_slice(cached);
+                data[..chunk.len()].copy_from_slice(chunk);
+                self.cache.insert(sector, data, false);
+                if chunk.len() == size {
+                    self.cache.mark_clean(sector);
+                }
+            }
+        }
+    }
+}
+
+impl<D: BlockDevice> ReadBlockDevice for CachedDevice<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        if !self.cacheable(buffer.len()) {
+            self.device.read_sector(sector, buffer)?;
+            self.overlay(sector, buffer);
+            return Ok(());
+        }
+
+        if let Some(cached) = self.cache.get(sector) {
+            buffer.copy_from_slice(cached);
+            self.stats.hits += 1;
+            return Ok(());
+        }
+
+        self.stats.misses += 1;
+        self.device.read_sector(sector, buffer)?;
+        self.insert(sector, SectorBuf::from_slice(buffer), false)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.device.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.device.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.device.read_sectors(first, count, buffer)?;
+        self.overlay(first, buffer);
+        Ok(())
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for CachedDevice<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        if !self.cacheable(buffer.len()) {
+            self.device.write_sector(sector, buffer)?;
+            self.refresh(sector, buffer);
+            return Ok(());
+        }
+        self.insert(sector, SectorBuf::from_slice(buffer), true)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        if buffer.len() != count as usize * self.device.sector_size() {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.device.write_sectors(first, count, buffer)?;
+        self.refresh(first, buffer);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        for sector in self.cache.dirty_sectors() {
+            if let Some(data) = self.cache.peek(sector) {
+                self.device.write_sector(sector, data)?;
+            }
+            self.cache.mark_clean(sector);
+            self.stats.write_backs += 1;
+        }
+        self.device.flush()
+    }
+}
@@ -0,0 +1,258 @@
+//! Injection de pannes pour les tests (feature `test-util`)
+
+use alloc::vec::Vec;
+
+use crate::cache::AlignedBuf;
+use crate::{BlockDevice, Fat32Error, IoOp, ReadBlockDevice, Result};
+
+/// Panne programmée d'un `FaultyDevice`
+///
+/// Les accès sont comptés secteur par secteur à partir de 0, transferts
+/// groupés compris.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// La lecture numéro `n` échoue
+    FailRead(usize),
+    /// L'écriture numéro `n` échoue sans rien écrire
+    FailWrite(usize),
+    /// L'écriture numéro `n` n'écrit que la première moitié du secteur, puis échoue
+    TearWrite(usize),
+}
+
+/// Accès vu par un `FaultyDevice`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Read(u32),
+    Write(u32),
+    Flush,
+}
+
+/// Ce qu'est devenu un accès
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    Failed,
+    /// Écriture faite à moitié
+    Torn,
+}
+
+/// Entrée du journal d'un `FaultyDevice`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub operation: Operation,
+    pub outcome: Outcome,
+}
+
+/// Device qui tombe en panne quand on le lui demande
+///
+/// Une panne programmée avec `inject` se déclenche une fois, avec l'erreur
+/// `IoError` (avec l'accès et le secteur). Avec `fail_after_trigger(true)`,
+/// tous les accès suivants échouent aussi, comme après une coupure de
+/// courant : le device sous-jacent garde alors l'état exact du moment de
+/// la panne, à remonter pour vérifier la cohérence du volume. Chaque accès
+/// est noté dans `log()`.
+pub struct FaultyDevice<D: BlockDevice> {
+    device: D,
+    fault: Option<Fault>,
+    sticky: bool,
+    triggered: bool,
+    reads: usize,
+    writes: usize,
+    log: Vec<LogEntry>,
+}
+
+impl<D: BlockDevice> FaultyDevice<D> {
+    /// Envelopper `device`, sans panne programmée
+    pub fn new(device: D) -> Self {
+        Self {
+            device,
+            fault: None,
+            sticky: false,
+            triggered: false,
+            reads: 0,
+            writes: 0,
+            log: Vec::new(),
+        }
+    }
+
+    /// Programmer une panne (remplace la précédente si elle n'a pas eu lieu)
+    pub fn inject(&mut self, fault: Fault) {
+        self.fault = Some(fault);
+    }
+
+    /// Faire échouer tous les accès après le déclenchement d'une panne
+    pub fn fail_after_trigger(&mut self, sticky: bool) {
+        self.sticky = sticky;
+    }
+
+    /// Une panne s'est-elle déclenchée ?
+    pub fn is_triggered(&self) -> bool {
+        self.triggered
+    }
+
+    /// Nombre de secteurs lus (ou tentés) depuis la création
+    pub fn reads(&self) -> usize {
+        self.reads
+    }
+
+    /// Nombre de secteurs écrits (ou tentés) depuis la création
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Tous les accès, dans l'ordre
+    pub fn log(&self) -> &[LogEntry] {
+        &self.log
+    }
+
+    /// Device sous-jacent
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Récupérer le device, dans l'état laissé par la panne
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Noter un accès dans le journal
+    fn note<T>(
+        &mut self,
+        operation: Operation,
+        outcome: Outcome,
+        result: Result<T, D::Error>,
+    ) -> Result<T, D::Error> {
+        self.log.push(LogEntry { operation, outcome });
+        result
+    }
+
+    /// La panne programmée concerne-t-elle cet accès ? (elle est alors consommée)
+    fn fires(&mut self, fault: Fault) -> bool {
+        if self.fault == Some(fault) {
+            self.fault = None;
+            self.triggered = true;
+            return true;
+        }
+        false
+    }
+
+    /// Tout échoue-t-il depuis la panne ?
+    fn dead(&self) -> bool {
+        self.sticky && self.triggered
+    }
+}
+
+impl<D: BlockDevice> ReadBlockDevice for FaultyDevice<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        let index = self.reads;
+        self.reads += 1;
+        let operation = Operation::Read(sector);
+        if self.dead() || self.fires(Fault::FailRead(index)) {
+            let error = Fat32Error::IoError { op: IoOp::Read, sector };
+            return self.note(operation, Outcome::Failed, Err(error));
+        }
+        let result = self.device.read_sector(sector, buffer);
+        let outcome = if result.is_ok() { Outcome::Done } else { Outcome::Failed };
+        self.note(operation, outcome, result)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.device.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.device.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for FaultyDevice<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        let index = self.writes;
+        self.writes += 1;
+        let operation = Operation::Write(sector);
+        let error = Fat32Error::IoError { op: IoOp::Write, sector };
+        if self.dead() || self.fires(Fault::FailWrite(index)) {
+            return self.note(operation, Outcome::Failed, Err(error));
+        }
+
+        if self.fires(Fault::TearWrite(index)) {
+            // Première moitié du nouveau contenu, seconde moitié de l'ancien
+            let mut torn = AlignedBuf::zeroed(buffer.len(), self.device.buffer_alignment());
+            let result = self.device.read_sector(sector, &mut torn).and_then(|()| {
+                torn[..buffer.len() / 2].copy_from_slice(&buffer[..buffer.len() / 2]);
+                self.device.write_sector(sector, &torn)
+            });
+            let result = result.and(Err(error));
+            return self.note(operation, Outcome::Torn, result);
+        }
+
+        let result = self.device.write_sector(sector, buffer);
+        let outcome = if result.is_ok() { Outcome::Done } else { Outcome::Failed };
+        self.note(operation, outcome, result)
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        if self.dead() {
+            let error = Fat32Error::IoError { op: IoOp::Flush, sector: 0 };
+            return self.note(Operation::Flush, Outcome::Failed, Err(error));
+        }
+        let result = self.device.flush();
+        let outcome = if result.is_ok() { Outcome::Done } else { Outcome::Failed };
+        self.note(Operation::Flush, outcome, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryDevice;
+
+    #[test]
+    fn test_faulty_device() {
+        let mut device = FaultyDevice::new(MemoryDevice::new(4));
+        device.write_sector(0, &[1; 512]).unwrap();
+
+        // Écriture déchirée : la moitié seulement, puis erreur
+        device.inject(Fault::TearWrite(1));
+        let io = |op, sector| Err(Fat32Error::IoError { op, sector });
+        assert_eq!(device.write_sector(0, &[2; 512]), io(IoOp::Write, 0));
+        assert_eq!(device.inner().data()[..256], [2; 256]);
+        assert_eq!(device.inner().data()[256..512], [1; 256]);
+
+        // Panne unique : l'accès suivant fonctionne
+        device.inject(Fault::FailRead(0));
+        let mut buffer = [0u8; 512];
+        assert_eq!(device.read_sector(1, &mut buffer), io(IoOp::Read, 1));
+        device.read_sector(1, &mut buffer).unwrap();
+
+        // Coupure : plus rien ne passe
+        device.fail_after_trigger(true);
+        device.inject(Fault::FailWrite(2));
+        assert_eq!(device.write_sector(3, &[3; 512]), io(IoOp::Write, 3));
+        assert_eq!(device.read_sector(1, &mut buffer), io(IoOp::Read, 1));
+        assert_eq!(device.flush(), io(IoOp::Flush, 0));
+        assert!(device.is_triggered());
+        assert_eq!((device.reads(), device.writes()), (3, 3));
+
+        let entry = |operation, outcome| LogEntry { operation, outcome };
+        assert_eq!(
+            device.log(),
+            [
+                entry(Operation::Write(0), Outcome::Done),
+                entry(Operation::Write(0), Outcome::Torn),
+                entry(Operation::Read(1), Outcome::Failed),
+                entry(Operation::Read(1), Outcome::Done),
+                entry(Operation::Write(3), Outcome::Failed),
+                entry(Operation::Read(1), Outcome::Failed),
+                entry(Operation::Flush, Outcome::Failed),
+            ]
+        );
+        assert_eq!(device.into_inner().data()[3 * 512], 0);
+    }
+}
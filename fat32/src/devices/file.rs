@@ -0,0 +1,210 @@
+//! Image disque dans un fichier de l'hôte (feature `std`)
+
+use alloc::format;
+use alloc::string::String;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use super::IoDevice;
+use crate::{BlockDevice, Fat32Error, IoErrorKind, ReadBlockDevice, Result};
+
+/// Secteurs d'une FAT de 65525 clusters : une entrée de 4 octets par
+/// cluster, plus les deux entrées réservées (262 108 octets, 512 secteurs)
+const MIN_FAT_SECTORS: u64 = ((65_525 + 2) * 4u64).div_ceil(512);
+
+/// Plus petit volume FAT32 en secteurs de 512 octets, tel que le formate
+/// `BootSector::format` : 32 secteurs réservés, deux FATs de
+/// `MIN_FAT_SECTORS` secteurs et 65525 clusters d'un secteur
+const MIN_IMAGE_SECTORS: u64 = 32 + 2 * MIN_FAT_SECTORS + 65_525;
+
+/// Device basé sur un fichier image
+///
+/// La taille du fichier est fixée à l'ouverture : un accès au-delà donne
+/// `IoError` au lieu d'agrandir le fichier. Les erreurs du fichier lui-même
+/// remontent en `Fat32Error::Device` avec leur `IoErrorKind`. C'est un
+/// `IoDevice<File>` dont `flush` attend l'écriture sur le disque.
+///
+/// Une image ouverte en lecture seule (`is_read_only`) refuse les écritures
+/// avec `ReadOnly`.
+#[derive(Debug)]
+pub struct FileDevice {
+    io: IoDevice<File>,
+    read_only: bool,
+}
+
+impl FileDevice {
+    /// Ouvrir une image existante, secteurs de 512 octets
+    ///
+    /// En lecture/écriture si possible ; si le fichier ou son système de
+    /// fichiers ne le permet pas, en lecture seule (voir `is_read_only`).
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::open_with_sector_size(path, 512)
+    }
+
+    /// Ouvrir une image existante avec des secteurs de `sector_size` octets
+    ///
+    /// Comme `open`. Échoue (`InvalidData`) si la taille du fichier n'est pas
+    /// un multiple de la taille de secteur.
+    pub fn open_with_sector_size<P: AsRef<Path>>(path: P, sector_size: usize) -> io::Result<Self> {
+        let path = path.as_ref();
+        match File::options().read(true).write(true).open(path) {
+            Ok(file) => Self::from_file(file, sector_size),
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+                ) =>
+            {
+                Self::open_read_only_with_sector_size(path, sector_size)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Ouvrir une image existante en lecture seule, secteurs de 512 octets
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::open_read_only_with_sector_size(path, 512)
+    }
+
+    /// Ouvrir une image existante en lecture seule avec des secteurs de `sector_size` octets
+    pub fn open_read_only_with_sector_size<P: AsRef<Path>>(
+        path: P,
+        sector_size: usize,
+    ) -> io::Result<Self> {
+        let mut device = Self::from_file(File::open(path)?, sector_size)?;
+        device.read_only = true;
+        Ok(device)
+    }
+
+    /// Créer une image creuse de `size_bytes` octets, prête pour `format`
+    ///
+    /// Échoue (`AlreadyExists`) si le fichier existe déjà, et
+    /// (`InvalidInput`) si la taille n'est pas un multiple de 512 ou est
+    /// trop petite pour un volume FAT32 (environ 33 Mo). Le fichier est
+    /// creux quand le système de l'hôte le permet.
+    pub fn create<P: AsRef<Path>>(path: P, size_bytes: u64) -> io::Result<Self> {
+        Self::create_image(path, size_bytes, false)
+    }
+
+    /// Comme `create`, en écrasant le fichier s'il existe déjà
+    pub fn create_overwrite<P: AsRef<Path>>(path: P, size_bytes: u64) -> io::Result<Self> {
+        Self::create_image(path, size_bytes, true)
+    }
+
+    fn create_image<P: AsRef<Path>>(path: P, size_bytes: u64, overwrite: bool) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+        if !size_bytes.is_multiple_of(512) {
+            return Err(invalid(format!("taille ({} octets) non multiple de 512", size_bytes)));
+        }
+        if size_bytes / 512 < MIN_IMAGE_SECTORS {
+            return Err(invalid(format!(
+                "taille ({} octets) inférieure au minimum FAT32 ({} octets)",
+                size_bytes,
+                MIN_IMAGE_SECTORS * 512
+            )));
+        }
+        if size_bytes / 512 > u32::MAX as u64 {
+            return Err(invalid(format!("taille ({} octets) trop grande", size_bytes)));
+        }
+
+        let mut options = File::options();
+        options.read(true).write(true);
+        if overwrite {
+            options.create(true).truncate(true);
+        } else {
+            options.create_new(true);
+        }
+        let file = options.open(path)?;
+        file.set_len(size_bytes)?;
+        Self::from_file(file, 512)
+    }
+
+    fn from_file(file: File, sector_size: usize) -> io::Result<Self> {
+        if sector_size == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "taille de secteur nulle"));
+        }
+        let len = file.metadata()?.len();
+        if len % sector_size as u64 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "taille de l'image ({} octets) non multiple de la taille de secteur ({})",
+                    len, sector_size
+                ),
+            ));
+        }
+        Ok(Self {
+            io: IoDevice::with_sector_size(file, sector_size)?,
+            read_only: false,
+        })
+    }
+
+    /// L'image a-t-elle été ouverte en lecture seule ?
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Nombre de secteurs de l'image
+    pub fn sector_count(&self) -> u32 {
+        self.io.sector_count()
+    }
+
+    /// Récupérer le fichier
+    pub fn into_file(self) -> File {
+        self.io.into_inner()
+    }
+}
+
+impl ReadBlockDevice for FileDevice {
+    type Error = IoErrorKind;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), IoErrorKind> {
+        self.io.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.io.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.io.num_sectors()
+    }
+
+    fn read_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), IoErrorKind> {
+        self.io.read_sectors(first, count, buffer)
+    }
+}
+
+impl BlockDevice for FileDevice {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), IoErrorKind> {
+        if self.read_only {
+            return Err(Fat32Error::ReadOnly);
+        }
+        self.io.write_sector(sector, buffer)
+    }
+
+    fn write_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &[u8],
+    ) -> Result<(), IoErrorKind> {
+        if self.read_only {
+            return Err(Fat32Error::ReadOnly);
+        }
+        self.io.write_sectors(first, count, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), IoErrorKind> {
+        if self.read_only {
+            return Ok(());
+        }
+        self.io.get_mut().sync_data().map_err(Fat32Error::from)
+    }
+}
@@ -0,0 +1,144 @@
+//! Device qui refuse ou compte les écritures
+
+use alloc::vec::Vec;
+
+use crate::{BlockDevice, Fat32Error, ReadBlockDevice, Result};
+
+/// Comportement d'un `WriteGuard` face aux écritures
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuardMode {
+    /// Refuser toute écriture (`ReadOnly`)
+    Deny,
+    /// Laisser passer les écritures en les comptant
+    Count,
+}
+
+/// Device qui surveille les écritures du device qu'il enveloppe
+///
+/// Chaque secteur écrit (ou dont l'écriture est refusée) est noté dans
+/// `writes()`, transferts groupés compris. En mode `Deny`, rien n'atteint le
+/// device : pour vérifier qu'une opération n'écrit pas, ou pour doubler le
+/// mode lecture seule du système de fichiers en production. Les lectures,
+/// la taille des secteurs et `flush` sont transmis tels quels.
+pub struct WriteGuard<D: BlockDevice> {
+    device: D,
+    mode: GuardMode,
+    writes: Vec<u32>,
+}
+
+impl<D: BlockDevice> WriteGuard<D> {
+    /// Surveiller `device` dans le mode `mode`
+    pub fn new(device: D, mode: GuardMode) -> Self {
+        Self { device, mode, writes: Vec::new() }
+    }
+
+    /// Mode actuel
+    pub fn mode(&self) -> GuardMode {
+        self.mode
+    }
+
+    /// Changer de mode (les écritures notées sont gardées)
+    pub fn set_mode(&mut self, mode: GuardMode) {
+        self.mode = mode;
+    }
+
+    /// Secteurs écrits (ou refusés), dans l'ordre des appels
+    pub fn writes(&self) -> &[u32] {
+        &self.writes
+    }
+
+    /// Oublier les écritures notées
+    pub fn clear_writes(&mut self) {
+        self.writes.clear();
+    }
+
+    /// Device sous-jacent
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Récupérer le device
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Noter `count` secteurs à partir de `first`, puis décider
+    fn record(&mut self, first: u32, count: u32) -> Result<(), D::Error> {
+        self.writes.extend(first..first.saturating_add(count));
+        match self.mode {
+            GuardMode::Deny => Err(Fat32Error::ReadOnly),
+            GuardMode::Count => Ok(()),
+        }
+    }
+}
+
+impl<D: BlockDevice> ReadBlockDevice for WriteGuard<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.device.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.device.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.device.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.device.read_sectors(first, count, buffer)
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for WriteGuard<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        self.record(sector, 1)?;
+        self.device.write_sector(sector, buffer)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        self.record(first, count)?;
+        self.device.write_sectors(first, count, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        self.device.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryDevice;
+
+    #[test]
+    fn test_write_guard_modes() {
+        let mut device = WriteGuard::new(MemoryDevice::new(8), GuardMode::Count);
+        device.write_sector(3, &[1; 512]).unwrap();
+        device.write_sectors(5, 2, &[2; 1024]).unwrap();
+        assert_eq!(device.writes(), [3, 5, 6]);
+        assert_eq!(device.inner().data()[5 * 512], 2);
+
+        // Refusées mais notées, le device n'est pas touché
+        device.set_mode(GuardMode::Deny);
+        device.clear_writes();
+        assert_eq!(device.write_sector(0, &[9; 512]), Err(Fat32Error::ReadOnly));
+        assert_eq!(device.write_sectors(6, 2, &[9; 1024]), Err(Fat32Error::ReadOnly));
+        assert_eq!(device.writes(), [0, 6, 7]);
+        assert_eq!(device.inner().data()[0], 0);
+        assert_eq!(device.inner().data()[6 * 512], 2);
+
+        let mut buffer = [0u8; 512];
+        device.read_sector(3, &mut buffer).unwrap();
+        assert_eq!(buffer, [1; 512]);
+        assert_eq!(device.sector_size(), 512);
+        assert_eq!(device.num_sectors(), 8);
+        device.flush().unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+//! Image disque projetée en mémoire (feature `mmap`)
+
+use alloc::format;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::MmapMut;
+
+use crate::{BlockDevice, Fat32Error, IoErrorKind, IoOp, ReadBlockDevice, Result};
+
+/// Device basé sur un fichier image projeté en mémoire
+///
+/// Les lectures et écritures sont des copies depuis et vers la projection,
+/// sans appel système par secteur : utile pour parcourir les métadonnées de
+/// nombreuses images. `flush` écrit les pages modifiées sur le disque
+/// (`msync`). Si la taille du fichier n'est pas un multiple de la taille de
+/// secteur, le secteur incomplet de la fin est ignoré.
+///
+/// Le fichier ne doit pas être modifié ni tronqué par un autre processus tant
+/// qu'il est projeté : les données lues changeraient sans prévenir, et un
+/// accès au-delà d'un fichier tronqué termine le processus (`SIGBUS`).
+#[derive(Debug)]
+pub struct MmapDevice {
+    map: MmapMut,
+    sector_size: usize,
+    sectors: u32,
+}
+
+impl MmapDevice {
+    /// Projeter une image existante en lecture/écriture, secteurs de 512 octets
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::open_with_sector_size(path, 512)
+    }
+
+    /// Projeter une image existante avec des secteurs de `sector_size` octets
+    pub fn open_with_sector_size<P: AsRef<Path>>(path: P, sector_size: usize) -> io::Result<Self> {
+        let file = File::options().read(true).write(true).open(path)?;
+        Self::from_file(&file, sector_size)
+    }
+
+    /// Projeter un fichier déjà ouvert en lecture/écriture
+    pub fn from_file(file: &File, sector_size: usize) -> io::Result<Self> {
+        if sector_size == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "taille de secteur nulle"));
+        }
+        // SAFETY : voir la documentation du type, le fichier ne doit pas
+        // changer de taille pendant la projection
+        let map = unsafe { MmapMut::map_mut(file)? };
+        let sectors = u32::try_from(map.len() / sector_size).map_err(|_| {
+            let msg = format!("image trop grande ({} octets)", map.len());
+            io::Error::new(io::ErrorKind::InvalidData, msg)
+        })?;
+        Ok(Self { map, sector_size, sectors })
+    }
+
+    /// Nombre de secteurs complets de l'image
+    pub fn sector_count(&self) -> u32 {
+        self.sectors
+    }
+
+    /// Zone de `len` octets à partir du secteur `sector`, si elle est dans l'image
+    fn range(
+        &self,
+        op: IoOp,
+        sector: u32,
+        len: usize,
+    ) -> Result<core::ops::Range<usize>, IoErrorKind> {
+        let start = sector as u64 * self.sector_size as u64;
+        let end = start + len as u64;
+        if end > self.sectors as u64 * self.sector_size as u64 {
+            return Err(Fat32Error::IoError { op, sector });
+        }
+        Ok(start as usize..end as usize)
+    }
+}
+
+impl ReadBlockDevice for MmapDevice {
+    type Error = IoErrorKind;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), IoErrorKind> {
+        let range = self.range(IoOp::Read, sector, buffer.len())?;
+        buffer.copy_from_slice(&self.map[range]);
+        Ok(())
+    }
+
+    fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.sectors as u64
+    }
+
+    fn read_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), IoErrorKind> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.read_sector(first, buffer)
+    }
+}
+
+impl BlockDevice for MmapDevice {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), IoErrorKind> {
+        let range = self.range(IoOp::Write, sector, buffer.len())?;
+        self.map[range].copy_from_slice(buffer);
+        Ok(())
+    }
+
+    fn write_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &[u8],
+    ) -> Result<(), IoErrorKind> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.write_sector(first, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), IoErrorKind> {
+        self.map.flush().map_err(Fat32Error::from)
+    }
+}
@@ -0,0 +1,182 @@
+//! Nouvelles tentatives sur un device capricieux
+
+use alloc::boxed::Box;
+
+use crate::{BlockDevice, Fat32Error, ReadBlockDevice, Result};
+
+/// Fonction appelée avant chaque nouvelle tentative
+///
+/// Reçoit le device et le numéro de la tentative à venir (2 pour la
+/// première reprise) : attendre, réinitialiser la carte...
+pub type Backoff<D> = Box<dyn FnMut(&mut D, u32) + Send>;
+
+/// Device qui recommence les accès en échec
+///
+/// Chaque accès est tenté jusqu'à `max_attempts` fois. Seules les erreurs
+/// qui peuvent disparaître d'elles-mêmes sont reprises : `IoError` et les
+/// erreurs du device (`Fat32Error::Device`). Les autres (`BufferTooSmall`,
+/// `ReadOnly`...) et les accès réussis sont rendus tout de suite : une
+/// lecture qui réussit n'est jamais recommencée. Après la dernière
+/// tentative, l'erreur de celle-ci remonte.
+///
+/// Les écritures et `flush` sont repris aussi. Une écriture en échec a pu
+/// arriver en partie sur le support avant d'être recommencée : si la
+/// reprise échoue aussi, le contenu des secteurs concernés est indéterminé.
+/// `read_sectors` et `write_sectors` sont repris en entier.
+pub struct RetryDevice<D: BlockDevice> {
+    device: D,
+    max_attempts: u32,
+    backoff: Option<Backoff<D>>,
+    retries: u64,
+}
+
+impl<D: BlockDevice> RetryDevice<D> {
+    /// Jusqu'à `max_attempts` tentatives par accès (au moins 1), sans attente
+    pub fn new(device: D, max_attempts: u32) -> Self {
+        Self {
+            device,
+            max_attempts: max_attempts.max(1),
+            backoff: None,
+            retries: 0,
+        }
+    }
+
+    /// Appeler `backoff` avant chaque nouvelle tentative
+    pub fn with_backoff<F>(mut self, backoff: F) -> Self
+    where
+        F: FnMut(&mut D, u32) + Send + 'static,
+    {
+        self.backoff = Some(Box::new(backoff));
+        self
+    }
+
+    /// Nombre maximal de tentatives par accès
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Nouvelles tentatives faites depuis la création ou `reset_retries`
+    pub fn retries(&self) -> u64 {
+        self.retries
+    }
+
+    /// Remettre le compteur à zéro
+    pub fn reset_retries(&mut self) {
+        self.retries = 0;
+    }
+
+    /// Device sous-jacent
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Accès direct au device (réinitialisation, configuration...)
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Récupérer le device
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Faire `op` jusqu'au succès ou à une erreur définitive
+    fn attempt<T>(
+        &mut self,
+        mut op: impl FnMut(&mut D) -> Result<T, D::Error>,
+    ) -> Result<T, D::Error> {
+        let mut attempt = 1;
+        loop {
+            match op(&mut self.device) {
+                Err(Fat32Error::IoError { .. } | Fat32Error::Device(_))
+                    if attempt < self.max_attempts =>
+                {
+                    attempt += 1;
+                    self.retries += 1;
+                    if let Some(backoff) = self.backoff.as_mut() {
+                        backoff(&mut self.device, attempt);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<D: BlockDevice> ReadBlockDevice for RetryDevice<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.attempt(|device| device.read_sector(sector, buffer))
+    }
+
+    fn sector_size(&self) -> usize {
+        self.device.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.device.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.attempt(|device| device.read_sectors(first, count, buffer))
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for RetryDevice<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        self.attempt(|device| device.write_sector(sector, buffer))
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        self.attempt(|device| device.write_sectors(first, count, buffer))
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        self.attempt(|device| device.flush())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::{Fault, FaultyDevice};
+    use crate::MemoryDevice;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_retry_device() {
+        static BACKOFFS: AtomicU32 = AtomicU32::new(0);
+        let faulty = FaultyDevice::new(MemoryDevice::new(4));
+        let mut device = RetryDevice::new(faulty, 3).with_backoff(|_, attempt| {
+            BACKOFFS.store(attempt, Ordering::Relaxed);
+        });
+
+        // Une panne isolée est absorbée, en lecture comme en écriture
+        device.device.inject(Fault::FailWrite(0));
+        device.write_sector(1, &[5; 512]).unwrap();
+        device.device.inject(Fault::FailRead(0));
+        let mut buffer = [0u8; 512];
+        device.read_sector(1, &mut buffer).unwrap();
+        assert_eq!(buffer, [5; 512]);
+        assert_eq!(device.retries(), 2);
+        assert_eq!(BACKOFFS.load(Ordering::Relaxed), 2);
+
+        // Panne durable : trois tentatives, puis l'erreur remonte
+        device.device.fail_after_trigger(true);
+        device.device.inject(Fault::FailRead(2));
+        let error = Fat32Error::IoError { op: crate::IoOp::Read, sector: 1 };
+        assert_eq!(device.read_sector(1, &mut buffer), Err(error));
+        assert_eq!(device.retries(), 4);
+
+        // Une erreur d'utilisation n'est pas reprise
+        device.reset_retries();
+        assert_eq!(device.read_sectors(0, 2, &mut buffer), Err(Fat32Error::BufferTooSmall));
+        assert_eq!(device.retries(), 0);
+        assert_eq!(device.into_inner().reads(), 5);
+    }
+}
@@ -0,0 +1,132 @@
+//! Device au-dessus d'un `embedded_storage::Storage` (feature `embedded-storage`)
+
+use alloc::vec;
+use embedded_storage::{ReadStorage, Storage};
+
+use crate::{BlockDevice, Fat32Error, IoOp, ReadBlockDevice, Result};
+
+/// Device basé sur une mémoire adressée à l'octet (flash, EEPROM...)
+///
+/// Le secteur `n` commence à l'octet `n * sector_size`. Un accès qui dépasse
+/// `capacity()` donne `IoError` ; les erreurs de la mémoire remontent en
+/// `Fat32Error::Device`.
+///
+/// Avec une taille d'effacement plus grande que le secteur, chaque écriture
+/// couvre des blocs d'effacement entiers : les blocs touchés sont relus,
+/// modifiés puis réécrits d'un coup.
+#[derive(Debug)]
+pub struct StorageDevice<S> {
+    storage: S,
+    sector_size: usize,
+    erase_size: usize,
+}
+
+impl<S: ReadStorage> StorageDevice<S> {
+    /// Secteurs de 512 octets
+    pub fn new(storage: S) -> Self {
+        Self::with_sector_size(storage, 512)
+    }
+
+    /// Secteurs de `sector_size` octets (au moins 1)
+    pub fn with_sector_size(storage: S, sector_size: usize) -> Self {
+        Self::with_erase_size(storage, sector_size, sector_size)
+    }
+
+    /// Secteurs de `sector_size` octets, écritures par blocs de `erase_size`
+    ///
+    /// `erase_size` est arrondi au multiple de `sector_size` supérieur.
+    pub fn with_erase_size(storage: S, sector_size: usize, erase_size: usize) -> Self {
+        let sector_size = sector_size.max(1);
+        Self {
+            storage,
+            sector_size,
+            erase_size: erase_size.max(sector_size).next_multiple_of(sector_size),
+        }
+    }
+
+    /// Nombre de secteurs complets
+    pub fn sector_count(&self) -> u32 {
+        (self.storage.capacity() / self.sector_size) as u32
+    }
+
+    /// Mémoire sous-jacente
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Récupérer la mémoire
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    /// Offset de `len` octets à partir du secteur `sector`, s'ils sont dans la mémoire
+    fn offset(&self, op: IoOp, sector: u32, len: usize) -> Result<u32> {
+        let start = (sector as usize).checked_mul(self.sector_size);
+        let error = Fat32Error::IoError { op, sector };
+        match start.and_then(|start| Some((start, start.checked_add(len)?))) {
+            Some((start, end)) if end <= self.storage.capacity() => {
+                u32::try_from(start).map_err(|_| error)
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+impl<S: ReadStorage> ReadBlockDevice for StorageDevice<S>
+where
+    S::Error: core::fmt::Debug,
+{
+    type Error = S::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), S::Error> {
+        let offset = self.offset(IoOp::Read, sector, buffer.len()).map_err(Fat32Error::widen)?;
+        self.storage.read(offset, buffer).map_err(Fat32Error::Device)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.sector_count() as u64
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), S::Error> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.read_sector(first, buffer)
+    }
+}
+
+impl<S: Storage> BlockDevice for StorageDevice<S>
+where
+    S::Error: core::fmt::Debug,
+{
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), S::Error> {
+        let offset = self.offset(IoOp::Write, sector, buffer.len());
+        let offset = offset.map_err(Fat32Error::widen)? as usize;
+
+        // Blocs d'effacement touchés par l'écriture
+        let start = offset / self.erase_size * self.erase_size;
+        let end = (offset + buffer.len())
+            .next_multiple_of(self.erase_size)
+            .min(self.storage.capacity());
+        if start == offset && end == offset + buffer.len() {
+            return self.storage.write(offset as u32, buffer).map_err(Fat32Error::Device);
+        }
+
+        // Lecture-modification-écriture
+        let mut block = vec![0u8; end - start];
+        self.storage.read(start as u32, &mut block).map_err(Fat32Error::Device)?;
+        block[offset - start..offset - start + buffer.len()].copy_from_slice(buffer);
+        self.storage.write(start as u32, &block).map_err(Fat32Error::Device)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), S::Error> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.write_sector(first, buffer)
+    }
+}
@@ -0,0 +1,154 @@
+//! Image disque derrière n'importe quel `Read + Seek` (feature `std`)
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::{BlockDevice, Fat32Error, IoErrorKind, IoOp, ReadBlockDevice, Result};
+
+/// Device au-dessus d'un flux : `Cursor<Vec<u8>>`, flux décompressé, lecteur réseau...
+///
+/// La taille est mesurée à la création (`SeekFrom::End`) ; un secteur
+/// incomplet à la fin est ignoré et un accès au-delà donne `IoError`, comme
+/// une lecture tronquée par la fin du flux. Les autres erreurs remontent en
+/// `Fat32Error::Device` avec leur `IoErrorKind`.
+///
+/// Sur un `Read + Seek` seul, `IoDevice` n'est qu'un `ReadBlockDevice`, à
+/// monter avec `ReadOnlyFileSystem` ; avec `Write`, c'est un `BlockDevice`
+/// dont `flush` appelle `Write::flush`.
+#[derive(Debug)]
+pub struct IoDevice<T> {
+    inner: T,
+    sector_size: usize,
+    sectors: u32,
+}
+
+impl<T: Seek> IoDevice<T> {
+    /// Secteurs de 512 octets
+    pub fn new(inner: T) -> io::Result<Self> {
+        Self::with_sector_size(inner, 512)
+    }
+
+    /// Secteurs de `sector_size` octets
+    pub fn with_sector_size(mut inner: T, sector_size: usize) -> io::Result<Self> {
+        if sector_size == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "taille de secteur nulle"));
+        }
+        let len = inner.seek(SeekFrom::End(0))?;
+        let sectors = u32::try_from(len / sector_size as u64)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "image trop grande"))?;
+        Ok(Self { inner, sector_size, sectors })
+    }
+
+    /// Nombre de secteurs complets
+    pub fn sector_count(&self) -> u32 {
+        self.sectors
+    }
+
+    /// Flux sous-jacent
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Accès direct au flux (ne pas en changer la taille)
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Récupérer le flux
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Se placer au secteur `sector` pour un accès de `len` octets
+    fn seek(&mut self, op: IoOp, sector: u32, len: usize) -> Result<(), IoErrorKind> {
+        let start = sector as u64 * self.sector_size as u64;
+        let end = self.sectors as u64 * self.sector_size as u64;
+        if start + len as u64 > end {
+            return Err(Fat32Error::IoError { op, sector });
+        }
+        self.inner.seek(SeekFrom::Start(start)).map_err(device_error(op, sector))?;
+        Ok(())
+    }
+}
+
+/// Erreur du flux pendant l'accès `op` au secteur `sector` (voir `Fat32Error::from_io_at`)
+fn device_error(op: IoOp, sector: u32) -> impl Fn(io::Error) -> Fat32Error<IoErrorKind> {
+    move |err| Fat32Error::from_io_at(err, op, sector)
+}
+
+impl<T: Read + Seek> ReadBlockDevice for IoDevice<T> {
+    type Error = IoErrorKind;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), IoErrorKind> {
+        self.seek(IoOp::Read, sector, buffer.len())?;
+        self.inner.read_exact(buffer).map_err(device_error(IoOp::Read, sector))
+    }
+
+    fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.sectors as u64
+    }
+
+    fn read_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), IoErrorKind> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.read_sector(first, buffer)
+    }
+}
+
+impl<T: Read + Write + Seek> BlockDevice for IoDevice<T> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), IoErrorKind> {
+        self.seek(IoOp::Write, sector, buffer.len())?;
+        self.inner.write_all(buffer).map_err(device_error(IoOp::Write, sector))
+    }
+
+    fn write_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &[u8],
+    ) -> Result<(), IoErrorKind> {
+        if buffer.len() != count as usize * self.sector_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        self.write_sector(first, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), IoErrorKind> {
+        self.inner.flush().map_err(device_error(IoOp::Flush, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_io_device_bounds() {
+        // Deux secteurs et demi : le dernier, incomplet, est ignoré
+        let mut device = IoDevice::new(Cursor::new(vec![0u8; 512 * 2 + 256])).unwrap();
+        assert_eq!(device.num_sectors(), 2);
+
+        device.write_sector(1, &[7; 512]).unwrap();
+        let mut buffer = [0u8; 512];
+        device.read_sector(1, &mut buffer).unwrap();
+        assert_eq!(buffer, [7; 512]);
+        let read_error = Fat32Error::IoError { op: IoOp::Read, sector: 2 };
+        assert_eq!(device.read_sector(2, &mut buffer), Err(read_error));
+        let write_error = Fat32Error::IoError { op: IoOp::Write, sector: 2 };
+        assert_eq!(device.write_sector(2, &buffer), Err(write_error));
+        assert_eq!(device.into_inner().into_inner().len(), 512 * 2 + 256);
+
+        assert!(IoDevice::with_sector_size(Cursor::new(vec![0u8; 512]), 0).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Enregistrement des accès d'un device (feature `test-util`)
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::{BlockDevice, ReadBlockDevice, Result};
+
+/// Nombre d'accès gardés par défaut
+const DEFAULT_CAPACITY: usize = 1 << 16;
+
+/// Nature d'un accès
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TraceOp {
+    Read,
+    Write,
+    Flush,
+}
+
+/// Un accès : `len` secteurs à partir de `sector` (0 et 0 pour un flush)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceEntry {
+    pub op: TraceOp,
+    pub sector: u32,
+    pub len: u32,
+}
+
+/// Résumé d'une trace
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TraceSummary {
+    /// Appels de lecture (`read_sector` ou `read_sectors`)
+    pub reads: usize,
+    /// Appels d'écriture
+    pub writes: usize,
+    pub flushes: usize,
+    pub sectors_read: u64,
+    pub sectors_written: u64,
+    /// Secteurs distincts lus
+    pub unique_sectors_read: usize,
+    /// Suites de lectures consécutives sur le disque (une lecture qui
+    /// reprend là où la précédente s'est arrêtée prolonge la suite)
+    pub read_runs: usize,
+    /// Plus longue suite de lectures consécutives, en secteurs
+    pub longest_read_run: u64,
+}
+
+/// Device qui enregistre chaque accès, dans l'ordre
+///
+/// Les transferts groupés sont notés tels quels (un accès de `count`
+/// secteurs) et transmis au device, qui garde ses redéfinitions. La trace
+/// est bornée : une fois pleine, les accès suivants ne sont plus notés mais
+/// comptés dans `dropped()`.
+pub struct TraceDevice<D: BlockDevice> {
+    device: D,
+    trace: Vec<TraceEntry>,
+    capacity: usize,
+    dropped: usize,
+}
+
+impl<D: BlockDevice> TraceDevice<D> {
+    /// Envelopper `device`, trace de 65 536 accès au plus
+    pub fn new(device: D) -> Self {
+        Self::with_capacity(device, DEFAULT_CAPACITY)
+    }
+
+    /// Envelopper `device`, trace de `capacity` accès au plus
+    pub fn with_capacity(device: D, capacity: usize) -> Self {
+        Self { device, trace: Vec::new(), capacity, dropped: 0 }
+    }
+
+    /// Accès enregistrés
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    /// Accès perdus faute de place
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Vider la trace
+    pub fn clear(&mut self) {
+        self.trace.clear();
+        self.dropped = 0;
+    }
+
+    /// Secteurs lus, un par un, dans l'ordre
+    pub fn sectors_read(&self) -> Vec<u32> {
+        self.trace
+            .iter()
+            .filter(|e| e.op == TraceOp::Read)
+            .flat_map(|e| e.sector..e.sector + e.len)
+            .collect()
+    }
+
+    /// Statistiques de la trace
+    pub fn summary(&self) -> TraceSummary {
+        let mut summary = TraceSummary::default();
+        let mut unique = BTreeSet::new();
+        let mut run: Option<(u32, u64)> = None; // (fin de la suite, longueur)
+        for entry in &self.trace {
+            match entry.op {
+                TraceOp::Read => {
+                    summary.reads += 1;
+                    summary.sectors_read += entry.len as u64;
+                    unique.extend(entry.sector..entry.sector + entry.len);
+                    let length = match run {
+                        Some((end, length)) if end == entry.sector => length + entry.len as u64,
+                        _ => {
+                            summary.read_runs += 1;
+                            entry.len as u64
+                        }
+                    };
+                    summary.longest_read_run = summary.longest_read_run.max(length);
+                    run = Some((entry.sector + entry.len, length));
+                }
+                TraceOp::Write => {
+                    summary.writes += 1;
+                    summary.sectors_written += entry.len as u64;
+                }
+                TraceOp::Flush => summary.flushes += 1,
+            }
+        }
+        summary.unique_sectors_read = unique.len();
+        summary
+    }
+
+    /// Écrire la trace en CSV (`op,sector,len`, une ligne par accès)
+    #[cfg(feature = "std")]
+    pub fn write_csv<W: std::io::Write>(&self, mut out: W) -> std::io::Result<()> {
+        writeln!(out, "op,sector,len")?;
+        for entry in &self.trace {
+            let op = match entry.op {
+                TraceOp::Read => "read",
+                TraceOp::Write => "write",
+                TraceOp::Flush => "flush",
+            };
+            writeln!(out, "{},{},{}", op, entry.sector, entry.len)?;
+        }
+        Ok(())
+    }
+
+    /// Device sous-jacent
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Récupérer le device
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    fn record(&mut self, op: TraceOp, sector: u32, len: u32) {
+        if self.trace.len() < self.capacity {
+            self.trace.push(TraceEntry { op, sector, len });
+        } else {
+            self.dropped += 1;
+        }
+    }
+}
+
+impl<D: BlockDevice> ReadBlockDevice for TraceDevice<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.record(TraceOp::Read, sector, 1);
+        self.device.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.device.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.device.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.record(TraceOp::Read, first, count);
+        self.device.read_sectors(first, count, buffer)
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for TraceDevice<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        self.record(TraceOp::Write, sector, 1);
+        self.device.write_sector(sector, buffer)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        self.record(TraceOp::Write, first, count);
+        self.device.write_sectors(first, count, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        self.record(TraceOp::Flush, 0, 0);
+        self.device.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryDevice;
+
+    #[test]
+    fn test_trace_summary() {
+        let mut device = TraceDevice::with_capacity(MemoryDevice::new(64), 6);
+        let mut buffer = [0u8; 4 * 512];
+        device.read_sectors(10, 4, &mut buffer).unwrap();
+        device.read_sector(14, &mut buffer[..512]).unwrap();
+        device.read_sector(11, &mut buffer[..512]).unwrap();
+        device.write_sectors(20, 2, &buffer[..1024]).unwrap();
+        device.flush().unwrap();
+        device.read_sector(30, &mut buffer[..512]).unwrap();
+        device.read_sector(31, &mut buffer[..512]).unwrap();
+
+        assert_eq!(device.trace()[0], TraceEntry { op: TraceOp::Read, sector: 10, len: 4 });
+        assert_eq!(device.dropped(), 1);
+        assert_eq!(device.sectors_read(), [10, 11, 12, 13, 14, 11, 30]);
+        let summary = device.summary();
+        assert_eq!((summary.reads, summary.writes, summary.flushes), (4, 1, 1));
+        assert_eq!((summary.sectors_read, summary.sectors_written), (7, 2));
+        assert_eq!(summary.unique_sectors_read, 6);
+        // 10..15, 11, 30
+        assert_eq!((summary.read_runs, summary.longest_read_run), (3, 5));
+
+        #[cfg(feature = "std")]
+        {
+            let mut csv = Vec::new();
+            device.write_csv(&mut csv).unwrap();
+            assert!(csv.starts_with(b"op,sector,len\nread,10,4\nread,14,1\n"));
+            assert!(csv.ends_with(b"flush,0,0\nread,30,1\n"));
+        }
+
+        device.clear();
+        assert!(device.trace().is_empty());
+        assert_eq!(device.summary(), TraceSummary::default());
+    }
+}
@@ -0,0 +1,289 @@
+//! Petits secteurs logiques au-dessus de grands secteurs physiques
+
+use crate::cache::AlignedBuf;
+use crate::{BlockDevice, Fat32Error, ReadBlockDevice, Result};
+
+/// Device en secteurs logiques de 512 octets sur un device à grands secteurs
+///
+/// Pour un volume formaté en 512 octets/secteur sur un support qui ne sait
+/// lire et écrire que des secteurs de 4096 (NAND brute, certains ponts USB).
+/// Le secteur logique `n` est la tranche `n % k` du secteur physique `n / k`.
+///
+/// Le dernier secteur physique touché reste en mémoire : des lectures
+/// logiques consécutives ne le relisent pas, et des écritures logiques dans
+/// le même secteur physique ne donnent qu'une écriture, faite quand un autre
+/// secteur physique est chargé ou à `flush()`. Une écriture logique seule
+/// relit donc le secteur physique (lecture-modification-écriture). Les
+/// secteurs physiques entièrement couverts par `write_sectors` sont écrits
+/// directement.
+///
+/// Comme pour `CachedDevice`, une écriture en attente est perdue si le
+/// device est détruit sans `flush()`.
+pub struct SectorTranslator<D: BlockDevice> {
+    device: D,
+    logical_size: usize,
+    /// Secteurs logiques par secteur physique
+    ratio: u32,
+    buffer: AlignedBuf,
+    /// Secteur physique dans `buffer`
+    loaded: Option<u32>,
+    dirty: bool,
+}
+
+impl<D: BlockDevice> SectorTranslator<D> {
+    /// Secteurs logiques de 512 octets
+    ///
+    /// `InvalidInput` si les secteurs du device ne sont pas un multiple de 512.
+    pub fn new(device: D) -> Result<Self, D::Error> {
+        Self::with_logical_size(device, 512)
+    }
+
+    /// Secteurs logiques de `logical_size` octets
+    ///
+    /// `InvalidInput` si les secteurs du device n'en sont pas un multiple.
+    pub fn with_logical_size(device: D, logical_size: usize) -> Result<Self, D::Error> {
+        let physical_size = device.sector_size();
+        if logical_size == 0
+            || physical_size < logical_size
+            || !physical_size.is_multiple_of(logical_size)
+        {
+            return Err(Fat32Error::InvalidInput);
+        }
+        Ok(Self {
+            ratio: (physical_size / logical_size) as u32,
+            buffer: AlignedBuf::zeroed(physical_size, device.buffer_alignment()),
+            device,
+            logical_size,
+            loaded: None,
+            dirty: false,
+        })
+    }
+
+    /// Y a-t-il une écriture en attente ?
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Device sous-jacent (sans l'écriture en attente)
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Écrire le secteur en attente et récupérer le device
+    pub fn into_inner(mut self) -> Result<D, D::Error> {
+        self.write_back()?;
+        Ok(self.device)
+    }
+
+    /// Secteur physique et offset dans celui-ci d'un secteur logique
+    fn locate(&self, sector: u32) -> (u32, usize) {
+        (sector / self.ratio, (sector % self.ratio) as usize * self.logical_size)
+    }
+
+    /// Écrire le secteur physique chargé s'il a été modifié
+    fn write_back(&mut self) -> Result<(), D::Error> {
+        if let (Some(physical), true) = (self.loaded, self.dirty) {
+            self.device.write_sector(physical, &self.buffer)?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Charger le secteur physique `physical` dans `buffer`
+    fn load(&mut self, physical: u32) -> Result<(), D::Error> {
+        if self.loaded == Some(physical) {
+            return Ok(());
+        }
+        self.write_back()?;
+        self.loaded = None;
+        self.device.read_sector(physical, &mut self.buffer)?;
+        self.loaded = Some(physical);
+        Ok(())
+    }
+}
+
+impl<D: BlockDevice> ReadBlockDevice for SectorTranslator<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        if buffer.len() != self.logical_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        let (physical, offset) = self.locate(sector);
+        self.load(physical)?;
+        buffer.copy_from_slice(&self.buffer[offset..offset + self.logical_size]);
+        Ok(())
+    }
+
+    fn sector_size(&self) -> usize {
+        self.logical_size
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.device.num_sectors().saturating_mul(self.ratio as u64)
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for SectorTranslator<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        if buffer.len() != self.logical_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        let (physical, offset) = self.locate(sector);
+        self.load(physical)?;
+        self.buffer[offset..offset + self.logical_size].copy_from_slice(buffer);
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        if buffer.len() != count as usize * self.logical_size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+
+        let physical_size = self.buffer.len();
+        let mut sector = first;
+        let mut rest = buffer;
+        while !rest.is_empty() {
+            let (physical, offset) = self.locate(sector);
+            if offset == 0 && rest.len() >= physical_size {
+                // Secteur physique entier : pas besoin de le relire
+                let (chunk, tail) = rest.split_at(physical_size);
+                self.device.write_sector(physical, chunk)?;
+                if self.loaded == Some(physical) {
+                    self.buffer.copy_from_slice(chunk);
+                    self.dirty = false;
+                }
+                sector += self.ratio;
+                rest = tail;
+            } else {
+                let (chunk, tail) = rest.split_at(self.logical_size);
+                self.write_sector(sector, chunk)?;
+                sector += 1;
+                rest = tail;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        self.write_back()?;
+        self.device.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryDevice;
+    use core::convert::Infallible;
+
+    /// Device de secteurs de 4096 octets qui compte les accès physiques
+    struct Counting {
+        inner: MemoryDevice,
+        reads: usize,
+        writes: usize,
+    }
+
+    impl ReadBlockDevice for Counting {
+        type Error = Infallible;
+
+        fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
+            self.reads += 1;
+            self.inner.read_sector(sector, buffer)
+        }
+
+        fn sector_size(&self) -> usize {
+            4096
+        }
+
+        fn num_sectors(&self) -> u64 {
+            self.inner.num_sectors()
+        }
+    }
+
+    impl BlockDevice for Counting {
+        fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
+            self.writes += 1;
+            self.inner.write_sector(sector, buffer)
+        }
+    }
+
+    fn translator(physical_sectors: u32) -> SectorTranslator<Counting> {
+        let inner = MemoryDevice::with_sector_size(physical_sectors, 4096);
+        SectorTranslator::new(Counting { inner, reads: 0, writes: 0 }).unwrap()
+    }
+
+    #[test]
+    fn test_translator_boundaries_and_batching() {
+        let mut device = translator(4);
+        assert_eq!(device.sector_size(), 512);
+        assert_eq!(device.num_sectors(), 32);
+
+        // Huit écritures consécutives : une lecture et une écriture physiques
+        for sector in 8..16 {
+            device.write_sector(sector, &[sector as u8; 512]).unwrap();
+        }
+        assert!(device.is_dirty());
+        assert_eq!((device.inner().reads, device.inner().writes), (1, 0));
+        device.flush().unwrap();
+        assert_eq!((device.inner().reads, device.inner().writes), (1, 1));
+        assert!(!device.is_dirty());
+
+        // Dernier secteur logique d'un physique, puis premier du suivant
+        let data = device.inner().inner.data();
+        assert_eq!(data[2 * 4096 - 1], 15);
+        assert_eq!(data[4096], 8);
+        let mut buffer = [0u8; 512];
+        device.read_sector(15, &mut buffer).unwrap();
+        assert_eq!(buffer, [15; 512]);
+        device.read_sector(16, &mut buffer).unwrap();
+        assert_eq!(buffer, [0; 512]);
+        assert_eq!(device.inner().reads, 2);
+
+        // Lectures dans le secteur physique chargé : pas de relecture
+        for sector in 17..24 {
+            device.read_sector(sector, &mut buffer).unwrap();
+        }
+        assert_eq!(device.inner().reads, 2);
+
+        // Hors du device (secteur physique dans l'erreur), ou tampon d'une
+        // mauvaise taille
+        let error = Fat32Error::IoError { op: crate::IoOp::Read, sector: 4 };
+        assert_eq!(device.read_sector(32, &mut buffer), Err(error));
+        assert_eq!(device.read_sector(0, &mut [0u8; 4096]), Err(Fat32Error::BufferTooSmall));
+        assert!(SectorTranslator::with_logical_size(MemoryDevice::new(1), 1024).is_err());
+        assert!(SectorTranslator::with_logical_size(MemoryDevice::new(1), 384).is_err());
+    }
+
+    #[test]
+    fn test_translator_read_modify_write() {
+        let mut device = translator(4);
+        device.write_sectors(0, 8, &[0xAA; 4096]).unwrap();
+        assert_eq!((device.inner().reads, device.inner().writes), (0, 1));
+
+        // Un secteur logique au milieu : les sept autres sont conservés
+        device.write_sector(5, &[0x55; 512]).unwrap();
+        // Changer de secteur physique écrit le précédent
+        let mut buffer = [0u8; 512];
+        device.read_sector(8, &mut buffer).unwrap();
+        assert_eq!((device.inner().reads, device.inner().writes), (2, 2));
+
+        let data = device.inner().inner.data();
+        assert!(data[..5 * 512].iter().all(|&b| b == 0xAA));
+        assert!(data[5 * 512..6 * 512].iter().all(|&b| b == 0x55));
+        assert!(data[6 * 512..4096].iter().all(|&b| b == 0xAA));
+
+        // À cheval : fin d'un physique en RMW, physique entier direct, début du suivant
+        device.write_sectors(7, 10, &[0x11; 10 * 512]).unwrap();
+        let device = device.into_inner().unwrap();
+        let data = device.inner.data();
+        assert!(data[6 * 512..7 * 512].iter().all(|&b| b == 0xAA));
+        assert!(data[7 * 512..17 * 512].iter().all(|&b| b == 0x11));
+        assert!(data[17 * 512..3 * 4096].iter().all(|&b| b == 0));
+    }
+}
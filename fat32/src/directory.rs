@@ -1,10 +1,17 @@
 //! Gestion des entrées de répertoire
 
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+use crate::{Fat32Error, NameError, Result};
 
 /// Attributs d'un fichier/dossier
-#[derive(Copy, Clone)]
+///
+/// Les constantes restent des `u8` ; les opérateurs `|`, `&` et `!`
+/// combinent des `FileAttributes` entre eux.
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct FileAttributes(pub u8);
 
 impl FileAttributes {
@@ -27,6 +34,49 @@ impl FileAttributes {
     pub fn is_volume_id(&self) -> bool {
         self.0 & Self::VOLUME_ID != 0
     }
+
+    /// Description lisible des bits positionnés ("DIRECTORY | ARCHIVE")
+    pub fn describe(&self) -> String {
+        if self.is_long_name() {
+            return "LONG_NAME".into();
+        }
+
+        let names = [
+            (Self::READ_ONLY, "READ_ONLY"),
+            (Self::HIDDEN, "HIDDEN"),
+            (Self::SYSTEM, "SYSTEM"),
+            (Self::VOLUME_ID, "VOLUME_ID"),
+            (Self::DIRECTORY, "DIRECTORY"),
+            (Self::ARCHIVE, "ARCHIVE"),
+        ];
+
+        let mut description = String::new();
+        for (bit, name) in names {
+            if self.0 & bit != 0 {
+                if !description.is_empty() {
+                    description.push_str(" | ");
+                }
+                description.push_str(name);
+            }
+        }
+
+        if description.is_empty() {
+            description.push_str("NONE");
+        }
+        description
+    }
+}
+
+impl fmt::LowerHex for FileAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:02x}", self.0)
+    }
+}
+
+impl fmt::Binary for FileAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0b{:08b}", self.0)
+    }
 }
 
 impl fmt::Debug for FileAttributes {
@@ -35,7 +85,223 @@ impl fmt::Debug for FileAttributes {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for FileAttributes {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Attributes({=u8:#04x})", self.0)
+    }
+}
+
+impl BitOr for FileAttributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for FileAttributes {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOrAssign for FileAttributes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAndAssign for FileAttributes {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Not for FileAttributes {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+/// Date au format FAT : année depuis 1980 (bits 15-9), mois (8-5), jour (4-0)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FatDate(pub u16);
+
+impl FatDate {
+    /// Encoder une date (année de 1980 à 2107)
+    pub fn new(year: u16, month: u8, day: u8) -> Self {
+        let year = year.saturating_sub(1980).min(127);
+        Self((year << 9) | ((month as u16 & 0x0F) << 5) | (day as u16 & 0x1F))
+    }
+
+    pub fn year(&self) -> u16 {
+        1980 + (self.0 >> 9)
+    }
+
+    pub fn month(&self) -> u8 {
+        ((self.0 >> 5) & 0x0F) as u8
+    }
+
+    pub fn day(&self) -> u8 {
+        (self.0 & 0x1F) as u8
+    }
+
+    /// Jours depuis le 1970-01-01 (négatif avant)
+    pub fn to_unix_days(&self) -> i64 {
+        days_from_civil(self.year() as i64, self.month() as i64, self.day() as i64)
+    }
+
+    /// Date à `days` jours du 1970-01-01 (bornée à 1980..=2107)
+    pub fn from_unix_days(days: i64) -> Self {
+        let (year, month, day) = civil_from_days(days.clamp(FAT_MIN_DAYS, FAT_MAX_DAYS));
+        Self::new(year as u16, month, day)
+    }
+
+    /// Jours écoulés depuis `epoch` (négatif si la date est avant)
+    pub fn to_days_since_epoch(&self, epoch: FatDate) -> i64 {
+        self.to_unix_days() - epoch.to_unix_days()
+    }
+
+    /// Date à `days` jours de `epoch` (bornée à 1980..=2107)
+    pub fn from_days_since_epoch(days: i64, epoch: FatDate) -> Self {
+        Self::from_unix_days(epoch.to_unix_days().saturating_add(days))
+    }
+}
+
+/// `AAAA-MM-JJ` (ISO 8601)
+impl fmt::Display for FatDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year(), self.month(), self.day())
+    }
+}
+
+/// Heure au format FAT : heures (bits 15-11), minutes (10-5), secondes / 2 (4-0)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FatTime(pub u16);
+
+impl FatTime {
+    /// Encoder une heure (les secondes sont arrondies au pair inférieur)
+    pub fn new(hour: u8, minute: u8, second: u8) -> Self {
+        let hour = (hour as u16 & 0x1F) << 11;
+        let minute = (minute as u16 & 0x3F) << 5;
+        Self(hour | minute | ((second as u16 / 2) & 0x1F))
+    }
+
+    pub fn hour(&self) -> u8 {
+        (self.0 >> 11) as u8
+    }
+
+    pub fn minute(&self) -> u8 {
+        ((self.0 >> 5) & 0x3F) as u8
+    }
+
+    pub fn second(&self) -> u8 {
+        ((self.0 & 0x1F) * 2) as u8
+    }
+}
+
+/// `HH:MM:SS` (ISO 8601) ; les secondes FAT sont toujours paires
+impl fmt::Display for FatTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour(), self.minute(), self.second())
+    }
+}
+
+/// Date et heure FAT réunies, convertibles en secondes Unix (UTC)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FatTimestamp {
+    pub date: FatDate,
+    pub time: FatTime,
+}
+
+impl FatTimestamp {
+    pub fn new(date: FatDate, time: FatTime) -> Self {
+        Self { date, time }
+    }
+
+    /// Secondes depuis le 1970-01-01 00:00:00
+    pub fn to_unix_seconds(&self) -> i64 {
+        let time = &self.time;
+        self.date.to_unix_days() * 86_400
+            + time.hour() as i64 * 3600
+            + time.minute() as i64 * 60
+            + time.second() as i64
+    }
+
+    /// Convertir des secondes Unix (bornées à 1980..=2107, secondes paires)
+    pub fn from_unix_seconds(secs: i64) -> Self {
+        let secs = secs.clamp(FAT_MIN_DAYS * 86_400, FAT_MAX_DAYS * 86_400 + 86_398);
+        let rem = secs.rem_euclid(86_400);
+        let time = FatTime::new((rem / 3600) as u8, (rem / 60 % 60) as u8, (rem % 60) as u8);
+        Self::new(FatDate::from_unix_days(secs.div_euclid(86_400)), time)
+    }
+}
+
+/// `AAAA-MM-JJTHH:MM:SS` (ISO 8601, sans fuseau)
+impl fmt::Display for FatTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}T{}", self.date, self.time)
+    }
+}
+
+/// Premier et dernier jour représentables (1980-01-01, 2107-12-31), depuis 1970
+const FAT_MIN_DAYS: i64 = 3652;
+const FAT_MAX_DAYS: i64 = 50_402;
+
+/// Jours depuis le 1970-01-01 d'une date civile (algorithme de H. Hinnant)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Date civile à `days` jours du 1970-01-01 (inverse de `days_from_civil`)
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Source de l'heure courante pour dater les entrées
+pub trait TimeSource {
+    fn now(&self) -> (FatDate, FatTime);
+}
+
+/// Heure système (UTC) via `std::time::SystemTime`
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTimeSource;
+
+#[cfg(feature = "std")]
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> (FatDate, FatTime) {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let timestamp = FatTimestamp::from_unix_seconds(secs);
+        (timestamp.date, timestamp.time)
+    }
+}
+
 /// Entrée de répertoire (32 octets)
+///
+/// Structure packed : les champs de plus d'un octet ne sont lus et écrits
+/// qu'avec `read_unaligned` / `write_unaligned`, jamais par référence.
 #[repr(C, packed)]
 #[derive(Copy, Clone)]
 pub struct DirectoryEntry {
@@ -70,6 +336,106 @@ impl DirectoryEntry {
         unsafe { core::ptr::read_unaligned(data.as_ptr() as *const DirectoryEntry) }
     }
 
+    /// Créer une nouvelle entrée à partir d'un nom 8.3 déjà encodé
+    pub fn new(name: [u8; 11], attributes: u8, first_cluster: u32, file_size: u32) -> Self {
+        Self {
+            name,
+            attributes,
+            nt_reserved: 0,
+            creation_time_tenth: 0,
+            creation_time: 0,
+            creation_date: 0,
+            last_access_date: 0,
+            first_cluster_high: (first_cluster >> 16) as u16,
+            write_time: 0,
+            write_date: 0,
+            first_cluster_low: first_cluster as u16,
+            file_size,
+        }
+    }
+
+    /// Sérialiser l'entrée (32 octets)
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut data = [0u8; 32];
+        unsafe { core::ptr::write_unaligned(data.as_mut_ptr() as *mut DirectoryEntry, *self) };
+        data
+    }
+
+    /// Encoder un nom lisible ("readme.txt") en nom 8.3 ("README  TXT")
+    ///
+    /// `NameTooLong` si le nom dépasse 8 caractères ou l'extension 3,
+    /// `InvalidPath` (composant 0) si un caractère n'a pas cours en 8.3.
+    pub fn encode_short_name(name: &str) -> Result<[u8; 11]> {
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(Fat32Error::InvalidPath { component: 0 });
+        }
+
+        let (base, ext) = match name.rfind('.') {
+            Some(pos) => (&name[..pos], &name[pos + 1..]),
+            None => (name, ""),
+        };
+        if base.is_empty() {
+            return Err(Fat32Error::InvalidPath { component: 0 });
+        }
+        if base.len() > 8 || ext.len() > 3 {
+            return Err(Fat32Error::NameTooLong);
+        }
+
+        let mut encoded = [b' '; 11];
+        for (i, c) in base.bytes().enumerate() {
+            encoded[i] = Self::short_name_char(c)?;
+        }
+        for (i, c) in ext.bytes().enumerate() {
+            encoded[8 + i] = Self::short_name_char(c)?;
+        }
+
+        Ok(encoded)
+    }
+
+    /// Encoder un nom de volume (11 caractères au plus, complétés par des espaces)
+    ///
+    /// Mêmes caractères qu'un nom 8.3, plus l'espace ; les minuscules passent
+    /// en majuscules et les espaces de fin sont ignorés. Un nom vide donne
+    /// 11 espaces.
+    pub fn encode_volume_label(label: &str) -> Result<[u8; 11]> {
+        let label = label.trim_end_matches(' ');
+        if label.chars().count() > 11 {
+            return Err(Fat32Error::NameTooLong);
+        }
+
+        let mut encoded = [b' '; 11];
+        for (i, c) in label.chars().enumerate() {
+            encoded[i] = match c {
+                ' ' => b' ',
+                _ if c.is_ascii() => Self::short_name_char(c as u8)
+                    .map_err(|_| Fat32Error::InvalidName(NameError::IllegalChar(c)))?,
+                _ => return Err(Fat32Error::InvalidName(NameError::IllegalChar(c))),
+            };
+        }
+        Ok(encoded)
+    }
+
+    /// Caractère autorisé dans un nom 8.3 (converti en majuscule)
+    fn short_name_char(c: u8) -> Result<u8> {
+        match c {
+            b'A'..=b'Z' | b'0'..=b'9' => Ok(c),
+            b'a'..=b'z' => Ok(c.to_ascii_uppercase()),
+            b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'(' | b')' | b'-' | b'@' | b'^'
+            | b'_' | b'`' | b'{' | b'}' | b'~' => Ok(c),
+            _ => Err(Fat32Error::InvalidPath { component: 0 }),
+        }
+    }
+
+    /// Changer le nom 8.3 (déjà encodé)
+    pub(crate) fn set_name(&mut self, name: [u8; 11]) {
+        self.name = name;
+    }
+
+    /// Marquer l'entrée comme supprimée
+    pub fn mark_deleted(&mut self) {
+        self.name[0] = 0xE5;
+    }
+
     pub fn is_free(&self) -> bool {
         self.name[0] == 0xE5
     }
@@ -90,14 +456,93 @@ impl DirectoryEntry {
 
     /// premier cluster
     pub fn first_cluster(&self) -> u32 {
-        let high = self.first_cluster_high;
-        let low = self.first_cluster_low;
+        let (high, low) = unsafe {
+            (
+                core::ptr::read_unaligned(core::ptr::addr_of!(self.first_cluster_high)),
+                core::ptr::read_unaligned(core::ptr::addr_of!(self.first_cluster_low)),
+            )
+        };
         ((high as u32) << 16) | (low as u32)
     }
 
+    /// Changer le premier cluster
+    pub fn set_first_cluster(&mut self, cluster: u32) {
+        let (high, low) = ((cluster >> 16) as u16, cluster as u16);
+        unsafe {
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.first_cluster_high), high);
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.first_cluster_low), low);
+        }
+    }
+
     /// Taille du fichier
     pub fn file_size(&self) -> u32 {
-        self.file_size
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.file_size)) }
+    }
+
+    /// Changer la taille du fichier
+    pub fn set_file_size(&mut self, size: u32) {
+        unsafe { core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.file_size), size) };
+    }
+
+    /// Date de création
+    pub fn creation_date(&self) -> FatDate {
+        FatDate(unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.creation_date)) })
+    }
+
+    /// Heure de création
+    pub fn creation_time(&self) -> FatTime {
+        FatTime(unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.creation_time)) })
+    }
+
+    /// Date de dernière modification
+    pub fn write_date(&self) -> FatDate {
+        FatDate(unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.write_date)) })
+    }
+
+    /// Heure de dernière modification
+    pub fn write_time(&self) -> FatTime {
+        FatTime(unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.write_time)) })
+    }
+
+    /// Date de dernier accès
+    pub fn last_access_date(&self) -> FatDate {
+        FatDate(unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.last_access_date)) })
+    }
+
+    /// Changer la date et l'heure de dernière modification
+    pub fn update_write_timestamp(&mut self, date: FatDate, time: FatTime) {
+        unsafe {
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.write_date), date.0);
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.write_time), time.0);
+        }
+    }
+
+    /// Changer la date de dernier accès
+    pub fn update_access_date(&mut self, date: FatDate) {
+        unsafe {
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.last_access_date), date.0);
+        }
+    }
+
+    /// Changer la date et l'heure de création (les centièmes sont remis à zéro)
+    pub fn update_creation_timestamp(&mut self, date: FatDate, time: FatTime) {
+        self.creation_time_tenth = 0;
+        unsafe {
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.creation_date), date.0);
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.creation_time), time.0);
+        }
+    }
+
+    /// Dater une entrée qui vient d'être créée
+    pub(crate) fn set_created(&mut self, date: FatDate, time: FatTime) {
+        self.update_creation_timestamp(date, time);
+        self.set_modified(date, time);
+    }
+
+    /// Dater une modification (écriture et accès)
+    pub(crate) fn set_modified(&mut self, date: FatDate, time: FatTime) {
+        self.update_write_timestamp(date, time);
+        self.update_access_date(date);
     }
 
     /// Convertir le nom en String lisible
@@ -115,7 +560,7 @@ impl DirectoryEntry {
             .trim_end();
 
         if ext_part.is_empty() {
-            alloc::format!("{}", name_part)
+            name_part.into()
         } else {
             alloc::format!("{}.{}", name_part, ext_part)
         }
@@ -132,6 +577,22 @@ impl DirectoryEntry {
     }
 }
 
+// Comparaison sur les 32 octets bruts : pas de référence vers les champs packed
+impl PartialEq for DirectoryEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for DirectoryEntry {}
+
+// Cohérent avec PartialEq : même représentation disque, même hash
+impl Hash for DirectoryEntry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state);
+    }
+}
+
 impl fmt::Debug for DirectoryEntry {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Copiervaleurs au lieu de créer références
@@ -149,3 +610,361 @@ impl fmt::Debug for DirectoryEntry {
     }
 }
 
+// Mêmes champs que Debug, copiés hors de la structure packed
+#[cfg(feature = "defmt")]
+impl defmt::Format for DirectoryEntry {
+    fn format(&self, f: defmt::Formatter) {
+        let name = self.short_name();
+        defmt::write!(
+            f,
+            "DirectoryEntry {{ name: {=str}, attributes: {}, cluster: {=u32}, size: {=u32} }}",
+            name.as_str(),
+            self.attributes(),
+            self.first_cluster(),
+            self.file_size()
+        )
+    }
+}
+
+/// Nombre maximum d'entrées dans un nom long (255 caractères / 13)
+const LFN_MAX_PARTS: u8 = 20;
+
+/// Somme de contrôle d'un nom 8.3, répétée dans ses entrées de nom long
+pub fn lfn_checksum(name: &[u8; 11]) -> u8 {
+    name.iter().fold(0u8, |sum, &c| sum.rotate_right(1).wrapping_add(c))
+}
+
+/// Les 13 caractères UTF-16 d'une entrée de nom long
+fn lfn_units(raw: &[u8]) -> [u16; 13] {
+    let mut units = [0u16; 13];
+    let offsets = (1..11).step_by(2).chain((14..26).step_by(2)).chain((28..32).step_by(2));
+    for (unit, offset) in units.iter_mut().zip(offsets) {
+        *unit = u16::from_le_bytes([raw[offset], raw[offset + 1]]);
+    }
+    units
+}
+
+/// Assemblage des entrées de nom long qui précèdent une entrée 8.3
+///
+/// Les entrées sont stockées de la dernière (bit 0x40) à la première. Une
+/// séquence incomplète, dans le désordre ou de somme de contrôle différente
+/// est abandonnée.
+#[derive(Default)]
+pub(crate) struct LongNameBuilder {
+    parts: Vec<[u16; 13]>,
+    checksum: u8,
+    expected: u8,
+}
+
+impl LongNameBuilder {
+    /// Ajouter une entrée de nom long (32 octets bruts)
+    pub(crate) fn push(&mut self, raw: &[u8]) {
+        let order = raw[0] & 0x1F;
+        let checksum = raw[13];
+
+        if raw[0] & 0x40 != 0 {
+            if order == 0 || order > LFN_MAX_PARTS {
+                self.reset();
+                return;
+            }
+            self.parts = alloc::vec![[0xFFFF; 13]; order as usize];
+            self.checksum = checksum;
+        } else if self.parts.is_empty() || order == 0 || order != self.expected || checksum != self.checksum {
+            self.reset();
+            return;
+        }
+
+        self.parts[order as usize - 1] = lfn_units(raw);
+        self.expected = order - 1;
+    }
+
+    /// Nom long de l'entrée 8.3 qui suit la séquence, s'il est valide
+    pub(crate) fn finish(&mut self, entry: &DirectoryEntry) -> Option<String> {
+        let parts = core::mem::take(&mut self.parts);
+        let complete = !parts.is_empty() && self.expected == 0 && lfn_checksum(&entry.name) == self.checksum;
+        self.reset();
+        if !complete {
+            return None;
+        }
+
+        let units = parts
+            .iter()
+            .flatten()
+            .copied()
+            .take_while(|&unit| unit != 0x0000 && unit != 0xFFFF);
+        let name = char::decode_utf16(units).collect::<core::result::Result<String, _>>().ok()?;
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Abandonner la séquence en cours
+    pub(crate) fn reset(&mut self) {
+        self.parts.clear();
+        self.expected = 0;
+    }
+}
+
+/// Ce qu'apporte une entrée brute au parcours d'un répertoire
+pub(crate) enum DirSlot {
+    /// Marque de fin : aucune entrée après
+    End,
+    /// Entrée visible, avec son nom long s'il est valide
+    Entry(DirectoryEntry, Option<String>),
+    /// Entrée libre, de nom long, de volume...
+    Skip,
+}
+
+/// Parcours des entrées brutes d'un répertoire, dans l'ordre du disque
+///
+/// Ne fait aucune lecture : partagé par `Fat32FileSystem` et
+/// `AsyncFat32FileSystem`, qui ne diffèrent que par la lecture des clusters.
+#[derive(Default)]
+pub(crate) struct DirScanner {
+    long_name: LongNameBuilder,
+}
+
+impl DirScanner {
+    /// Traiter l'entrée suivante (32 octets bruts)
+    pub(crate) fn scan(&mut self, raw: &[u8]) -> DirSlot {
+        let entry = unsafe { DirectoryEntry::from_bytes(raw) };
+
+        if entry.is_end() {
+            return DirSlot::End;
+        }
+        if entry.is_free() {
+            self.long_name.reset();
+        } else if entry.attributes().is_long_name() {
+            self.long_name.push(raw);
+        } else if is_listed(&entry) {
+            let long = self.long_name.finish(&entry);
+            return DirSlot::Entry(entry, long);
+        } else {
+            self.long_name.reset();
+        }
+        DirSlot::Skip
+    }
+}
+
+/// Entrée visible dans un listing (ni supprimée, ni nom long, ni volume)
+pub(crate) fn is_listed(entry: &DirectoryEntry) -> bool {
+    entry.is_valid() && !entry.attributes().is_long_name() && !entry.attributes().is_volume_id()
+}
+
+/// L'entrée porte-t-elle le nom `name` ?
+///
+/// Nom 8.3 comparé sans tenir compte de la casse ASCII, nom long sans tenir
+/// compte de la casse Unicode.
+pub(crate) fn matches_name(entry: &DirectoryEntry, long: Option<&str>, name: &str) -> bool {
+    entry.short_name().eq_ignore_ascii_case(name)
+        || long.is_some_and(|long| same_long_name(long, name))
+}
+
+/// Comparer deux noms longs sans tenir compte de la casse
+fn same_long_name(a: &str, b: &str) -> bool {
+    a.chars().flat_map(char::to_lowercase).eq(b.chars().flat_map(char::to_lowercase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_encode_short_name() {
+        let name = DirectoryEntry::encode_short_name("readme.txt").unwrap();
+        assert_eq!(&name, b"README  TXT");
+
+        let entry = DirectoryEntry::new(name, FileAttributes::ARCHIVE, 0x12345, 42);
+        let copy = unsafe { DirectoryEntry::from_bytes(&entry.to_bytes()) };
+        assert_eq!(copy.short_name(), "README.TXT");
+        assert_eq!(copy.first_cluster(), 0x12345);
+        assert_eq!(copy.file_size(), 42);
+
+        let too_long = Err(Fat32Error::NameTooLong);
+        assert_eq!(DirectoryEntry::encode_short_name("toolongname.txt"), too_long);
+        assert_eq!(DirectoryEntry::encode_short_name("a.text"), too_long);
+        assert!(DirectoryEntry::encode_short_name("a b").is_err());
+    }
+
+    #[test]
+    fn test_attributes_formatting() {
+        let attrs = FileAttributes(FileAttributes::DIRECTORY | FileAttributes::ARCHIVE);
+        assert_eq!(alloc::format!("{:x}", attrs), "0x30");
+        assert_eq!(alloc::format!("{:b}", attrs), "0b00110000");
+        assert_eq!(attrs.describe(), "DIRECTORY | ARCHIVE");
+        assert_eq!(FileAttributes(0).describe(), "NONE");
+        assert_eq!(FileAttributes(FileAttributes::LONG_NAME).describe(), "LONG_NAME");
+    }
+
+    #[test]
+    fn test_attribute_operators() {
+        let read_only = FileAttributes(FileAttributes::READ_ONLY);
+        let hidden = FileAttributes(FileAttributes::HIDDEN);
+
+        let mut attrs = read_only | hidden;
+        assert_eq!(attrs, FileAttributes(0x03));
+        assert_eq!(attrs & hidden, hidden);
+        assert_eq!(attrs & FileAttributes(FileAttributes::SYSTEM), FileAttributes(0));
+
+        attrs |= FileAttributes(FileAttributes::ARCHIVE);
+        attrs &= !read_only;
+        assert_eq!(attrs.describe(), "HIDDEN | ARCHIVE");
+        assert_eq!(!FileAttributes(0), FileAttributes(0xFF));
+    }
+
+    #[test]
+    fn test_entry_equality() {
+        let name = DirectoryEntry::encode_short_name("a.txt").unwrap();
+        let entry = DirectoryEntry::new(name, FileAttributes::ARCHIVE, 5, 100);
+
+        assert_eq!(entry, unsafe { DirectoryEntry::from_bytes(&entry.to_bytes()) });
+        assert_ne!(entry, DirectoryEntry::new(name, FileAttributes::ARCHIVE, 5, 101));
+        assert!(alloc::vec![entry].contains(&entry));
+    }
+
+    #[test]
+    fn test_update_timestamps() {
+        let name = DirectoryEntry::encode_short_name("a.txt").unwrap();
+        let mut entry = DirectoryEntry::new(name, FileAttributes::ARCHIVE, 5, 100);
+        let date = FatDate::new(2024, 2, 29);
+        let time = FatTime::new(12, 34, 56);
+
+        entry.update_write_timestamp(date, time);
+        entry.update_access_date(FatDate::new(2024, 3, 1));
+        entry.update_creation_timestamp(FatDate::new(2023, 1, 2), FatTime::new(8, 0, 0));
+
+        assert_eq!((entry.write_date(), entry.write_time()), (date, time));
+        assert_eq!(entry.last_access_date(), FatDate::new(2024, 3, 1));
+        assert_eq!(entry.creation_date(), FatDate::new(2023, 1, 2));
+        assert_eq!(entry.creation_time(), FatTime::new(8, 0, 0));
+        // Octets 22..26 : heure puis date d'écriture, little-endian
+        let bytes = entry.to_bytes();
+        assert_eq!(u16::from_le_bytes([bytes[22], bytes[23]]), time.0);
+        assert_eq!(u16::from_le_bytes([bytes[24], bytes[25]]), date.0);
+        assert_eq!(entry.file_size(), 100);
+    }
+
+    #[test]
+    fn test_fields_at_odd_offset() {
+        // Entrée à une adresse impaire dans le tampon
+        let mut raw = [0u8; 33];
+        raw[1..12].copy_from_slice(b"DATA    BIN");
+        raw[12] = FileAttributes::ARCHIVE;
+        raw[21..23].copy_from_slice(&0x0012u16.to_le_bytes());
+        raw[27..29].copy_from_slice(&0x3456u16.to_le_bytes());
+        raw[29..33].copy_from_slice(&70_000u32.to_le_bytes());
+
+        let mut entry = unsafe { DirectoryEntry::from_bytes(&raw[1..]) };
+        assert_eq!(entry.first_cluster(), 0x0012_3456);
+        assert_eq!(entry.file_size(), 70_000);
+        assert!(!entry.is_dot() && !entry.is_dot_dot());
+
+        entry.set_first_cluster(0x0ABC_DEF0);
+        entry.set_file_size(5);
+        let bytes = entry.to_bytes();
+        assert_eq!(bytes[20..22], 0x0ABCu16.to_le_bytes());
+        assert_eq!(bytes[26..28], 0xDEF0u16.to_le_bytes());
+        assert_eq!(bytes[28..32], 5u32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_entry_hash() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        let name = DirectoryEntry::encode_short_name("a.txt").unwrap();
+        let entry = DirectoryEntry::new(name, FileAttributes::ARCHIVE, 5, 100);
+
+        let mut set = HashSet::new();
+        assert!(set.insert(entry));
+        assert!(!set.insert(unsafe { DirectoryEntry::from_bytes(&entry.to_bytes()) }));
+        assert!(set.insert(DirectoryEntry::new(name, FileAttributes::ARCHIVE, 6, 100)));
+    }
+
+    #[test]
+    fn test_fat_date_time_encoding() {
+        let date = FatDate::new(2024, 2, 29);
+        assert_eq!((date.year(), date.month(), date.day()), (2024, 2, 29));
+        assert_eq!(date.0, (44 << 9) | (2 << 5) | 29);
+
+        let time = FatTime::new(23, 59, 59);
+        assert_eq!((time.hour(), time.minute(), time.second()), (23, 59, 58));
+        assert_eq!(FatDate::new(1970, 1, 1).year(), 1980);
+
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert_eq!(time.to_string(), "23:59:58");
+        let timestamp = FatTimestamp::new(FatDate::new(1980, 1, 1), FatTime::new(7, 5, 3));
+        assert_eq!(timestamp.to_string(), "1980-01-01T07:05:02");
+    }
+
+    #[test]
+    fn test_unix_conversions() {
+        // 2024-02-29 12:34:56 UTC
+        let timestamp = FatTimestamp::from_unix_seconds(1_709_210_096);
+        assert_eq!(timestamp.date, FatDate::new(2024, 2, 29));
+        assert_eq!(timestamp.time, FatTime::new(12, 34, 56));
+        assert_eq!(timestamp.to_unix_seconds(), 1_709_210_096);
+        assert_eq!(FatTimestamp::from_unix_seconds(1_709_210_097).to_unix_seconds(), 1_709_210_096);
+
+        // Bornes
+        assert_eq!(FatTimestamp::from_unix_seconds(0).date, FatDate::new(1980, 1, 1));
+        assert_eq!(FatTimestamp::from_unix_seconds(i64::MAX).date, FatDate::new(2107, 12, 31));
+        assert_eq!(FatDate::new(1980, 1, 1).to_unix_days(), 3652);
+
+        // Années bissextiles : 2000 oui, 2100 non
+        let epoch = FatDate::new(1980, 1, 1);
+        for date in [
+            FatDate::new(1980, 1, 1),
+            FatDate::new(2000, 2, 29),
+            FatDate::new(2000, 3, 1),
+            FatDate::new(2100, 2, 28),
+            FatDate::new(2100, 3, 1),
+            FatDate::new(2107, 12, 31),
+        ] {
+            let days = date.to_days_since_epoch(epoch);
+            assert_eq!(FatDate::from_days_since_epoch(days, epoch), date);
+        }
+        let day_before = |y, m, d| FatDate::new(y, m, d).to_days_since_epoch(FatDate::new(2100, 3, 1));
+        assert_eq!(day_before(2100, 2, 28), -1);
+        assert_eq!(FatDate::new(2000, 3, 1).to_days_since_epoch(FatDate::new(2000, 2, 28)), 2);
+    }
+
+    /// Entrée de nom long numéro `order` portant `text` (13 caractères max)
+    fn lfn_entry(order: u8, checksum: u8, text: &str) -> [u8; 32] {
+        let mut units: Vec<u16> = text.encode_utf16().collect();
+        if units.len() < 13 {
+            units.push(0);
+        }
+        units.resize(13, 0xFFFF);
+
+        let mut raw = [0u8; 32];
+        raw[0] = order;
+        raw[11] = 0x0F;
+        raw[13] = checksum;
+        let offsets = (1..11).step_by(2).chain((14..26).step_by(2)).chain((28..32).step_by(2));
+        for (unit, offset) in units.iter().zip(offsets) {
+            raw[offset..offset + 2].copy_from_slice(&unit.to_le_bytes());
+        }
+        raw
+    }
+
+    #[test]
+    fn test_long_name_builder() {
+        let short = DirectoryEntry::new(*b"README  TXT", 0x20, 0, 0);
+        let sum = lfn_checksum(b"README  TXT");
+        assert_eq!(sum, 0x73);
+
+        // "Un nom très long.txt" : 20 caractères, deux entrées
+        let mut builder = LongNameBuilder::default();
+        builder.push(&lfn_entry(0x42, sum, "ong.txt"));
+        builder.push(&lfn_entry(0x01, sum, "Un nom très l"));
+        assert_eq!(builder.finish(&short).as_deref(), Some("Un nom très long.txt"));
+
+        // Somme de contrôle fausse
+        builder.push(&lfn_entry(0x41, sum ^ 1, "autre.txt"));
+        assert_eq!(builder.finish(&short), None);
+
+        // Séquence incomplète (l'entrée 1 manque)
+        builder.push(&lfn_entry(0x42, sum, "suite"));
+        assert_eq!(builder.finish(&short), None);
+    }
+}
@@ -1,33 +1,523 @@
+use core::convert::Infallible;
 use core::fmt;
+use crate::path::{components, last_component, NameError};
 
-pub type Result<T> = core::result::Result<T, Fat32Error>;
+/// Nature d'un accès au device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IoOp {
+    Read,
+    Write,
+    Flush,
+}
+
+impl fmt::Display for IoOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "lecture"),
+            Self::Write => write!(f, "écriture"),
+            Self::Flush => write!(f, "flush"),
+        }
+    }
+}
+
+/// Défaut d'une entrée de répertoire (voir `CorruptionKind`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EntryDefect {
+    /// Fichier non vide sans premier cluster
+    MissingCluster,
+    /// Chaîne plus courte que la taille du fichier
+    ChainTooShort,
+}
+
+impl fmt::Display for EntryDefect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingCluster => write!(f, "fichier non vide sans cluster"),
+            Self::ChainTooShort => write!(f, "chaîne plus courte que la taille"),
+        }
+    }
+}
+
+/// Nature d'une corruption détectée (voir `Fat32Error::Corrupted`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum CorruptionKind {
+    /// Chaîne qui boucle : plus longue que le volume en arrivant au
+    /// cluster `at`
+    ChainCycle { at: u32 },
+    /// Entrée de FAT libre ou réservée au cluster `at`, au milieu d'une chaîne
+    ChainBroken { at: u32 },
+    /// Numéro de cluster hors du volume, lu dans la FAT ou une entrée
+    ClusterOutOfRange { value: u32 },
+    /// Répertoire d'au moins `clusters` clusters, plus que la limite FAT
+    DirectoryTooLarge { clusters: u32 },
+    /// Entrée de répertoire incohérente
+    DirectoryEntryInvalid { reason: EntryDefect },
+    /// Copie `copy` de la FAT (1 pour la deuxième) différente de la première
+    /// à son secteur `sector`
+    FatMismatch { copy: u8, sector: u32 },
+    /// Compteur de clusters libres de FSInfo trop loin de celui de la FAT
+    FreeCountMismatch { recorded: u32, actual: u32 },
+}
+
+impl fmt::Display for CorruptionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ChainCycle { at } => write!(f, "chaîne qui boucle (cluster {})", at),
+            Self::ChainBroken { at } => write!(f, "chaîne interrompue au cluster {}", at),
+            Self::ClusterOutOfRange { value } => write!(f, "cluster {} hors du volume", value),
+            Self::DirectoryTooLarge { clusters } => {
+                write!(f, "répertoire d'au moins {} clusters", clusters)
+            }
+            Self::DirectoryEntryInvalid { reason } => {
+                write!(f, "entrée de répertoire invalide ({})", reason)
+            }
+            Self::FatMismatch { copy, sector } => {
+                write!(f, "FAT {} différente de la FAT 1 au secteur {}", copy + 1, sector)
+            }
+            Self::FreeCountMismatch { recorded, actual } => write!(
+                f,
+                "FSInfo annonce {} clusters libres, la FAT en compte {}",
+                recorded, actual
+            ),
+        }
+    }
+}
+
+/// Nom du composant introuvable, copié dans `Fat32Error::NotFound`
+///
+/// Copie bornée (`CAPACITY` octets, coupée entre deux caractères) pour que
+/// l'erreur reste `Copy` et sans allocation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentName {
+    bytes: [u8; ComponentName::CAPACITY],
+    len: u8,
+    truncated: bool,
+}
+
+impl ComponentName {
+    /// Octets gardés au plus
+    pub const CAPACITY: usize = 32;
+
+    /// Copier `name`, tronqué à `CAPACITY` octets
+    pub fn new(name: &str) -> Self {
+        let mut len = name.len().min(Self::CAPACITY);
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut bytes = [0; Self::CAPACITY];
+        bytes[..len].copy_from_slice(&name.as_bytes()[..len]);
+        Self { bytes, len: len as u8, truncated: len < name.len() }
+    }
+
+    /// Le nom copié (son début s'il a été tronqué)
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+
+    /// Le nom d'origine dépassait `CAPACITY` octets
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl fmt::Debug for ComponentName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())?;
+        if self.truncated {
+            write!(f, "…")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ComponentName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())?;
+        if self.truncated {
+            write!(f, "…")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ComponentName {
+    fn format(&self, f: defmt::Formatter) {
+        let ellipsis = if self.truncated { "…" } else { "" };
+        defmt::write!(f, "{=str}{=str}", self.as_str(), ellipsis);
+    }
+}
+
+/// Catégorie d'une erreur, pour décider quoi faire (voir `Fat32Error::kind`)
+///
+/// La catégorie de chaque variante existante ne changera pas ; une nouvelle
+/// variante rejoindra l'une de ces catégories ou une nouvelle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Fichier ou dossier absent : `NotFound`
+    NotFound,
+    /// Appel incorrect, à corriger chez l'appelant : `InvalidPath`,
+    /// `NotADirectory`, `InvalidCluster`, `EndOfChain`, `BufferTooSmall`,
+    /// `AlreadyExists`, `NameTooLong`, `InvalidName`, `InvalidUtf8`,
+    /// `InvalidInput`, `DirectoryNotEmpty`, `SectorSizeMismatch`
+    InvalidInput,
+    /// Volume illisible ou incohérent, à réparer ou reformater :
+    /// `InvalidBootSector`, `InvalidPartition`, `InvalidEntry`, `Corrupted`
+    Corruption,
+    /// Accès au device en échec, peut-être passager : `IoError`, `Device`
+    Io,
+    /// Plus de place sur le volume : `DiskFull`
+    NoSpace,
+    /// Écriture refusée : `ReadOnly` (device), `ReadOnlyFilesystem`
+    ReadOnly,
+}
+
+/// `E` est le type d'erreur du device (`BlockDevice::Error`)
+pub type Result<T, E = Infallible> = core::result::Result<T, Fat32Error<E>>;
 
 /// Les différentes erreurs possibles
+///
+/// `E` est l'erreur propre au device, gardée telle quelle dans `Device`.
+/// Les erreurs qui ne viennent pas d'un device sont des `Fat32Error` (avec
+/// `E = Infallible`), à convertir avec `widen`. De nouvelles variantes
+/// peuvent apparaître : un `match` doit avoir un cas `_`, ou passer par
+/// `kind`.
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Fat32Error {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Fat32Error<E = Infallible> {
     InvalidBootSector,
     InvalidCluster,
-    InvalidPath,
-    NotFound,
+    /// Chemin invalide : `component` est l'index (à partir de 0) du
+    /// composant en cause (voir `component`)
+    InvalidPath { component: u16 },
+    /// Fichier ou dossier absent : `component` est l'index (à partir de 0)
+    /// du premier composant introuvable (voir `component`), `name` son nom
+    NotFound { component: u16, name: ComponentName },
     NotADirectory,
     EndOfChain,
-    IoError,
+    /// Accès au device impossible (hors limites, panne) : `sector` est le
+    /// premier secteur de l'accès, 0 pour un flush
+    IoError { op: IoOp, sector: u32 },
     BufferTooSmall,
     InvalidEntry,
+    AlreadyExists,
+    DiskFull,
+    NameTooLong,
+    InvalidName(NameError),
+    InvalidUtf8,
+    /// Incohérence sur le volume, avec son diagnostic
+    Corrupted(CorruptionKind),
+    InvalidInput,
+    ReadOnly,
+    InvalidPartition,
+    /// Écriture refusée par un système de fichiers en lecture seule
+    ReadOnlyFilesystem,
+    /// Suppression d'un répertoire qui contient encore des entrées
+    DirectoryNotEmpty,
+    /// Taille de secteur du device différente de celle du boot sector
+    ///
+    /// Si les secteurs du device sont un multiple de ceux du volume, voir
+    /// `devices::SectorTranslator`.
+    SectorSizeMismatch { device: usize, volume: u16 },
+    /// Erreur remontée par le device
+    Device(E),
+}
+
+impl<E> Fat32Error<E> {
+    /// Catégorie de l'erreur (la table est dans `ErrorKind`)
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NotFound { .. } => ErrorKind::NotFound,
+            Self::InvalidPath { .. }
+            | Self::NotADirectory
+            | Self::InvalidCluster
+            | Self::EndOfChain
+            | Self::BufferTooSmall
+            | Self::AlreadyExists
+            | Self::NameTooLong
+            | Self::InvalidName(_)
+            | Self::InvalidUtf8
+            | Self::InvalidInput
+            | Self::DirectoryNotEmpty
+            | Self::SectorSizeMismatch { .. } => ErrorKind::InvalidInput,
+            Self::InvalidBootSector
+            | Self::InvalidPartition
+            | Self::InvalidEntry
+            | Self::Corrupted(_) => ErrorKind::Corruption,
+            Self::IoError { .. } | Self::Device(_) => ErrorKind::Io,
+            Self::DiskFull => ErrorKind::NoSpace,
+            Self::ReadOnly | Self::ReadOnlyFilesystem => ErrorKind::ReadOnly,
+        }
+    }
+
+    /// `kind() == ErrorKind::NotFound`
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+
+    /// `kind() == ErrorKind::InvalidInput`
+    pub fn is_invalid_input(&self) -> bool {
+        self.kind() == ErrorKind::InvalidInput
+    }
+
+    /// `kind() == ErrorKind::Corruption`
+    pub fn is_corruption(&self) -> bool {
+        self.kind() == ErrorKind::Corruption
+    }
+
+    /// `kind() == ErrorKind::Io`
+    pub fn is_io(&self) -> bool {
+        self.kind() == ErrorKind::Io
+    }
+
+    /// Index du composant du chemin en cause (`NotFound` et `InvalidPath`)
+    pub fn component_index(&self) -> Option<usize> {
+        match self {
+            Self::NotFound { component, .. } | Self::InvalidPath { component } => {
+                Some(*component as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Composant en cause dans `path`, le chemin passé à l'appel en échec
+    ///
+    /// Les composants sont comptés sans les vides ni les "." : pour
+    /// "/logs//2024/./jan", "jan" est le composant 2.
+    pub fn component<'p>(&self, path: &'p str) -> Option<&'p str> {
+        components(path).nth(self.component_index()?)
+    }
+
+    /// `NotFound` pour le dernier composant de `path`
+    pub(crate) fn not_found(path: &str) -> Self {
+        let name = ComponentName::new(components(path).last().unwrap_or(""));
+        Self::NotFound { component: last_component(path), name }
+    }
+
+    /// `NotFound` pour le composant `component`, nommé `name`
+    pub(crate) fn not_found_at(component: u16, name: &str) -> Self {
+        Self::NotFound { component, name: ComponentName::new(name) }
+    }
+
+    /// `InvalidPath` pour le dernier composant de `path`
+    pub(crate) fn invalid_path(path: &str) -> Self {
+        Self::InvalidPath { component: last_component(path) }
+    }
+
+    /// `Corrupted` pour une entrée de répertoire incohérente
+    pub(crate) fn bad_entry(reason: EntryDefect) -> Self {
+        Self::Corrupted(CorruptionKind::DirectoryEntryInvalid { reason })
+    }
+
+    /// Même erreur, rapportée au composant `component` d'un chemin
+    pub(crate) fn at_component(self, component: u16) -> Self {
+        match self {
+            Self::NotFound { name, .. } => Self::NotFound { component, name },
+            Self::InvalidPath { .. } => Self::InvalidPath { component },
+            other => other,
+        }
+    }
 }
 
-impl fmt::Display for Fat32Error {
+impl Fat32Error {
+    /// Même erreur, pour un device dont l'erreur est `E`
+    pub fn widen<E>(self) -> Fat32Error<E> {
+        match self {
+            Self::InvalidBootSector => Fat32Error::InvalidBootSector,
+            Self::InvalidCluster => Fat32Error::InvalidCluster,
+            Self::InvalidPath { component } => Fat32Error::InvalidPath { component },
+            Self::NotFound { component, name } => Fat32Error::NotFound { component, name },
+            Self::NotADirectory => Fat32Error::NotADirectory,
+            Self::EndOfChain => Fat32Error::EndOfChain,
+            Self::IoError { op, sector } => Fat32Error::IoError { op, sector },
+            Self::BufferTooSmall => Fat32Error::BufferTooSmall,
+            Self::InvalidEntry => Fat32Error::InvalidEntry,
+            Self::AlreadyExists => Fat32Error::AlreadyExists,
+            Self::DiskFull => Fat32Error::DiskFull,
+            Self::NameTooLong => Fat32Error::NameTooLong,
+            Self::InvalidName(reason) => Fat32Error::InvalidName(reason),
+            Self::InvalidUtf8 => Fat32Error::InvalidUtf8,
+            Self::Corrupted(kind) => Fat32Error::Corrupted(kind),
+            Self::InvalidInput => Fat32Error::InvalidInput,
+            Self::ReadOnly => Fat32Error::ReadOnly,
+            Self::InvalidPartition => Fat32Error::InvalidPartition,
+            Self::ReadOnlyFilesystem => Fat32Error::ReadOnlyFilesystem,
+            Self::DirectoryNotEmpty => Fat32Error::DirectoryNotEmpty,
+            Self::SectorSizeMismatch { device, volume } => {
+                Fat32Error::SectorSizeMismatch { device, volume }
+            }
+            Self::Device(never) => match never {},
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for Fat32Error<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::InvalidBootSector => write!(f, "Boot sector invalide"),
             Self::InvalidCluster => write!(f, "Numéro de cluster invalide"),
-            Self::InvalidPath => write!(f, "Chemin invalide"),
-            Self::NotFound => write!(f, "Fichier ou dossier non trouvé"),
+            Self::InvalidPath { component } => {
+                write!(f, "Chemin invalide (composant {})", component + 1)
+            }
+            Self::NotFound { component, name } => {
+                write!(f, "Composant {} ('{}') du chemin non trouvé", component + 1, name)
+            }
             Self::NotADirectory => write!(f, "Ce n'est pas un dossier"),
             Self::EndOfChain => write!(f, "Fin de la chaîne"),
-            Self::IoError => write!(f, "Erreur d'entrée/sortie"),
+            Self::IoError { op: IoOp::Flush, .. } => write!(f, "Erreur d'entrée/sortie (flush)"),
+            Self::IoError { op, sector } => {
+                write!(f, "Erreur d'entrée/sortie ({} du secteur {})", op, sector)
+            }
             Self::BufferTooSmall => write!(f, "Buffer trop petit"),
             Self::InvalidEntry => write!(f, "Entrée invalide"),
+            Self::AlreadyExists => write!(f, "Le fichier existe déjà"),
+            Self::DiskFull => write!(f, "Disque plein"),
+            Self::NameTooLong => write!(f, "Nom trop long"),
+            Self::InvalidName(reason) => write!(f, "Nom invalide : {}", reason),
+            Self::InvalidUtf8 => write!(f, "Texte UTF-8 invalide"),
+            Self::Corrupted(kind) => write!(f, "Système de fichiers corrompu : {}", kind),
+            Self::InvalidInput => write!(f, "Paramètres invalides"),
+            Self::ReadOnly => write!(f, "Device en lecture seule"),
+            Self::InvalidPartition => write!(f, "Table de partitions invalide"),
+            Self::ReadOnlyFilesystem => write!(f, "Système de fichiers en lecture seule"),
+            Self::DirectoryNotEmpty => write!(f, "Le dossier n'est pas vide"),
+            Self::SectorSizeMismatch { device, volume } => write!(
+                f,
+                "Secteurs de {} octets sur le device, {} dans le boot sector",
+                device, volume
+            ),
+            Self::Device(error) => write!(f, "Erreur du device : {:?}", error),
+        }
+    }
+}
+
+/// `source` rend l'erreur du device gardée dans `Device`, `None` sinon
+///
+/// L'erreur du device doit donc implémenter `Error` elle aussi : c'est le cas
+/// d'`Infallible` et d'`IoErrorKind` (devices `std`).
+impl<E: core::error::Error + 'static> core::error::Error for Fat32Error<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Device(error) => Some(error),
+            _ => None,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Erreur des devices `std` (`FileDevice`, `IoDevice`, `MmapDevice`)
+///
+/// L'`io::ErrorKind` de l'erreur d'origine, dans un type qui implémente
+/// `Error` (ce que ne fait pas `io::ErrorKind`) pour que `source` la rende.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IoErrorKind(pub std::io::ErrorKind);
+
+#[cfg(feature = "std")]
+impl fmt::Display for IoErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for IoErrorKind {}
+
+#[cfg(feature = "std")]
+impl From<std::io::ErrorKind> for IoErrorKind {
+    fn from(kind: std::io::ErrorKind) -> Self {
+        Self(kind)
+    }
+}
+
+/// Erreur d'entrée/sortie de l'hôte, vue comme une erreur de device
+///
+/// Un `io::Error` construit depuis un `Fat32Error` rend l'erreur d'origine ;
+/// `NotFound` donne `NotFound` (composant 0, sans nom : l'hôte ne le donne
+/// pas), les autres `Device` avec leur `ErrorKind`. C'est la seule conversion
+/// depuis `io::Error` : les devices `std` passent par elle (voir `from_io_at`).
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Fat32Error<IoErrorKind> {
+    fn from(error: std::io::Error) -> Self {
+        let error = match error.downcast::<Self>() {
+            Ok(original) => return original,
+            Err(error) => error,
+        };
+        match error.downcast::<Fat32Error>() {
+            Ok(original) => original.widen(),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Self::NotFound { component: 0, name: ComponentName::new("") }
+            }
+            Err(error) => Self::Device(IoErrorKind(error.kind())),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Fat32Error<IoErrorKind> {
+    /// Erreur d'un device `std::io` pendant l'accès `op` au secteur `sector`
+    ///
+    /// Comme `From<io::Error>`, sauf qu'une fin prématurée (`UnexpectedEof`)
+    /// est un accès hors limites : `IoError { op, sector }`.
+    pub fn from_io_at(error: std::io::Error, op: IoOp, sector: u32) -> Self {
+        match Self::from(error) {
+            Self::Device(IoErrorKind(std::io::ErrorKind::UnexpectedEof)) => {
+                Self::IoError { op, sector }
+            }
+            error => error,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> Fat32Error<E> {
+    /// `io::ErrorKind` correspondant à la catégorie (voir `From<Fat32Error>`)
+    fn io_kind(&self, device: impl FnOnce(&E) -> std::io::ErrorKind) -> std::io::ErrorKind {
+        use std::io::ErrorKind as Io;
+        match (self, self.kind()) {
+            (Self::Device(error), _) => device(error),
+            (_, ErrorKind::NotFound) => Io::NotFound,
+            (_, ErrorKind::InvalidInput) => Io::InvalidInput,
+            (_, ErrorKind::Corruption) => Io::InvalidData,
+            (_, ErrorKind::ReadOnly) => Io::PermissionDenied,
+            (_, ErrorKind::NoSpace) => Io::StorageFull,
+            _ => Io::Other,
+        }
+    }
+}
+
+/// Vers `io::Error`, l'erreur d'origine gardée dedans (`get_ref`, `downcast`)
+///
+/// `NotFound` pour un fichier absent, `InvalidInput` pour un mauvais appel,
+/// `InvalidData` pour un volume corrompu, `PermissionDenied` en lecture
+/// seule, `StorageFull` pour un disque plein, `Other` sinon.
+#[cfg(feature = "std")]
+impl From<Fat32Error> for std::io::Error {
+    fn from(error: Fat32Error) -> Self {
+        std::io::Error::new(error.io_kind(|never| match *never {}), error)
+    }
+}
+
+/// Comme pour `Fat32Error`, et `Device` garde son `ErrorKind`
+#[cfg(feature = "std")]
+impl From<Fat32Error<IoErrorKind>> for std::io::Error {
+    fn from(error: Fat32Error<IoErrorKind>) -> Self {
+        std::io::Error::new(error.io_kind(|kind| kind.0), error)
+    }
+}
+
+impl<E> From<NameError> for Fat32Error<E> {
+    fn from(error: NameError) -> Self {
+        match error {
+            NameError::TooLong => Self::NameTooLong,
+            other => Self::InvalidName(other),
+        }
+    }
+}
@@ -2,140 +2,1376 @@
 
 use alloc::vec::Vec;
 use alloc::vec;  // ← Import de la macro vec!
-use crate::{BlockDevice, BootSector, Fat32Error, Result};
+use crate::boot_sector::FAT_EOC;
+use crate::cache::{AlignedBuf, SectorBuf, SectorCache};
+use crate::devices::{check_bounds, read_sector_aligned, read_sectors_aligned, write_sector_aligned};
+use crate::{BlockDevice, BootSector, CorruptionKind, DirectoryEntry, Fat32Error, IoOp, Result};
 
-/// Gère la lecture de la File Allocation Table
+/// Nombre de secteurs de FAT gardés en mémoire
+pub(crate) const FAT_CACHE_SECTORS: usize = 8;
+
+/// Valeur d'une entrée de FAT marquant un cluster défectueux
+const FAT_BAD: u32 = 0x0FFFFFF7;
+
+/// Bit de l'entrée 1 de la FAT : volume démonté proprement
+const VOLUME_CLEAN: u32 = 0x08000000;
+
+/// Position (secteur, offset) de l'entrée d'un cluster dans la FAT1
+///
+/// Les fonctions libres de ce module ne font aucune lecture : elles sont
+/// partagées par `FatTable` et le système de fichiers asynchrone.
+pub(crate) fn entry_position(boot_sector: &BootSector, cluster: u32) -> (u32, usize) {
+    // Calculer l'offset dans la FAT
+    let fat_offset = cluster * 4;
+    let bytes_per_sec = boot_sector.bytes_per_sector() as u32;
+    let fat_sector = boot_sector.first_fat_sector() + fat_offset / bytes_per_sec;
+    let entry_offset = (fat_offset % bytes_per_sec) as usize;
+
+    (fat_sector, entry_offset)
+}
+
+/// Valeur d'une entrée lue à `offset` dans un secteur de FAT
+pub(crate) fn decode_entry(sector: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        sector[offset],
+        sector[offset + 1],
+        sector[offset + 2],
+        sector[offset + 3],
+    ]) & 0x0FFFFFFF // Seulement 28 bits utilisés
+}
+
+/// Interpréter l'entrée d'un cluster : cluster suivant de la chaîne
+///
+/// `entry` est celle de `cluster`. `EndOfChain` en fin de chaîne ; une
+/// entrée libre ou réservée (`ChainBroken`) ou un suivant au-delà de
+/// `max_cluster` (`ClusterOutOfRange`) sont des corruptions.
+pub(crate) fn next_from_entry<E>(cluster: u32, entry: u32, max_cluster: u32) -> Result<u32, E> {
+    match entry {
+        0x0FFFFFF8..=0x0FFFFFFF => Err(Fat32Error::EndOfChain),
+        0x00000000 | 0x00000001 => Err(Fat32Error::Corrupted(CorruptionKind::ChainBroken {
+            at: cluster,
+        })),
+        next if next > max_cluster => {
+            Err(Fat32Error::Corrupted(CorruptionKind::ClusterOutOfRange { value: next }))
+        }
+        next => Ok(next),
+    }
+}
+
+/// État d'un cluster d'après son entrée de FAT
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClusterState {
+    Free,
+    /// Alloué, suivi de ce cluster dans la chaîne
+    Next(u32),
+    /// Alloué, dernier de sa chaîne
+    EndOfChain,
+    /// Marqué défectueux (`0x0FFFFFF7`)
+    Bad,
+    /// Alloué mais l'entrée ne désigne aucun cluster du volume (valeur
+    /// réservée ou hors limites) : FAT corrompue
+    Invalid(u32),
+}
+
+impl ClusterState {
+    /// Interpréter l'entrée brute (28 bits) d'un volume dont le dernier
+    /// cluster est `max_cluster`
+    pub fn from_entry(entry: u32, max_cluster: u32) -> Self {
+        match entry {
+            0 => Self::Free,
+            FAT_BAD => Self::Bad,
+            0x0FFFFFF8..=0x0FFFFFFF => Self::EndOfChain,
+            next @ 2.. if next <= max_cluster => Self::Next(next),
+            other => Self::Invalid(other),
+        }
+    }
+
+    /// Le cluster appartient-il à une chaîne (ni libre, ni défectueux) ?
+    pub fn is_used(&self) -> bool {
+        !matches!(self, Self::Free | Self::Bad)
+    }
+}
+
+/// Copie de la FAT1 en mémoire (voir `FatTable::snapshot_fat`)
+///
+/// S'indexe par numéro de cluster : `snapshot[cluster]` est l'entrée brute
+/// sur 28 bits, 0 (libre) au-delà de la FAT.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FatSnapshot {
+    entries: Vec<u32>,
+    max_cluster: u32,
+}
+
+impl FatSnapshot {
+    /// État d'un cluster (`Free` au-delà de la FAT)
+    pub fn state_of(&self, cluster: u32) -> ClusterState {
+        ClusterState::from_entry(self[cluster], self.max_cluster)
+    }
+
+    /// Le cluster est-il libre ?
+    pub fn is_free(&self, cluster: u32) -> bool {
+        self[cluster] == 0
+    }
+
+    /// Toutes les entrées, 0 et 1 comprises
+    pub fn entries(&self) -> &[u32] {
+        &self.entries
+    }
+
+    /// Récupérer les entrées
+    pub fn into_inner(self) -> Vec<u32> {
+        self.entries
+    }
+}
+
+impl core::ops::Index<u32> for FatSnapshot {
+    type Output = u32;
+
+    fn index(&self, cluster: u32) -> &u32 {
+        self.entries.get(cluster as usize).unwrap_or(&0)
+    }
+}
+
+/// Résultat de `FatTable::check_integrity`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IntegrityReport {
+    /// Premier cluster des entrées dont la chaîne partage des clusters avec une autre
+    pub cross_linked: Vec<u32>,
+    /// Clusters alloués dans la FAT qu'aucune entrée n'atteint (clusters perdus)
+    pub unreferenced: Vec<u32>,
+    /// Clusters atteints plusieurs fois
+    pub multiply_referenced: Vec<u32>,
+}
+
+impl IntegrityReport {
+    /// Aucune anomalie trouvée
+    pub fn is_clean(&self) -> bool {
+        self.cross_linked.is_empty()
+            && self.unreferenced.is_empty()
+            && self.multiply_referenced.is_empty()
+    }
+}
+
+/// État de la FAT gardé entre deux opérations
+///
+/// Contient les secteurs de la FAT1 lus ou modifiés (les modifications
+/// restent en mémoire jusqu'à `FatTable::flush`) et les compteurs du
+/// secteur FSInfo.
+pub struct FatCache {
+    sectors: SectorCache,
+    free_count: Option<u32>,
+    next_free: Option<u32>,
+    info_dirty: bool,
+}
+
+impl FatCache {
+    pub fn new() -> Self {
+        Self {
+            sectors: SectorCache::new(FAT_CACHE_SECTORS),
+            free_count: None,
+            next_free: None,
+            info_dirty: false,
+        }
+    }
+
+    /// Initialiser les compteurs lus dans le secteur FSInfo
+    pub fn set_fs_info(&mut self, free_count: Option<u32>, next_free: Option<u32>) {
+        self.free_count = free_count;
+        self.next_free = next_free;
+        self.info_dirty = false;
+    }
+
+    /// Nombre de clusters libres connu (FSInfo tenu à jour)
+    pub fn free_count(&self) -> Option<u32> {
+        self.free_count
+    }
+
+    /// Remplacer le compteur de clusters libres par la valeur comptée dans la FAT
+    ///
+    /// Le secteur FSInfo sera réécrit au prochain `flush` si la valeur change.
+    pub(crate) fn correct_free_count(&mut self, free: u32) {
+        if self.free_count != Some(free) {
+            self.free_count = Some(free);
+            self.info_dirty = true;
+        }
+    }
+
+    /// Prochain cluster libre probable
+    pub fn next_free(&self) -> Option<u32> {
+        self.next_free
+    }
+
+    /// Ne plus limiter le nombre de secteurs gardés (mode lot), ou revenir à la limite
+    pub(crate) fn set_unbounded(&mut self, unbounded: bool) {
+        let capacity = if unbounded { usize::MAX } else { FAT_CACHE_SECTORS };
+        self.sectors.set_capacity(capacity);
+    }
+
+    /// Des secteurs de la FAT attendent d'être écrits
+    pub fn is_dirty(&self) -> bool {
+        self.sectors.is_dirty()
+    }
+
+    /// Les compteurs FSInfo ont changé depuis la dernière écriture
+    pub fn is_info_dirty(&self) -> bool {
+        self.info_dirty
+    }
+
+    /// Marquer les compteurs FSInfo comme écrits
+    pub fn mark_info_clean(&mut self) {
+        self.info_dirty = false;
+    }
+
+    /// Oublier les secteurs non modifiés
+    pub fn clear(&mut self) {
+        self.sectors.clear_clean();
+    }
+}
+
+impl Default for FatCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gère la lecture et l'écriture de la File Allocation Table
+///
+/// Les modifications restent dans le `FatCache` jusqu'à `flush()` (ou jusqu'à
+/// ce qu'un secteur modifié soit évincé du cache).
 pub struct FatTable<'a, D: BlockDevice> {
     device: &'a mut D,
     boot_sector: &'a BootSector,
-    cache: Option<(u32, Vec<u8>)>,
+    cache: &'a mut FatCache,
 }
 
 impl<'a, D: BlockDevice> FatTable<'a, D> {
-    pub fn new(device: &'a mut D, boot_sector: &'a BootSector) -> Self {
+    pub fn new(device: &'a mut D, boot_sector: &'a BootSector, cache: &'a mut FatCache) -> Self {
         Self {
             device,
             boot_sector,
-            cache: None,
+            cache,
         }
     }
 
     /// Obtenir le cluster suivant dans la chaîne
-    pub fn next_cluster(&mut self, cluster: u32) -> Result<u32> {
+    ///
+    /// `InvalidCluster` pour `cluster` < 2, `Corrupted` si l'entrée ne mène
+    /// pas à un cluster valide (voir `CorruptionKind`).
+    pub fn next_cluster(&mut self, cluster: u32) -> Result<u32, D::Error> {
         // Les clusters commencent à 2
         if cluster < 2 {
             return Err(Fat32Error::InvalidCluster);
         }
 
-        // Calculer l'offset dans la FAT
-        let fat_offset = cluster * 4;
-        let bytes_per_sec = self.boot_sector.bytes_per_sector();
-        let fat_sector = self.boot_sector.first_fat_sector() 
-            + (fat_offset / bytes_per_sec as u32);
-        let entry_offset = (fat_offset % bytes_per_sec as u32) as usize;
+        let entry = self.read_entry(cluster)?;
+        next_from_entry(cluster, entry, self.max_cluster())
+    }
+
+    /// Lire l'entrée brute (28 bits) d'un cluster
+    pub fn read_entry(&mut self, cluster: u32) -> Result<u32, D::Error> {
+        let (fat_sector, entry_offset) = entry_position(self.boot_sector, cluster);
 
         // Lire le secteur de la FAT
         let sector_data = self.read_fat_sector(fat_sector)?;
+        Ok(decode_entry(sector_data, entry_offset))
+    }
 
-        // Lire l'entrée (4 octets)
-        let entry = u32::from_le_bytes([
-            sector_data[entry_offset],
-            sector_data[entry_offset + 1],
-            sector_data[entry_offset + 2],
-            sector_data[entry_offset + 3],
-        ]) & 0x0FFFFFFF; // Seulement 28 bits utilisés
+    /// Le volume a-t-il été démonté proprement ? (bit 27 de l'entrée 1 de la FAT)
+    pub fn is_volume_clean(&mut self) -> Result<bool, D::Error> {
+        let sector = self.boot_sector.first_fat_sector();
+        let data = self.read_fat_sector(sector)?;
+        Ok(u32::from_le_bytes([data[4], data[5], data[6], data[7]]) & VOLUME_CLEAN != 0)
+    }
 
-        // Interpréter la valeur
-        match entry {
-            0x0FFFFFF8..=0x0FFFFFFF => Err(Fat32Error::EndOfChain),
-            0x00000000 | 0x00000001 => Err(Fat32Error::InvalidCluster),
-            cluster => Ok(cluster),
+    /// Marquer le volume propre (démonté) ou en cours d'utilisation
+    pub fn set_volume_clean(&mut self, clean: bool) -> Result<(), D::Error> {
+        if self.is_volume_clean()? == clean {
+            return Ok(());
         }
+
+        let sector = self.boot_sector.first_fat_sector();
+        let Some(data) = self.cache.sectors.get_mut(sector) else {
+            unreachable!()
+        };
+        let entry = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) ^ VOLUME_CLEAN;
+        data[4..8].copy_from_slice(&entry.to_le_bytes());
+        Ok(())
     }
 
-    /// Lire un secteur de la FAT (avec cache)
-    fn read_fat_sector(&mut self, sector: u32) -> Result<&Vec<u8>> {
-        // Vérifier le cache
-        if let Some((cached_sector, _)) = &self.cache {
-            if *cached_sector == sector {
-                if let Some((_, ref data)) = self.cache {
-                    return Ok(data);
+    /// Modifier l'entrée d'un cluster
+    ///
+    /// Le changement est fait dans le cache ; `flush()` l'écrit dans toutes
+    /// les copies de la FAT.
+    pub fn write_entry(&mut self, cluster: u32, value: u32) -> Result<(), D::Error> {
+        if cluster < 2 || cluster > self.max_cluster() {
+            return Err(Fat32Error::InvalidCluster);
+        }
+
+        let (fat_sector, entry_offset) = entry_position(self.boot_sector, cluster);
+        self.read_fat_sector(fat_sector)?;
+
+        let Some(data) = self.cache.sectors.get_mut(fat_sector) else {
+            unreachable!()
+        };
+
+        // Les 4 bits de poids fort sont réservés et doivent être conservés
+        let old = u32::from_le_bytes([
+            data[entry_offset],
+            data[entry_offset + 1],
+            data[entry_offset + 2],
+            data[entry_offset + 3],
+        ]);
+        let new = (old & 0xF0000000) | (value & 0x0FFFFFFF);
+        data[entry_offset..entry_offset + 4].copy_from_slice(&new.to_le_bytes());
+
+        // Tenir à jour les compteurs FSInfo
+        let was_free = old & 0x0FFFFFFF == 0;
+        let is_free = value & 0x0FFFFFFF == 0;
+        if was_free != is_free {
+            if let Some(count) = self.cache.free_count.as_mut() {
+                if is_free {
+                    *count += 1;
+                } else {
+                    *count = count.saturating_sub(1);
                 }
             }
+            if !is_free {
+                self.cache.next_free = Some(cluster + 1);
+            }
+            self.cache.info_dirty = true;
         }
 
-        // Lire depuis le disque
-        let bytes_per_sec = self.boot_sector.bytes_per_sector();
-        let mut buffer = vec![0u8; bytes_per_sec as usize];
-        self.device.read_sector(sector, &mut buffer)?;
+        Ok(())
+    }
 
-        // Mettre en cache
-        self.cache = Some((sector, buffer));
+    /// Charger la FAT1 dans le cache, dans l'ordre des secteurs
+    ///
+    /// Si la FAT est plus grande que le cache, seuls ses premiers secteurs
+    /// (autant que de lignes de cache) sont chargés. Ce n'est qu'une
+    /// indication : les secteurs peuvent être évincés ensuite comme les autres.
+    pub fn preload(&mut self) -> Result<(), D::Error> {
+        let first = self.boot_sector.first_fat_sector();
+        let count = self.boot_sector.fat_size().min(self.cache.sectors.capacity() as u32);
+        let bytes_per_sec = self.boot_sector.bytes_per_sector() as usize;
 
-        if let Some((_, ref data)) = self.cache {
-            Ok(data)
-        } else {
-            unreachable!()
+        let align = self.device.buffer_alignment();
+        let mut data = AlignedBuf::zeroed(count as usize * bytes_per_sec, align);
+        check_bounds(self.device, IoOp::Read, first, count)?;
+        read_sectors_aligned(self.device, first, count, &mut data)?;
+
+        // Les secteurs déjà en cache (peut-être modifiés) sont gardés
+        for (sector, chunk) in (first..).zip(data.chunks(bytes_per_sec)) {
+            if self.cache.sectors.contains(sector) {
+                continue;
+            }
+            if let Some((evicted, old)) = self.cache.sectors.insert(sector, SectorBuf::from_slice(chunk), false) {
+                self.write_fat_sector(evicted - first, &old)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Écrire les secteurs modifiés dans toutes les copies de la FAT
+    ///
+    /// Pour chaque secteur, la FAT1 est écrite avant les copies suivantes.
+    pub fn flush(&mut self) -> Result<(), D::Error> {
+        let first = self.boot_sector.first_fat_sector();
+        for sector in self.cache.sectors.dirty_sectors() {
+            if let Some(data) = self.cache.sectors.peek(sector).map(SectorBuf::from_slice) {
+                self.write_fat_sector(sector - first, &data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Écrire un secteur de la FAT dans toutes ses copies, FAT1 en premier
+    ///
+    /// `fat_sector_offset` est relatif au début de la FAT1. Si le secteur est
+    /// en cache, la ligne prend `data` et n'est plus marquée modifiée. C'est
+    /// le seul chemin d'écriture de la FAT sur le device ; les compteurs
+    /// FSInfo ne sont pas recalculés.
+    pub fn write_fat_sector(
+        &mut self,
+        fat_sector_offset: u32,
+        data: &[u8],
+    ) -> Result<(), D::Error> {
+        if fat_sector_offset >= self.boot_sector.fat_size() {
+            return Err(Fat32Error::InvalidInput);
+        }
+        if data.len() != self.boot_sector.bytes_per_sector() as usize {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+
+        let sector = self.boot_sector.first_fat_sector() + fat_sector_offset;
+        let fat_size = self.boot_sector.fat_size();
+        let num_fats = self.boot_sector.num_fats as u32;
+        check_bounds(self.device, IoOp::Write, sector, (num_fats - 1) * fat_size + 1)?;
+        for fat in 0..num_fats {
+            write_sector_aligned(self.device, sector + fat * fat_size, data)?;
+        }
+
+        if let Some(line) = self.cache.sectors.get_mut(sector) {
+            line.copy_from_slice(data);
+            self.cache.sectors.mark_clean(sector);
+        }
+        Ok(())
+    }
+
+    /// Lire toute la FAT1 (modifications en attente comprises)
+    pub fn read_entire_fat(&mut self) -> Result<Vec<u8>, D::Error> {
+        let bytes_per_sec = self.boot_sector.bytes_per_sector() as usize;
+        let first = self.boot_sector.first_fat_sector();
+        let mut data = vec![0u8; self.boot_sector.fat_size() as usize * bytes_per_sec];
+        check_bounds(self.device, IoOp::Read, first, self.boot_sector.fat_size())?;
+
+        for (i, chunk) in data.chunks_mut(bytes_per_sec).enumerate() {
+            let sector = first + i as u32;
+            match self.cache.sectors.peek(sector) {
+                Some(cached) => chunk.copy_from_slice(cached),
+                None => read_sector_aligned(self.device, sector, chunk)?,
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Comparer sur le device chaque copie de la FAT à la FAT1
+    ///
+    /// `FatMismatch` pour le premier secteur qui diffère. Ne regarde pas le
+    /// cache : à appeler quand il ne garde aucune modification en attente.
+    pub fn check_copies(&mut self) -> Result<(), D::Error> {
+        let bytes_per_sec = self.boot_sector.bytes_per_sector() as usize;
+        let first = self.boot_sector.first_fat_sector();
+        let fat_size = self.boot_sector.fat_size();
+        let num_fats = self.boot_sector.num_fats as u32;
+        check_bounds(self.device, IoOp::Read, first, num_fats * fat_size)?;
+
+        let mut original = SectorBuf::new(bytes_per_sec);
+        let mut copy = SectorBuf::new(bytes_per_sec);
+        for sector in 0..fat_size {
+            read_sector_aligned(self.device, first + sector, &mut original)?;
+            for fat in 1..num_fats {
+                read_sector_aligned(self.device, first + fat * fat_size + sector, &mut copy)?;
+                if copy[..] != original[..] {
+                    let kind = CorruptionKind::FatMismatch { copy: fat as u8, sector };
+                    return Err(Fat32Error::Corrupted(kind));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Toutes les entrées de la FAT1 sur 28 bits, entrées 0 et 1 comprises
+    ///
+    /// Un élément par entrée : `fat_size * bytes_per_sector / 4` éléments,
+    /// même au-delà du dernier cluster de données.
+    pub fn snapshot_fat(&mut self) -> Result<FatSnapshot, D::Error> {
+        let data = self.read_entire_fat()?;
+        let entries = (0..data.len() / 4).map(|i| decode_entry(&data, i * 4)).collect();
+        Ok(FatSnapshot { entries, max_cluster: self.max_cluster() })
+    }
+
+    /// Remplacer toute la FAT, dans toutes ses copies
+    ///
+    /// `data` doit faire exactement `fat_size * bytes_per_sector` octets. Les
+    /// modifications en attente dans le cache sont abandonnées.
+    pub fn write_entire_fat(&mut self, data: &[u8]) -> Result<(), D::Error> {
+        let bytes_per_sec = self.boot_sector.bytes_per_sector() as usize;
+        if data.len() != self.boot_sector.fat_size() as usize * bytes_per_sec {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+
+        for (i, chunk) in data.chunks(bytes_per_sec).enumerate() {
+            self.write_fat_sector(i as u32, chunk)?;
+        }
+        self.cache.sectors.clear();
+
+        // Le nombre de clusters libres se recalcule depuis la nouvelle FAT,
+        // sur les seules entrées que `data` contient
+        let free = data
+            .chunks_exact(4)
+            .take(self.max_cluster() as usize + 1)
+            .skip(2)
+            .filter(|entry| decode_entry(entry, 0) == 0)
+            .count() as u32;
+        self.cache.free_count = Some(free);
+        self.cache.info_dirty = true;
+
+        Ok(())
+    }
+
+    /// Numéro du dernier cluster de données
+    fn max_cluster(&self) -> u32 {
+        self.boot_sector.num_clusters() + 1
+    }
+
+    /// Lire un secteur de la FAT (avec cache)
+    fn read_fat_sector(&mut self, sector: u32) -> Result<&[u8], D::Error> {
+        // Lire depuis le disque si absent du cache
+        if !self.cache.sectors.contains(sector) {
+            check_bounds(self.device, IoOp::Read, sector, 1)?;
+            let bytes_per_sec = self.boot_sector.bytes_per_sector();
+            let mut buffer = SectorBuf::new(bytes_per_sec as usize);
+            read_sector_aligned(self.device, sector, &mut buffer)?;
+
+            // Un secteur modifié évincé est écrit tout de suite
+            if let Some((evicted, data)) = self.cache.sectors.insert(sector, buffer, false) {
+                let first = self.boot_sector.first_fat_sector();
+                self.write_fat_sector(evicted - first, &data)?;
+            }
+        }
+
+        match self.cache.sectors.get(sector) {
+            Some(data) => Ok(data),
+            None => unreachable!(),
         }
     }
 
     /// Obtenir tous les clusters d'une chaîne
-    pub fn cluster_chain(&mut self, start_cluster: u32) -> Result<Vec<u32>> {
+    ///
+    /// Une chaîne plus longue que le nombre de clusters du volume boucle :
+    /// `ChainCycle`.
+    pub fn cluster_chain(&mut self, start_cluster: u32) -> Result<Vec<u32>, D::Error> {
         let mut chain = Vec::new();
+        self.follow_chain(start_cluster, |cluster| chain.push(cluster))?;
+        Ok(chain)
+    }
+
+    /// Suivre une chaîne jusqu'à sa fin en passant chaque cluster à `visit`
+    ///
+    /// Parcours commun à `cluster_chain`, `chain_length` et `extend_chain` :
+    /// au-delà de `max_cluster` pas, la chaîne boucle et le parcours s'arrête
+    /// sur `ChainCycle`. Retourne le dernier cluster.
+    fn follow_chain(
+        &mut self,
+        start_cluster: u32,
+        mut visit: impl FnMut(u32),
+    ) -> Result<u32, D::Error> {
         let mut current = start_cluster;
+        let mut steps = 0;
 
         loop {
-            chain.push(current);
+            if steps >= self.max_cluster() {
+                return Err(Fat32Error::Corrupted(CorruptionKind::ChainCycle { at: current }));
+            }
+            visit(current);
+            steps += 1;
 
             match self.next_cluster(current) {
                 Ok(next) => current = next,
-                Err(Fat32Error::EndOfChain) => break,
+                Err(Fat32Error::EndOfChain) => return Ok(current),
                 Err(e) => return Err(e),
             }
         }
+    }
+
+    /// Cluster atteint en suivant `n` fois la chaîne depuis `start`
+    ///
+    /// Ne construit pas la chaîne : utile quand un seul cluster est voulu
+    /// (celui d'un offset, `n = offset / cluster_size`). `EndOfChain` si la
+    /// chaîne a moins de `n + 1` clusters.
+    pub fn chain_nth(&mut self, start: u32, n: u32) -> Result<u32, D::Error> {
+        let mut current = start;
+        for _ in 0..n {
+            current = self.next_cluster(current)?;
+        }
+        Ok(current)
+    }
+
+    /// Nombre de clusters d'une chaîne (`ChainCycle` si elle boucle)
+    pub fn chain_length(&mut self, start_cluster: u32) -> Result<u32, D::Error> {
+        let mut length = 0;
+        self.follow_chain(start_cluster, |_| length += 1)?;
+        Ok(length)
+    }
+
+    /// Clusters libres comptés dans la FAT, à comparer au compteur de FSInfo
+    ///
+    /// Toutes les entrées sont lues (`count_free_clusters`), le compteur de
+    /// FSInfo est ignoré : c'est la valeur retenue au montage quand ce
+    /// compteur est vérifié.
+    pub fn free_count_from_fat(&mut self) -> Result<u32, D::Error> {
+        self.count_free_clusters()
+    }
+
+    /// Compter les clusters libres en lisant la FAT1 seule
+    ///
+    /// Entrées 2 à `num_clusters() + 1` nulles (sur 28 bits), lues comme
+    /// `used_clusters` sans remplir le cache. Avec `count_used_clusters` et
+    /// les clusters défectueux, on retrouve `num_clusters()`.
+    pub fn count_free_clusters(&mut self) -> Result<u32, D::Error> {
+        let mut scan = self.used_clusters();
+        let mut free = 0;
+        for cluster in 2..=scan.fat.max_cluster() {
+            if scan.state(cluster)? == ClusterState::Free {
+                free += 1;
+            }
+        }
+        Ok(free)
+    }
+
+    /// Compter les clusters alloués (ni libres ni défectueux) dans la FAT1
+    pub fn count_used_clusters(&mut self) -> Result<u32, D::Error> {
+        self.used_clusters().try_fold(0, |used, item| item.map(|_| used + 1))
+    }
+
+    /// État d'un cluster d'après la FAT
+    pub fn cluster_state(&mut self, cluster: u32) -> Result<ClusterState, D::Error> {
+        if cluster < 2 || cluster > self.max_cluster() {
+            return Err(Fat32Error::InvalidCluster);
+        }
+        Ok(ClusterState::from_entry(self.read_entry(cluster)?, self.max_cluster()))
+    }
+
+    /// Clusters alloués, dans l'ordre, avec leur état
+    ///
+    /// La FAT est lue secteur par secteur au fil de l'itération, sans
+    /// passer par le cache (un parcours complet ne l'évince pas) : les
+    /// secteurs déjà en cache sont pris tels quels, modifications en attente
+    /// comprises. Les clusters libres et défectueux sont sautés. Après une
+    /// erreur de lecture, l'itérateur s'arrête.
+    pub fn used_clusters(&mut self) -> UsedClusters<'_, 'a, D> {
+        let bytes_per_sec = self.boot_sector.bytes_per_sector() as usize;
+        UsedClusters {
+            fat: self,
+            sector: SectorBuf::new(bytes_per_sec),
+            loaded: None,
+            next: 2,
+            done: false,
+        }
+    }
+
+    /// Numéros des clusters alloués, dans l'ordre (voir `used_clusters`)
+    pub fn used_cluster_iter(
+        &mut self,
+    ) -> impl Iterator<Item = Result<u32, D::Error>> + use<'_, 'a, D> {
+        self.used_clusters().map(|item| item.map(|(cluster, _)| cluster))
+    }
+
+    /// Allouer une chaîne de `count` clusters et retourner le premier
+    ///
+    /// Rien n'est modifié si l'espace libre est insuffisant.
+    pub fn allocate_chain(&mut self, count: u32) -> Result<u32, D::Error> {
+        if count == 0 {
+            return Err(Fat32Error::InvalidCluster);
+        }
+
+        // Trouver les clusters libres avant de toucher à la FAT
+        let clusters = self.find_free_clusters(count)?;
+
+        // Chaîner en partant de la fin
+        let mut next = FAT_EOC;
+        for &cluster in clusters.iter().rev() {
+            self.write_entry(cluster, next)?;
+            next = cluster;
+        }
+
+        Ok(clusters[0])
+    }
+
+    /// Les `count` premiers clusters libres, `DiskFull` s'il en manque
+    fn find_free_clusters(&mut self, count: u32) -> Result<Vec<u32>, D::Error> {
+        let mut clusters = Vec::new();
+        for cluster in 2..=self.max_cluster() {
+            if self.read_entry(cluster)? == 0 {
+                clusters.push(cluster);
+                if clusters.len() == count as usize {
+                    return Ok(clusters);
+                }
+            }
+        }
+        Err(Fat32Error::DiskFull)
+    }
+
+    /// Allouer `count` clusters consécutifs si possible, retourne le premier
+    ///
+    /// Cherche un bloc libre assez long avec `find_free_range`, à partir du
+    /// prochain cluster libre indiqué par FSInfo ; s'il n'y en a pas, alloue
+    /// comme `allocate_chain` (clusters dispersés). Rien n'est modifié si
+    /// l'espace libre est insuffisant.
+    pub fn allocate_contiguous_chain(&mut self, count: u32) -> Result<u32, D::Error> {
+        let hint = self.cache.next_free.unwrap_or(2);
+        let start = match self.find_free_range(count, hint) {
+            Ok(start) => start,
+            Err(Fat32Error::DiskFull) => return self.allocate_chain(count),
+            Err(e) => return Err(e),
+        };
+
+        for cluster in start..start + count {
+            let next = if cluster + 1 == start + count { FAT_EOC } else { cluster + 1 };
+            self.write_entry(cluster, next)?;
+        }
+        Ok(start)
+    }
+
+    /// Chaîner des clusters déjà choisis, dans l'ordre de `clusters`
+    ///
+    /// Chaque cluster pointe sur le suivant, le dernier est marqué fin de
+    /// chaîne. Les entrées sont groupées par secteur de FAT : chaque secteur
+    /// touché est écrit une seule fois, dans toutes les copies. Rien n'est
+    /// modifié si un cluster est hors du volume (`InvalidCluster`).
+    pub fn write_cluster_chain_bulk(&mut self, clusters: &[u32]) -> Result<(), D::Error> {
+        if clusters.iter().any(|&cluster| cluster < 2 || cluster > self.max_cluster()) {
+            return Err(Fat32Error::InvalidCluster);
+        }
+
+        let next = clusters.iter().skip(1).copied().chain([FAT_EOC]);
+        let mut links: Vec<(u32, u32)> = clusters.iter().copied().zip(next).collect();
+        links.sort_unstable_by_key(|&(cluster, _)| cluster);
+
+        let first = self.boot_sector.first_fat_sector();
+        let sector_of = |cluster| entry_position(self.boot_sector, cluster).0;
+        for group in links.chunk_by(|a, b| sector_of(a.0) == sector_of(b.0)) {
+            for &(cluster, next) in group {
+                self.write_entry(cluster, next)?;
+            }
+            let sector = sector_of(group[0].0);
+            if let Some(data) = self.cache.sectors.peek(sector).map(SectorBuf::from_slice) {
+                self.write_fat_sector(sector - first, &data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Trouver `count` clusters libres consécutifs, retourne le premier
+    pub fn find_free_run(&mut self, count: u32) -> Result<Option<u32>, D::Error> {
+        if count == 0 {
+            return Ok(None);
+        }
+        self.scan_free_run(2, self.max_cluster(), count)
+    }
+
+    /// Trouver `count` clusters libres consécutifs en partant de `hint`
+    ///
+    /// Parcourt la FAT de `hint` jusqu'à la fin, puis reprend au cluster 2
+    /// pour les blocs qui commencent avant `hint`. Un `hint` hors du volume
+    /// (FSInfo inconnu ou faux) vaut 2. `DiskFull` si aucun bloc assez long
+    /// n'existe, `InvalidInput` si `count` est nul.
+    pub fn find_free_range(&mut self, count: u32, hint: u32) -> Result<u32, D::Error> {
+        if count == 0 {
+            return Err(Fat32Error::InvalidInput);
+        }
+        let max_cluster = self.max_cluster();
+        let hint = if (2..=max_cluster).contains(&hint) { hint } else { 2 };
+
+        if let Some(start) = self.scan_free_run(hint, max_cluster, count)? {
+            return Ok(start);
+        }
+        if hint > 2 {
+            // Un bloc qui commence avant `hint` peut déborder après
+            let last = hint.saturating_add(count - 2).min(max_cluster);
+            if let Some(start) = self.scan_free_run(2, last, count)? {
+                return Ok(start);
+            }
+        }
+        Err(Fat32Error::DiskFull)
+    }
+
+    /// Premier bloc de `count` clusters libres entre `first` et `last` inclus
+    fn scan_free_run(
+        &mut self,
+        first: u32,
+        last: u32,
+        count: u32,
+    ) -> Result<Option<u32>, D::Error> {
+        let mut run_start = first;
+        let mut run_length = 0;
+        for cluster in first..=last {
+            if self.read_entry(cluster)? == 0 {
+                if run_length == 0 {
+                    run_start = cluster;
+                }
+                run_length += 1;
+                if run_length == count {
+                    return Ok(Some(run_start));
+                }
+            } else {
+                run_length = 0;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Ajouter un cluster à la fin d'une chaîne et le retourner
+    ///
+    /// `ChainCycle` (rien n'est alloué) si la chaîne boucle.
+    pub fn extend_chain(&mut self, start_cluster: u32) -> Result<u32, D::Error> {
+        let last = self.follow_chain(start_cluster, |_| ())?;
+
+        let new_cluster = self.allocate_chain(1)?;
+        self.write_entry(last, new_cluster)?;
+        Ok(new_cluster)
+    }
+
+    /// Ajouter `count` clusters à la fin d'une chaîne, retourne les nouveaux
+    ///
+    /// Les clusters sont pris d'un seul bloc juste après la fin de la chaîne
+    /// si possible (`find_free_range`), sinon là où il y a de la place. Les
+    /// entrées sont modifiées dans le cache seulement : la FAT est écrite au
+    /// `flush` de l'appelant (ou à la fin du lot), chaque secteur une seule
+    /// fois dans chaque copie. Rien n'est modifié si l'espace libre est
+    /// insuffisant.
+    pub fn extend_chain_by(&mut self, start_cluster: u32, count: u32) -> Result<Vec<u32>, D::Error> {
+        let chain = self.cluster_chain(start_cluster)?;
+        let last = chain[chain.len() - 1];
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let clusters: Vec<u32> = match self.find_free_range(count, last + 1) {
+            Ok(first) => (first..first + count).collect(),
+            Err(Fat32Error::DiskFull) => self.find_free_clusters(count)?,
+            Err(e) => return Err(e),
+        };
+
+        // Nouvelle chaîne terminée avant de la raccrocher
+        let mut next = FAT_EOC;
+        for &cluster in clusters.iter().rev() {
+            self.write_entry(cluster, next)?;
+            next = cluster;
+        }
+        self.write_entry(last, clusters[0])?;
+        Ok(clusters)
+    }
+
+    /// Vérifier que chaque cluster alloué appartient à exactement une chaîne
+    ///
+    /// Parcourt toute l'arborescence depuis la racine en lisant les
+    /// répertoires sur le device (écrire d'abord les répertoires en attente)
+    /// et compte les références de chaque cluster. Un cluster est alloué si
+    /// son entrée n'est ni libre ni marquée défectueuse (fin de chaîne
+    /// comprise). Une chaîne qui boucle est coupée après `max_cluster` pas.
+    pub fn check_integrity(&mut self) -> Result<IntegrityReport, D::Error> {
+        let max_cluster = self.max_cluster();
+        let mut refs = vec![0u8; max_cluster as usize + 1];
+        let mut chains = Vec::new();
+
+        let root = self.boot_sector.root_cluster();
+        let mut pending = vec![root];
+        chains.push(self.walk_chain(root, &mut refs)?);
+
+        while let Some(dir) = pending.pop() {
+            let clusters = self.walk_chain(dir, &mut [])?;
+            'dir: for cluster in clusters {
+                let data = self.read_data_cluster(cluster)?;
+                for chunk in data.chunks_exact(DirectoryEntry::SIZE) {
+                    let entry = unsafe { DirectoryEntry::from_bytes(chunk) };
+                    if entry.is_end() {
+                        break 'dir;
+                    }
+                    if !entry.is_valid()
+                        || entry.attributes().is_long_name()
+                        || entry.attributes().is_volume_id()
+                        || entry.is_dot()
+                        || entry.is_dot_dot()
+                    {
+                        continue;
+                    }
+
+                    let first = entry.first_cluster();
+                    if first < 2 || first > max_cluster {
+                        continue;
+                    }
+                    // Un répertoire déjà vu n'est pas reparcouru (boucles)
+                    let seen = refs[first as usize] > 0;
+                    chains.push(self.walk_chain(first, &mut refs)?);
+                    if entry.attributes().is_directory() && !seen {
+                        pending.push(first);
+                    }
+                }
+            }
+        }
+
+        let mut report = IntegrityReport::default();
+        for chain in &chains {
+            if chain.iter().any(|&c| refs[c as usize] > 1) {
+                report.cross_linked.push(chain[0]);
+            }
+        }
+        report.cross_linked.sort_unstable();
+        report.cross_linked.dedup();
+
+        let fat = self.snapshot_fat()?;
+        for cluster in 2..=max_cluster {
+            let count = refs[cluster as usize];
+            if count == 0 && fat.state_of(cluster).is_used() {
+                report.unreferenced.push(cluster);
+            }
+            if count > 1 {
+                report.multiply_referenced.push(cluster);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Suivre une chaîne en comptant ses références dans `refs`
+    ///
+    /// S'arrête sans erreur sur un cluster libre, défectueux ou hors limites.
+    fn walk_chain(&mut self, start: u32, refs: &mut [u8]) -> Result<Vec<u32>, D::Error> {
+        let max_cluster = self.max_cluster();
+        let mut chain = Vec::new();
+        let mut current = start;
+
+        while (2..=max_cluster).contains(&current) && chain.len() <= max_cluster as usize {
+            chain.push(current);
+            if let Some(count) = refs.get_mut(current as usize) {
+                *count = count.saturating_add(1);
+            }
+            match self.read_entry(current)? {
+                0x0FFFFFF8..=0x0FFFFFFF | 0 | FAT_BAD => break,
+                next => current = next,
+            }
+        }
 
         Ok(chain)
     }
+
+    /// Lire un cluster de données directement sur le device
+    fn read_data_cluster(&mut self, cluster: u32) -> Result<AlignedBuf, D::Error> {
+        let bytes_per_sec = self.boot_sector.bytes_per_sector() as usize;
+        let first_sector = self.boot_sector.cluster_to_sector(cluster);
+        let sectors = self.boot_sector.sectors_per_cluster() as u32;
+        check_bounds(self.device, IoOp::Read, first_sector, sectors)?;
+
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let mut data = AlignedBuf::zeroed(cluster_size, self.device.buffer_alignment());
+        for (i, chunk) in data.chunks_mut(bytes_per_sec).enumerate() {
+            read_sector_aligned(self.device, first_sector + i as u32, chunk)?;
+        }
+        Ok(data)
+    }
+
+    /// Remettre à zéro les données d'un cluster, sans toucher à la FAT
+    ///
+    /// Pour effacer le contenu d'un cluster libéré. C'est un effacement au
+    /// mieux : le support (remappage de la flash, caches du disque) peut
+    /// garder des copies des anciennes données, ce n'est pas une protection
+    /// contre une analyse physique.
+    pub fn write_cluster_zero(&mut self, cluster: u32) -> Result<(), D::Error> {
+        if cluster < 2 || cluster > self.max_cluster() {
+            return Err(Fat32Error::InvalidCluster);
+        }
+
+        let first_sector = self.boot_sector.cluster_to_sector(cluster);
+        let sectors = self.boot_sector.sectors_per_cluster() as u32;
+        check_bounds(self.device, IoOp::Write, first_sector, sectors)?;
+
+        let zeros = SectorBuf::new(self.boot_sector.bytes_per_sector() as usize);
+        for sector in first_sector..first_sector + sectors {
+            write_sector_aligned(self.device, sector, &zeros)?;
+        }
+        Ok(())
+    }
+
+    /// Donner exactement `count` clusters à une chaîne, retourne son premier
+    ///
+    /// `start` inférieur à 2 désigne une chaîne vide : une nouvelle est
+    /// allouée. Une chaîne trop courte est allongée (`extend_chain_by`), une
+    /// trop longue coupée après son `count`-ième cluster, la suite libérée.
+    /// Avec `count == 0`, toute la chaîne est libérée et le résultat vaut 0.
+    pub fn resize_cluster_chain(&mut self, start: u32, count: u32) -> Result<u32, D::Error> {
+        if start < 2 {
+            return if count == 0 { Ok(0) } else { self.allocate_chain(count) };
+        }
+        if count == 0 {
+            self.free_chain(start)?;
+            return Ok(0);
+        }
+
+        let chain = self.cluster_chain(start)?;
+        let len = chain.len() as u32;
+        if len < count {
+            self.extend_chain_by(start, count - len)?;
+        } else if len > count {
+            self.write_entry(chain[count as usize - 1], FAT_EOC)?;
+            for &cluster in &chain[count as usize..] {
+                self.write_entry(cluster, 0)?;
+            }
+        }
+        Ok(start)
+    }
+
+    /// Libérer tous les clusters d'une chaîne, retourne le nombre libéré
+    pub fn free_chain(&mut self, start_cluster: u32) -> Result<u32, D::Error> {
+        let chain = self.cluster_chain(start_cluster)?;
+        for &cluster in &chain {
+            self.write_entry(cluster, 0)?;
+        }
+        Ok(chain.len() as u32)
+    }
+}
+
+/// Itérateur de `FatTable::used_clusters`
+pub struct UsedClusters<'t, 'a, D: BlockDevice> {
+    fat: &'t mut FatTable<'a, D>,
+    /// Secteur de FAT en cours (copie, hors cache)
+    sector: SectorBuf,
+    loaded: Option<u32>,
+    next: u32,
+    done: bool,
+}
+
+impl<D: BlockDevice> UsedClusters<'_, '_, D> {
+    /// Rendre disponible le secteur de FAT `sector`
+    fn load(&mut self, sector: u32) -> Result<(), D::Error> {
+        if self.loaded == Some(sector) {
+            return Ok(());
+        }
+        self.loaded = None;
+        match self.fat.cache.sectors.peek(sector) {
+            Some(cached) => self.sector.copy_from_slice(cached),
+            None => {
+                check_bounds(self.fat.device, IoOp::Read, sector, 1)?;
+                read_sector_aligned(self.fat.device, sector, &mut self.sector)?;
+            }
+        }
+        self.loaded = Some(sector);
+        Ok(())
+    }
+
+    /// État du cluster `cluster`, d'après le secteur de FAT qui le contient
+    fn state(&mut self, cluster: u32) -> Result<ClusterState, D::Error> {
+        let (sector, offset) = entry_position(self.fat.boot_sector, cluster);
+        self.load(sector)?;
+        let entry = decode_entry(&self.sector, offset);
+        Ok(ClusterState::from_entry(entry, self.fat.max_cluster()))
+    }
+}
+
+impl<D: BlockDevice> Iterator for UsedClusters<'_, '_, D> {
+    type Item = Result<(u32, ClusterState), D::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let max_cluster = self.fat.max_cluster();
+        while !self.done && self.next <= max_cluster {
+            let cluster = self.next;
+            self.next += 1;
+            match self.state(cluster) {
+                Ok(state) if state.is_used() => return Some(Ok((cluster, state))),
+                Ok(_) => {}
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::devices::TraceDevice;
+    use crate::filesystem::test_helpers::{FatImage, MockDevice, IMAGE_SECTORS};
     use crate::BootSector;
 
-    // Mock device pour les tests
-    struct MockDevice {
-        data: Vec<u8>,
+    #[test]
+    fn test_invalid_cluster() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+
+        assert!(fat.next_cluster(0).is_err());
+        assert!(fat.next_cluster(1).is_err());
+    }
+
+    #[test]
+    fn test_read_write_entire_fat() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+
+        let fat_bytes = bs.fat_size() as usize * 512;
+        let mut data = fat.read_entire_fat().unwrap();
+        assert_eq!(data.len(), fat_bytes);
+        assert!(fat.write_entire_fat(&data[1..]).is_err());
+
+        // Chaîne 2 -> 3 -> fin, bits réservés levés sur la fin de chaîne
+        data[8..12].copy_from_slice(&3u32.to_le_bytes());
+        data[12..16].copy_from_slice(&(0xF000_0000 | FAT_EOC).to_le_bytes());
+        fat.write_entire_fat(&data).unwrap();
+        assert_eq!(fat.cluster_chain(2).unwrap(), vec![2, 3]);
+        assert_eq!(fat.read_entire_fat().unwrap(), data);
+
+        let snapshot = fat.snapshot_fat().unwrap();
+        assert_eq!(snapshot.entries().len(), fat_bytes / 4);
+        assert_eq!(snapshot.entries()[2..5], [3, FAT_EOC & 0x0FFFFFFF, 0]);
+        assert_eq!((snapshot[2], snapshot[5000]), (3, 0));
+        assert_eq!(snapshot.state_of(2), ClusterState::Next(3));
+        assert_eq!(snapshot.state_of(3), ClusterState::EndOfChain);
+        assert!(snapshot.is_free(4) && snapshot.is_free(u32::MAX) && !snapshot.is_free(2));
+
+        // Les deux copies sont à jour
+        let fat1 = 32 * 512;
+        let fat2 = fat1 + fat_bytes;
+        assert_eq!(device.data[fat1..fat1 + fat_bytes], device.data[fat2..fat2 + fat_bytes]);
     }
 
-    impl BlockDevice for MockDevice {
-        fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
-            let offset = sector as usize * 512;
-            buffer.copy_from_slice(&self.data[offset..offset + buffer.len()]);
-            Ok(())
+    #[test]
+    fn test_write_entire_fat_short_fat() {
+        // Boot sector qui annonce une FAT trop petite pour ses clusters : le
+        // compte des libres s'arrête aux entrées fournies
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        image.data[36..40].copy_from_slice(&100u32.to_le_bytes());
+        let mut device = image.as_device();
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+
+        let data = vec![0u8; 100 * 512];
+        fat.write_entire_fat(&data).unwrap();
+        assert_eq!(cache.free_count, Some(100 * 128 - 2));
+    }
+
+    #[test]
+    fn test_preload_limited_to_cache() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let bs = unsafe { BootSector::from_bytes(&image.data[0..512]) };
+        let mut device = TraceDevice::new(image.as_device());
+
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+        fat.preload().unwrap();
+
+        // 128 entrées par secteur : les 8 premiers secteurs sont en cache
+        for cluster in (2..FAT_CACHE_SECTORS as u32 * 128).step_by(100) {
+            fat.read_entry(cluster).unwrap();
         }
+        fat.read_entry(FAT_CACHE_SECTORS as u32 * 128).unwrap();
+        assert_eq!(device.summary().sectors_read, FAT_CACHE_SECTORS as u64 + 1);
+    }
+
+    #[test]
+    fn test_used_clusters_lazy() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let bs = unsafe { BootSector::from_bytes(&image.data[0..512]) };
+        let mut device = TraceDevice::new(image.as_device());
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+
+        // 2 -> 3 -> fin, 5 défectueux, 300 (deuxième secteur) -> 7 (hors
+        // chaîne mais valide), 301 -> valeur réservée
+        fat.write_entry(2, 3).unwrap();
+        fat.write_entry(3, FAT_EOC).unwrap();
+        fat.write_entry(5, FAT_BAD).unwrap();
+        fat.write_entry(300, 7).unwrap();
+        fat.write_entry(301, 0x0FFFFFF0).unwrap();
+        fat.flush().unwrap();
+        fat.cache.clear();
+        let reads = fat.device.summary().reads;
+
+        let mut used = fat.used_clusters();
+        assert_eq!(used.next(), Some(Ok((2, ClusterState::Next(3)))));
+        assert_eq!(used.next(), Some(Ok((3, ClusterState::EndOfChain))));
+        // Un seul secteur lu jusqu'ici, et rien n'entre dans le cache
+        assert_eq!(used.fat.device.summary().reads, reads + 1);
+        assert_eq!(used.next(), Some(Ok((300, ClusterState::Next(7)))));
+        assert_eq!(used.next(), Some(Ok((301, ClusterState::Invalid(0x0FFFFFF0)))));
+        assert_eq!(used.next(), None);
+        assert_eq!(fat.cache.sectors.len(), 0);
+
+        let clusters: Result<Vec<u32>> = fat.used_cluster_iter().collect();
+        assert_eq!(clusters, Ok(vec![2, 3, 300, 301]));
+        assert_eq!(fat.cluster_state(5), Ok(ClusterState::Bad));
+        assert_eq!(fat.cluster_state(4), Ok(ClusterState::Free));
+        assert_eq!(fat.cluster_state(1), Err(Fat32Error::InvalidCluster));
+    }
+
+    #[test]
+    fn test_free_and_used_counts() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+        let clusters = bs.num_clusters();
 
-        fn write_sector(&mut self, _: u32, _: &[u8]) -> Result<()> {
-            Ok(())
+        // Image neuve : seule la racine est allouée
+        assert_eq!(fat.count_used_clusters(), Ok(1));
+        assert_eq!(fat.count_free_clusters(), Ok(clusters - 1));
+        assert_eq!(fat.free_count_from_fat(), Ok(clusters - 1));
+
+        // Chaîne de 3 clusters et un cluster défectueux (ni libre ni alloué)
+        fat.allocate_chain(3).unwrap();
+        fat.write_entry(100, FAT_BAD).unwrap();
+        let free = fat.count_free_clusters().unwrap();
+        let used = fat.count_used_clusters().unwrap();
+        assert_eq!((free, used), (clusters - 5, 4));
+        assert_eq!(free + used + 1, clusters);
+    }
+
+    #[test]
+    fn test_fat_table_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FatCache>();
+        assert_send_sync::<FatTable<'static, MockDevice<'static>>>();
+    }
+
+    #[test]
+    fn test_write_fat_sector_all_copies() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+
+        // Le secteur 1 de la FAT est en cache avec une modification en attente
+        fat.write_entry(130, 131).unwrap();
+        let mut sector = vec![0u8; 512];
+        sector[8..12].copy_from_slice(&FAT_EOC.to_le_bytes());
+        fat.write_fat_sector(1, &sector).unwrap();
+        assert_eq!(fat.read_entry(130).unwrap(), FAT_EOC);
+        assert!(!cache.is_dirty());
+
+        assert_eq!(
+            FatTable::new(&mut device, &bs, &mut cache).write_fat_sector(bs.fat_size(), &sector),
+            Err(Fat32Error::InvalidInput)
+        );
+        for start in [33 * 512, (33 + bs.fat_size() as usize) * 512] {
+            assert_eq!(device.data[start..start + 512], sector[..]);
         }
+    }
+
+    #[test]
+    fn test_write_cluster_chain_bulk() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let bs = unsafe { BootSector::from_bytes(&image.data[0..512]) };
+        let mut device = TraceDevice::new(image.as_device());
+
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+        let free = fat.count_free_clusters().unwrap();
+
+        // 128 entrées par secteur : deux secteurs touchés, chacun écrit une
+        // fois dans les deux FAT
+        let clusters = [10, 200, 11, 12, 130];
+        assert_eq!(fat.write_cluster_chain_bulk(&[5, 1]), Err(Fat32Error::InvalidCluster));
+        fat.write_cluster_chain_bulk(&clusters).unwrap();
+        assert!(!fat.cache.is_dirty());
+        assert_eq!(fat.cluster_chain(10).unwrap(), clusters);
+        assert_eq!(fat.read_entry(5), Ok(0));
+        assert_eq!(fat.count_free_clusters(), Ok(free - 5));
+        assert_eq!(device.summary().writes, 4);
+    }
 
-        fn sector_size(&self) -> usize {
-            512
+    #[test]
+    fn test_allocate_contiguous_chain() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+
+        // Cluster 2 pris par la racine, 3 à 29 : libres un sur deux
+        for cluster in (3..30).step_by(2) {
+            fat.write_entry(cluster, FAT_EOC).unwrap();
         }
+        let first = fat.allocate_contiguous_chain(3).unwrap();
+        assert_eq!(fat.cluster_chain(first).unwrap(), vec![30, 31, 32]);
+
+        // Plus long que le bloc libre de 33 à la fin : repli sur une chaîne
+        // dispersée
+        let count = bs.num_clusters() - 28;
+        let first = fat.allocate_contiguous_chain(count).unwrap();
+        let chain = fat.cluster_chain(first).unwrap();
+        assert_eq!(chain.len() as u32, count);
+        assert_eq!(&chain[..3], &[4, 6, 8]);
+        assert_eq!(fat.chain_nth(first, 0), Ok(4));
+        assert_eq!(fat.chain_nth(first, 2), Ok(8));
+        assert_eq!(fat.chain_nth(first, count - 1), Ok(chain[count as usize - 1]));
+        assert_eq!(fat.chain_nth(first, count), Err(Fat32Error::EndOfChain));
+        assert_eq!(fat.allocate_contiguous_chain(1000), Err(Fat32Error::DiskFull));
     }
 
     #[test]
-    fn test_invalid_cluster() {
-        let mut device = MockDevice { data: vec![0; 1024 * 512] };
-        device.data[66] = 0x29;
-        device.data[11..13].copy_from_slice(&512u16.to_le_bytes());
-        device.data[13] = 1;
-        device.data[16] = 2;
-        
+    fn test_extend_chain_by() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+
         let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
-        let mut fat = FatTable::new(&mut device, &bs);
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
 
-        assert!(fat.next_cluster(0).is_err());
-        assert!(fat.next_cluster(1).is_err());
+        // Racine en 2, 3 à 5 pris : le bloc suivant commence en 6
+        for cluster in 3..6 {
+            fat.write_entry(cluster, FAT_EOC).unwrap();
+        }
+        assert_eq!(fat.extend_chain_by(2, 4), Ok(vec![6, 7, 8, 9]));
+        assert_eq!(fat.cluster_chain(2).unwrap(), vec![2, 6, 7, 8, 9]);
+        assert_eq!(fat.extend_chain_by(2, 0), Ok(vec![]));
+
+        // Rien n'est écrit avant le flush, puis les deux copies de la FAT
+        let fat1 = bs.first_fat_sector() as usize * 512;
+        let fat2 = fat1 + bs.fat_size() as usize * 512;
+        assert!(cache.is_dirty());
+        assert_eq!(device.data[fat1 + 8..fat1 + 12], FAT_EOC.to_le_bytes());
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+        fat.flush().unwrap();
+        assert!(!fat.cache.is_dirty());
+
+        assert_eq!(fat.extend_chain_by(2, 100_000), Err(Fat32Error::DiskFull));
+        assert_eq!(fat.cluster_chain(2).unwrap().len(), 5);
+        assert_eq!(device.data[fat1 + 8..fat1 + 12], 6u32.to_le_bytes());
+        assert_eq!(device.data[fat1..fat1 + 512], device.data[fat2..fat2 + 512]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_find_free_range_wraps_around() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        let mut device = image.as_device();
+
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+        let max = bs.num_clusters() + 1;
+
+        // Libres : 3 à 9, puis 50 à 51 ; tout le reste est pris
+        for cluster in (10..50).chain(52..=max) {
+            fat.write_entry(cluster, FAT_EOC).unwrap();
+        }
+        assert_eq!(fat.find_free_range(2, 40), Ok(50));
+        assert_eq!(fat.find_free_range(4, 40), Ok(3));
+        assert_eq!(fat.find_free_range(4, 5), Ok(5));
+        // Bloc qui commence avant le hint et continue après
+        assert_eq!(fat.find_free_range(7, 5), Ok(3));
+        assert_eq!(fat.find_free_range(3, u32::MAX), Ok(3));
+        assert_eq!(fat.find_free_range(8, 2), Err(Fat32Error::DiskFull));
+        assert_eq!(fat.find_free_range(0, 2), Err(Fat32Error::InvalidInput));
+    }
+
+    #[test]
+    fn test_write_cluster_zero() {
+        let mut image = FatImage::new(2 * IMAGE_SECTORS, 2);
+        let mut device = image.as_device();
+        let bs = unsafe { BootSector::from_bytes(&device.data[0..512]) };
+        let start = bs.cluster_to_sector(5) as usize * 512;
+        device.data[start - 1] = 0xAA;
+        device.data[start..start + 1024].fill(0x55);
+        device.data[start + 1024] = 0xAA;
+
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+        fat.write_entry(5, FAT_EOC).unwrap();
+        fat.write_cluster_zero(5).unwrap();
+        assert_eq!(fat.read_entry(5), Ok(FAT_EOC));
+        assert_eq!(fat.write_cluster_zero(1), Err(Fat32Error::InvalidCluster));
+        assert_eq!(fat.write_cluster_zero(bs.num_clusters() + 2), Err(Fat32Error::InvalidCluster));
+
+        // Seul le cluster est effacé, pas ses voisins
+        assert!(device.data[start..start + 1024].iter().all(|&b| b == 0));
+        assert_eq!(device.data[start - 1], 0xAA);
+        assert_eq!(device.data[start + 1024], 0xAA);
+    }
+}
@@ -0,0 +1,242 @@
+//! Handles de lecture sur les fichiers
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::filesystem::EntryLocation;
+use crate::{BlockDevice, Fat32Error, Fat32FileSystem, Result};
+
+/// Marque d'ordre des octets UTF-8
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// Fichier ouvert
+///
+/// Le handle n'emprunte pas le système de fichiers : il garde une copie de la
+/// chaîne de clusters et de la taille prise à l'ouverture, plus la position
+/// courante. Chaque lecture ou écriture repasse par `Fat32FileSystem`
+/// (`read_handle`, `write_handle`), ce qui permet d'avoir plusieurs handles
+/// ouverts en même temps.
+///
+/// Si le fichier est modifié après l'ouverture par un autre moyen que ce
+/// handle, le handle ne le voit pas : il continue à lire les anciens clusters
+/// jusqu'à l'ancienne taille. Si ces clusters ont été libérés puis réutilisés,
+/// les données lues sont celles qui s'y trouvent maintenant. Rouvrir le
+/// fichier pour voir la nouvelle version.
+#[derive(Clone, Debug)]
+pub struct FatFile {
+    pub(crate) clusters: Vec<u32>,
+    pub(crate) size: u32,
+    pub(crate) position: u32,
+    pub(crate) entry: EntryLocation,
+    pub(crate) readable: bool,
+    pub(crate) writable: bool,
+    pub(crate) append: bool,
+}
+
+impl FatFile {
+    /// Taille du fichier (à l'ouverture, plus les écritures de ce handle)
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Position courante de lecture
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Déplacer la position de lecture (bornée à la taille du fichier)
+    pub fn set_position(&mut self, position: u32) {
+        self.position = position.min(self.size);
+    }
+
+    /// Nombre d'octets restant à lire
+    pub fn remaining(&self) -> u32 {
+        self.size - self.position
+    }
+
+    /// Fin du fichier atteinte
+    pub fn is_eof(&self) -> bool {
+        self.position >= self.size
+    }
+}
+
+/// Options d'ouverture d'un fichier, sur le modèle de `std::fs::OpenOptions`
+///
+/// Mêmes règles que std : `truncate`, `create` et `create_new` demandent
+/// l'écriture (`write` ou `append`), `truncate` et `append` sont exclusifs.
+/// Une combinaison incohérente donne `Fat32Error::InvalidInput`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpenOptions {
+    pub(crate) read: bool,
+    pub(crate) write: bool,
+    pub(crate) append: bool,
+    pub(crate) truncate: bool,
+    pub(crate) create: bool,
+    pub(crate) create_new: bool,
+}
+
+impl OpenOptions {
+    /// Toutes les options désactivées
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Autoriser la lecture
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.read = read;
+        self
+    }
+
+    /// Autoriser l'écriture
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.write = write;
+        self
+    }
+
+    /// Écrire toujours en fin de fichier (implique l'écriture)
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.append = append;
+        self
+    }
+
+    /// Vider le fichier s'il existe
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Créer le fichier s'il n'existe pas
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.create = create;
+        self
+    }
+
+    /// Créer le fichier, échouer avec `AlreadyExists` s'il existe
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.create_new = create_new;
+        self
+    }
+
+    /// Vérifier la cohérence des options
+    pub(crate) fn check(&self) -> Result<()> {
+        let writes = self.write || self.append;
+        if !self.read && !writes {
+            return Err(Fat32Error::InvalidInput);
+        }
+        if (self.truncate || self.create || self.create_new) && !writes {
+            return Err(Fat32Error::InvalidInput);
+        }
+        if self.truncate && self.append {
+            return Err(Fat32Error::InvalidInput);
+        }
+        Ok(())
+    }
+}
+
+/// Itérateur sur les lignes d'un fichier texte (voir `Fat32FileSystem::read_lines`)
+///
+/// Lit le fichier cluster par cluster : seule la ligne courante est gardée en
+/// mémoire. Les fins de ligne `\n`, `\r\n` et `\r` sont reconnues et retirées ;
+/// la dernière ligne n'a pas besoin de fin de ligne. Une ligne qui n'est pas
+/// de l'UTF-8 valide donne `Fat32Error::InvalidUtf8`, la lecture continue à
+/// la ligne suivante.
+pub struct Lines<'a, D: BlockDevice> {
+    fs: &'a mut Fat32FileSystem<D>,
+    file: FatFile,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    skip_lf: bool,
+    strip_bom: bool,
+    done: bool,
+}
+
+impl<'a, D: BlockDevice> Lines<'a, D> {
+    pub(crate) fn new(fs: &'a mut Fat32FileSystem<D>, file: FatFile) -> Self {
+        let cluster_size = fs.boot_sector().cluster_size() as usize;
+        Self {
+            fs,
+            file,
+            buffer: alloc::vec![0u8; cluster_size],
+            start: 0,
+            end: 0,
+            skip_lf: false,
+            strip_bom: false,
+            done: false,
+        }
+    }
+
+    /// Retirer la marque BOM UTF-8 en tête de fichier (désactivé par défaut)
+    pub fn strip_bom(mut self, strip: bool) -> Self {
+        self.strip_bom = strip;
+        self
+    }
+
+    /// Remplir le tampon, retourne false en fin de fichier
+    fn fill(&mut self) -> Result<bool, D::Error> {
+        let at_start = self.file.position() == 0;
+        let read = self.fs.read_handle(&mut self.file, &mut self.buffer)?;
+        self.start = 0;
+        self.end = read;
+
+        if at_start && self.strip_bom && self.buffer[..read].starts_with(&UTF8_BOM) {
+            self.start = UTF8_BOM.len();
+        }
+        Ok(read > 0)
+    }
+}
+
+impl<D: BlockDevice> Iterator for Lines<'_, D> {
+    type Item = Result<String, D::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut line = Vec::new();
+        loop {
+            if self.start == self.end {
+                match self.fill() {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        self.done = true;
+                        if line.is_empty() {
+                            return None;
+                        }
+                        break;
+                    }
+                    Err(err) => {
+                        self.done = true;
+                        return Some(Err(err));
+                    }
+                }
+                continue;
+            }
+
+            // Le \n d'un \r\n coupé entre deux lectures
+            if self.skip_lf {
+                self.skip_lf = false;
+                if self.buffer[self.start] == b'\n' {
+                    self.start += 1;
+                    continue;
+                }
+            }
+
+            let chunk = &self.buffer[self.start..self.end];
+            match chunk.iter().position(|&b| b == b'\n' || b == b'\r') {
+                Some(pos) => {
+                    line.extend_from_slice(&chunk[..pos]);
+                    self.skip_lf = chunk[pos] == b'\r';
+                    self.start += pos + 1;
+                    break;
+                }
+                None => {
+                    line.extend_from_slice(chunk);
+                    self.start = self.end;
+                }
+            }
+        }
+
+        Some(String::from_utf8(line).map_err(|_| Fat32Error::InvalidUtf8))
+    }
+}
@@ -0,0 +1,86 @@
+//! Fichiers vus comme des flux `embedded-io` (feature `embedded-io`)
+
+use core::fmt;
+use embedded_io::{ErrorKind, ErrorType, Read, Seek, SeekFrom};
+
+use crate::{BlockDevice, Fat32Error, Fat32FileSystem, FatFile};
+
+/// `error.kind()` appelle `Fat32Error::kind` : cette catégorie-ci s'obtient
+/// avec `embedded_io::Error::kind(&error)`.
+impl<E: fmt::Debug> embedded_io::Error for Fat32Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NotFound { .. } => ErrorKind::NotFound,
+            Self::AlreadyExists => ErrorKind::AlreadyExists,
+            Self::ReadOnly | Self::ReadOnlyFilesystem => ErrorKind::PermissionDenied,
+            Self::InvalidPath { .. }
+            | Self::NotADirectory
+            | Self::BufferTooSmall
+            | Self::NameTooLong
+            | Self::InvalidName(_)
+            | Self::InvalidInput => ErrorKind::InvalidInput,
+            Self::InvalidBootSector
+            | Self::InvalidCluster
+            | Self::EndOfChain
+            | Self::InvalidEntry
+            | Self::InvalidUtf8
+            | Self::Corrupted(_)
+            | Self::InvalidPartition
+            | Self::SectorSizeMismatch { .. } => ErrorKind::InvalidData,
+            Self::DiskFull => ErrorKind::OutOfMemory,
+            Self::DirectoryNotEmpty | Self::IoError { .. } | Self::Device(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// Handle de lecture avec son système de fichiers
+///
+/// Implémente `embedded_io::Read` et `Seek`, pour passer un fichier à un
+/// pilote qui attend ces traits. La position ne dépasse jamais la fin du
+/// fichier.
+pub struct FileReader<'a, D: BlockDevice> {
+    fs: &'a mut Fat32FileSystem<D>,
+    file: FatFile,
+}
+
+impl<'a, D: BlockDevice> FileReader<'a, D> {
+    /// Lire `file` (ouvert en lecture) à travers `fs`
+    pub fn new(fs: &'a mut Fat32FileSystem<D>, file: FatFile) -> Self {
+        Self { fs, file }
+    }
+
+    /// Handle sous-jacent
+    pub fn file(&self) -> &FatFile {
+        &self.file
+    }
+
+    /// Récupérer le handle (avec sa position)
+    pub fn into_inner(self) -> FatFile {
+        self.file
+    }
+}
+
+impl<D: BlockDevice> ErrorType for FileReader<'_, D> {
+    type Error = Fat32Error<D::Error>;
+}
+
+impl<D: BlockDevice> Read for FileReader<'_, D> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.fs.read_handle(&mut self.file, buf)
+    }
+}
+
+impl<D: BlockDevice> Seek for FileReader<'_, D> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => (self.file.size() as u64).checked_add_signed(delta),
+            SeekFrom::Current(delta) => (self.file.position() as u64).checked_add_signed(delta),
+        };
+        let target = target.ok_or(Fat32Error::InvalidInput)?;
+
+        self.file.set_position(target.min(self.file.size() as u64) as u32);
+        Ok(self.file.position() as u64)
+    }
+}
+
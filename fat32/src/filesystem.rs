@@ -1,267 +1,2590 @@
 //! Système de fichiers FAT32
 
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
 use alloc::vec::Vec;
-use crate::{BlockDevice, BootSector, DirectoryEntry, Fat32Error, FatTable, Result};
+use crate::boot_sector::{FAT_EOC, FS_INFO_LEAD_SIG, FS_INFO_STRUC_SIG, FS_INFO_TRAIL_SIG};
+use crate::cache::{AlignedBuf, SectorBuf, SectorCache};
+use crate::path::{
+    last_component, lookup_steps, split_parent, validate_component, validate_lookup_component, Step,
+};
+use crate::devices::{
+    check_bounds, read_sector_aligned, read_sectors_aligned, write_sector_aligned,
+    write_sectors_aligned,
+};
+use crate::directory::{is_listed, matches_name, DirScanner, DirSlot};
+use crate::{
+    BlockDevice, BootSector, CorruptionKind, DirectoryEntry, EntryDefect, Fat32Error, FatCache,
+    FatDate, FatFile, FatTable, FatTime, FileAttributes, IntegrityReport, IoOp, Lines, OpenOptions,
+    Result, TimeSource,
+};
 
+/// Nombre de secteurs de répertoire gardés en mémoire
+const DIR_CACHE_SECTORS: usize = 16;
+
+/// Nombre maximum d'entrées d'un répertoire (limite de la spec FAT)
+pub(crate) const MAX_DIR_ENTRIES: usize = 65_536;
+
+/// Résultat de la défragmentation d'un fichier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DefragStats {
+    /// Nombre de clusters recopiés
+    pub clusters_moved: u32,
+    /// Le fichier occupe une seule zone contiguë
+    pub contiguous: bool,
+}
+
+/// Occupation d'un répertoire, voir `Fat32FileSystem::count_entries`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DirCounts {
+    /// Fichiers (hors noms longs et nom de volume)
+    pub files: u32,
+    /// Sous-répertoires (hors "." et "..")
+    pub directories: u32,
+    /// Emplacements d'entrées supprimées, réutilisables
+    pub deleted: u32,
+    /// Emplacements jamais utilisés, après la marque de fin
+    pub free: u32,
+}
+
+/// Description du volume, voir `Fat32FileSystem::volume_info`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VolumeInfo {
+    /// Nom de volume du secteur de boot (voir `BootSector::volume_label_str`)
+    pub label: String,
+    /// Numéro de série du volume
+    pub serial: u32,
+    /// Nombre total de secteurs
+    pub total_sectors: u32,
+    /// Taille d'un cluster en octets
+    pub cluster_size: u32,
+    /// Clusters libres
+    pub free_clusters: u32,
+    /// Clusters occupés
+    pub used_clusters: u32,
+    /// Version du format FAT32
+    pub fs_version: u16,
+    /// Nom OEM du secteur de boot, sans les espaces de fin
+    pub oem_name: String,
+    /// Premier cluster du répertoire racine
+    pub root_cluster: u32,
+    /// Secteur de la copie du secteur de boot
+    pub backup_boot_sector: u16,
+    /// Secteur FSInfo
+    pub fs_info_sector: u16,
+}
+
+/// Façon de rattacher un cluster à une entrée dans `path_of_cluster_with`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClusterLookup {
+    /// Comparer seulement le premier cluster de chaque entrée (rapide)
+    FirstCluster,
+    /// Parcourir la chaîne complète de chaque entrée (lent mais exhaustif)
+    FullChain,
+}
+
+/// Répertoire de départ pour les opérations ancrées (`read_file_at`, ...)
+///
+/// Les chemins relatifs partent de ce répertoire au lieu du répertoire
+/// courant ; un chemin qui commence par `/` part toujours de la racine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DirRef {
+    /// Répertoire racine
+    Root,
+    /// Répertoire identifié par son premier cluster (voir `open_dir`)
+    Cluster(u32),
+}
+
+/// Position d'une entrée : cluster du répertoire + offset en octets
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct EntryLocation {
+    pub(crate) cluster: u32,
+    pub(crate) offset: usize,
+    /// Premier slot du nom long qui précède l'entrée (cluster, offset)
+    pub(crate) long_name: Option<(u32, usize)>,
+}
+
+/// Système de fichiers FAT32 monté sur un device
+///
+/// Les données des fichiers sont écrites immédiatement, mais les secteurs de
+/// la FAT et des répertoires modifiés restent en mémoire jusqu'à `flush()`.
+/// Voir `flush()` pour l'ordre des écritures.
+///
+/// La première modification efface le bit « propre » du volume (bit 27 de
+/// l'entrée 1 de la FAT) sur le disque ; `unmount` le remet. Un volume
+/// trouvé sans ce bit (coupure pendant un montage précédent) le reste.
 pub struct Fat32FileSystem<D: BlockDevice> {
     device: D,
     boot_sector: BootSector,
     current_directory: u32, // cluster du répertoire courant
+    fat_cache: FatCache,
+    dir_cache: SectorCache,
+    time_source: Option<Box<dyn TimeSource + Send>>,
+    in_batch: bool,
+    readonly: bool,
+    /// Une modification a eu lieu depuis le montage (voir `check_writable`)
+    in_use: bool,
+    /// Le bit « propre » a été effacé par ce montage : le remettre à la fin
+    restore_clean: bool,
 }
 
-impl<D: BlockDevice> Fat32FileSystem<D> {
-    /// Créer un nouveau système de fichiers
-    pub fn new(mut device: D) -> Result<Self> {
-        // Lire le boot sector
-        let mut buffer = alloc::vec![0u8; 512];
-        device.read_sector(0, &mut buffer)?;
+impl<D: BlockDevice> Fat32FileSystem<D> {
+    /// Créer un nouveau système de fichiers
+    ///
+    /// Le compteur de clusters libres de FSInfo est vérifié dans la FAT s'il
+    /// est inconnu (ou toujours, dans une compilation avec
+    /// `debug_assertions`), et corrigé s'il est faux. Un volume FAT12 ou
+    /// FAT16 donne `InvalidBootSector` (voir `BootSector::is_fat32`).
+    pub fn new(device: D) -> Result<Self, D::Error> {
+        Self::mount(device, false)
+    }
+
+    /// Comme `new`, mais compte toujours les clusters libres dans la FAT
+    ///
+    /// `Corrupted` si le compteur de FSInfo s'écarte de plus de 1 % du
+    /// nombre de clusters ; un petit écart (coupure avant l'écriture de
+    /// FSInfo) est corrigé. Si la FAT est en miroir (bit 7 de `ext_flags` à
+    /// zéro), ses copies doivent aussi être identiques à la FAT1.
+    pub fn new_strict(device: D) -> Result<Self, D::Error> {
+        Self::mount(device, true)
+    }
+
+    fn mount(mut device: D, strict: bool) -> Result<Self, D::Error> {
+        let boot_sector = Self::read_boot_sector(&mut device)?;
+        let current_directory = boot_sector.root_cluster();
+
+        let mut fs = Self {
+            device,
+            boot_sector,
+            current_directory,
+            fat_cache: FatCache::new(),
+            dir_cache: SectorCache::new(DIR_CACHE_SECTORS),
+            time_source: None,
+            in_batch: false,
+            readonly: false,
+            in_use: false,
+            restore_clean: false,
+        };
+        fs.load_fs_info(strict)?;
+        Ok(fs)
+    }
+
+    /// Relire le volume sur le device, comme un nouveau montage
+    ///
+    /// Pour un device modifié par ailleurs (autre processus sur l'image...) :
+    /// le boot sector est relu et vérifié comme par `new`, les secteurs de
+    /// FAT et de répertoire en mémoire sont oubliés, FSInfo est relu et le
+    /// répertoire courant redevient la racine. Les écritures en attente sont
+    /// perdues : appeler `flush()` avant pour les garder. Si le boot sector
+    /// est refusé, rien ne change. Un lot ouvert le reste, et en lecture
+    /// seule un compteur FSInfo corrigé n'est pas réécrit (voir `with_readonly`).
+    /// Si ce montage a effacé le bit « propre » du volume, `unmount` le
+    /// remet toujours.
+    pub fn hard_reset(&mut self) -> Result<(), D::Error> {
+        let boot_sector = Self::read_boot_sector(&mut self.device)?;
+        self.boot_sector = boot_sector;
+        self.current_directory = boot_sector.root_cluster();
+        self.fat_cache = FatCache::new();
+        self.dir_cache = SectorCache::new(DIR_CACHE_SECTORS);
+        if self.in_batch {
+            self.fat_cache.set_unbounded(true);
+            self.dir_cache.set_capacity(usize::MAX);
+        }
+        self.load_fs_info(false)?;
+        if self.readonly {
+            self.fat_cache.mark_info_clean();
+        }
+        Ok(())
+    }
+
+    /// Lire et vérifier le boot sector (voir `new`)
+    fn read_boot_sector(device: &mut D) -> Result<BootSector, D::Error> {
+        // Un secteur FAT32 fait au moins 512 octets
+        let align = device.buffer_alignment();
+        let mut buffer = AlignedBuf::zeroed(device.sector_size().max(512), align);
+        read_sector_aligned(device, 0, &mut buffer)?;
+
+        let boot_sector = unsafe { BootSector::from_bytes(&buffer) };
+        boot_sector.validate().map_err(Fat32Error::widen)?;
+        boot_sector.check_sector_size(device.sector_size()).map_err(Fat32Error::widen)?;
+
+        // FAT12 ou FAT16 : entrées de FAT et racine différentes, refusé,
+        // même avec un BPB FAT32 (le type vient du nombre de clusters)
+        if !boot_sector.is_fat32() || boot_sector.has_fat16_layout() {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+
+        // Un volume plus grand que le device : boot sector faux ou mauvais device
+        if boot_sector.total_sectors() as u64 > device.num_sectors() {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+
+        // Une FAT trop petite pour tous les clusters (entrées 0 et 1 comprises)
+        let fat_bytes = boot_sector.fat_size() as u64 * boot_sector.bytes_per_sector() as u64;
+        if fat_bytes < (boot_sector.num_clusters() as u64 + 2) * 4 {
+            return Err(Fat32Error::InvalidBootSector);
+        }
+        Ok(boot_sector)
+    }
+
+    /// Lire FSInfo, puis faire les vérifications du montage (voir `new_strict`)
+    fn load_fs_info(&mut self, strict: bool) -> Result<(), D::Error> {
+        self.read_fs_info()?;
+        if strict && self.boot_sector.ext_flags() & 0x80 == 0 {
+            self.fat().check_copies()?;
+        }
+        if strict || cfg!(debug_assertions) || self.fat_cache.free_count().is_none() {
+            self.check_free_count(strict)?;
+        }
+        Ok(())
+    }
+
+    /// Monter le système de fichiers en lecture seule
+    ///
+    /// Le device n'a pas à refuser les écritures : c'est le système de
+    /// fichiers qui les refuse (voir `set_readonly`). Un compteur FSInfo
+    /// corrigé au montage n'est pas réécrit.
+    pub fn with_readonly(device: D) -> Result<Self, D::Error> {
+        let mut fs = Self::new(device)?;
+        fs.readonly = true;
+        fs.fat_cache.mark_info_clean();
+        Ok(fs)
+    }
+
+    /// Monter le système de fichiers en datant les entrées avec `time_source`
+    pub fn with_time_source<T>(device: D, time_source: T) -> Result<Self, D::Error>
+    where
+        T: TimeSource + Send + 'static,
+    {
+        let mut fs = Self::new(device)?;
+        fs.set_time_source(time_source);
+        Ok(fs)
+    }
+
+    /// Le système de fichiers refuse-t-il les écritures ?
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Refuser (ou de nouveau accepter) les écritures
+    ///
+    /// En lecture seule, les opérations qui modifient le volume
+    /// (`write_file`, `create_dir`, `delete_file`, `write_handle`,
+    /// `open_with` en écriture...) échouent avec `ReadOnlyFilesystem` avant
+    /// de toucher au device. `flush`, `unmount` et `Drop` n'écrivent plus
+    /// rien : ce qui était en attente reste en mémoire jusqu'au retour en
+    /// écriture (appeler `flush()` avant de passer en lecture seule).
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.readonly = readonly;
+    }
+
+    /// Changer la source de l'heure courante
+    ///
+    /// Sans source (par défaut), les dates des entrées créées restent à zéro.
+    pub fn set_time_source<T: TimeSource + Send + 'static>(&mut self, time_source: T) {
+        self.time_source = Some(Box::new(time_source));
+    }
+
+    /// Formater le device et monter le système de fichiers obtenu
+    pub fn format(
+        mut device: D,
+        total_sectors: u32,
+        sectors_per_cluster: u8,
+    ) -> Result<Self, D::Error> {
+        BootSector::format(&mut device, total_sectors, sectors_per_cluster)?;
+        let mut fs = Self::new(device)?;
+
+        fs.initialize_root_directory()?;
+        fs.flush()?;
+
+        Ok(fs)
+    }
+
+    /// Vider le répertoire racine (appelé par `format`)
+    ///
+    /// Le cluster racine est mis à zéro, ce qui place la marque de fin de
+    /// répertoire sur la première entrée, et devient une chaîne d'un seul
+    /// cluster dans la FAT. La racine FAT32 n'a pas d'entrées "." et "..".
+    /// Sur un volume déjà rempli, les clusters de l'ancienne arborescence ne
+    /// sont pas libérés.
+    pub fn initialize_root_directory(&mut self) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let root = self.boot_sector.root_cluster();
+        let zeros = self.cluster_buffer();
+        self.write_cluster(root, &zeros)?;
+        self.fat().write_entry(root, FAT_EOC)
+    }
+
+    /// Boot sector du volume monté
+    pub fn boot_sector(&self) -> &BootSector {
+        &self.boot_sector
+    }
+
+    /// Accéder au device sous-jacent
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+
+    /// Accéder au device en écriture
+    ///
+    /// Attention : des écritures brutes peuvent rendre obsolètes les secteurs
+    /// de FAT et de répertoire gardés en mémoire. Appeler `flush()` avant, puis
+    /// `clear_caches()` après avoir modifié le device directement.
+    pub fn device_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Oublier les secteurs mis en cache à partir du device
+    ///
+    /// Les secteurs modifiés pas encore écrits sont gardés : appeler `flush()`
+    /// avant pour tout vider.
+    pub fn clear_caches(&mut self) {
+        self.fat_cache.clear();
+        self.dir_cache.clear_clean();
+    }
+
+    /// Démonter le système de fichiers et récupérer le device
+    ///
+    /// Tout ce qui est en attente d'écriture est écrit et le volume marqué
+    /// propre avant de rendre le device, comme par `unmount` ; une erreur à
+    /// ce moment est ignorée, appeler `flush()` avant pour la récupérer, ou
+    /// utiliser `unmount`.
+    pub fn into_device(mut self) -> D {
+        let _ = self.close();
+        self.take_device()
+    }
+
+    /// Démonter proprement : tout écrire, marquer le volume propre, rendre le device
+    ///
+    /// Le bit « propre » n'est remis que s'il était levé avant la première
+    /// modification de ce montage (voir `Fat32FileSystem`). En cas d'erreur,
+    /// le système de fichiers est rendu avec l'erreur : rien n'est perdu et
+    /// l'appel peut être refait. En lecture seule, le device est rendu tel
+    /// quel, bit "propre" compris.
+    #[allow(clippy::result_large_err)] // rendre `Self` est le but de l'erreur
+    pub fn unmount(mut self) -> core::result::Result<D, (Self, Fat32Error<D::Error>)> {
+        match self.close() {
+            Ok(()) => Ok(self.take_device()),
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    /// Remettre le bit « propre » si ce montage l'a effacé, puis tout écrire
+    fn close(&mut self) -> Result<(), D::Error> {
+        if self.readonly {
+            return Ok(());
+        }
+        if self.restore_clean {
+            self.fat().set_volume_clean(true)?;
+            // Après un échec, une nouvelle modification efface le bit à nouveau
+            self.in_use = false;
+            self.restore_clean = false;
+        }
+        self.flush()
+    }
+
+    /// Sortir le device sans passer par `Drop`
+    fn take_device(self) -> D {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` n'est plus utilisé ensuite et n'est jamais détruit :
+        // chaque champ est soit déplacé (device), soit détruit une seule fois.
+        // Les champs sans destructeur (boot sector, entiers) sont ignorés.
+        unsafe {
+            let device = core::ptr::read(&this.device);
+            core::ptr::drop_in_place(&mut this.fat_cache);
+            core::ptr::drop_in_place(&mut this.dir_cache);
+            core::ptr::drop_in_place(&mut this.time_source);
+            device
+        }
+    }
+
+    /// Des modifications attendent d'être écrites sur le disque
+    pub fn is_dirty(&self) -> bool {
+        self.fat_cache.is_dirty() || self.fat_cache.is_info_dirty() || self.dir_cache.is_dirty()
+    }
+
+    /// Écrire toutes les modifications en attente
+    ///
+    /// L'ordre est toujours le même, c'est le seul endroit qui le décide :
+    /// 1. les données des fichiers (déjà écrites au fil des opérations),
+    /// 2. la FAT, FAT1 puis ses copies, secteur par secteur,
+    /// 3. les secteurs de répertoire,
+    /// 4. le secteur FSInfo,
+    /// 5. `BlockDevice::flush`.
+    ///
+    /// Une coupure pendant le flush laisse au pire des clusters alloués mais
+    /// non référencés pour une création. Pour une suppression, l'entrée peut
+    /// encore pointer vers des clusters déjà libérés. En lecture seule, rien
+    /// n'est écrit (voir `set_readonly`).
+    pub fn flush(&mut self) -> Result<(), D::Error> {
+        if self.readonly {
+            return Ok(());
+        }
+        self.flush_metadata()?;
+        self.device.flush()
+    }
+
+    /// Écrire la FAT, les répertoires et FSInfo (étapes 2 à 4 de `flush`)
+    fn flush_metadata(&mut self) -> Result<(), D::Error> {
+        self.fat().flush()?;
+
+        for sector in self.dir_cache.dirty_sectors() {
+            if let Some(data) = self.dir_cache.peek(sector) {
+                write_sector_aligned(&mut self.device, sector, data)?;
+            }
+            self.dir_cache.mark_clean(sector);
+        }
+
+        if self.fat_cache.is_info_dirty() {
+            self.write_fs_info()?;
+            self.fat_cache.mark_info_clean();
+        }
+
+        Ok(())
+    }
+
+    /// Différer les écritures de la FAT et des répertoires jusqu'à `end_batch`
+    ///
+    /// Les caches ne sont plus limités : aucun secteur de métadonnées n'est
+    /// écrit par éviction, tout part en une fois à la fin du lot. Les données
+    /// des fichiers sont toujours écrites immédiatement, et les opérations qui
+    /// flushent elles-mêmes (`write_file_atomic`, `defragment_file`,
+    /// `compact_dir`...) le font encore. Sans effet si un lot est déjà ouvert.
+    pub fn begin_batch(&mut self) {
+        if !self.in_batch {
+            self.in_batch = true;
+            self.fat_cache.set_unbounded(true);
+            self.dir_cache.set_capacity(usize::MAX);
+        }
+    }
+
+    /// Fermer le lot : tout écrire (voir `flush`) et remettre les caches à leur taille
+    ///
+    /// Les caches reprennent leur taille même si l'écriture échoue ; les
+    /// secteurs modifiés restent alors en mémoire pour un prochain `flush`.
+    pub fn end_batch(&mut self) -> Result<(), D::Error> {
+        let result = self.flush();
+        self.in_batch = false;
+        self.fat_cache.set_unbounded(false);
+        self.dir_cache.set_capacity(DIR_CACHE_SECTORS);
+        result
+    }
+
+    /// Exécuter `f` dans un lot (voir `begin_batch`)
+    ///
+    /// Il n'y a pas de retour en arrière : ce que `f` a fait avant une erreur
+    /// est écrit quand même. Si `f` échoue et que l'écriture finale réussit,
+    /// l'erreur de `f` est retournée ; les opérations terminées avant elle
+    /// sont sur le disque, celle qui a échoué a fait le même nettoyage que
+    /// hors lot. Si l'écriture finale échoue, c'est son erreur qui est
+    /// retournée et le disque peut n'avoir qu'une partie du lot : appeler
+    /// `flush` pour réessayer. Dans un lot déjà ouvert, `f` est simplement
+    /// exécuté.
+    pub fn batch<T, F>(&mut self, f: F) -> Result<T, D::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, D::Error>,
+    {
+        if self.in_batch {
+            return f(self);
+        }
+        self.begin_batch();
+        let result = f(self);
+        self.end_batch()?;
+        result
+    }
+
+    /// Obtenir le cluster du répertoire courant
+    pub fn current_dir(&self) -> u32 {
+        self.current_directory
+    }
+
+    /// Changer de répertoire
+    pub fn change_dir(&mut self, path: &str) -> Result<(), D::Error> {
+        let cluster = self.resolve_path(path)?;
+        
+        // Vérifier que c'est bien un dossier
+        let _ = self.read_directory(cluster)?;
+        
+        self.current_directory = cluster;
+        Ok(())
+    }
+
+    /// Lister les fichiers d'un répertoire
+    pub fn list_dir(&mut self, path: Option<&str>) -> Result<Vec<DirectoryEntry>, D::Error> {
+        let cwd = DirRef::Cluster(self.current_directory);
+        self.list_dir_at(cwd, path.unwrap_or(""))
+    }
+
+    /// Lister un répertoire avec les noms longs (VFAT) des entrées
+    ///
+    /// Le nom long est `None` si l'entrée n'en a pas, ou si ses entrées de nom
+    /// long sont incomplètes ou ne correspondent pas au nom 8.3.
+    pub fn list_dir_with_long_names(
+        &mut self,
+        path: Option<&str>,
+    ) -> Result<Vec<(DirectoryEntry, Option<String>)>, D::Error> {
+        let cluster = self.resolve_path_from(self.current_directory, path.unwrap_or(""))?;
+        self.read_directory_named(cluster)
+    }
+
+    /// Lister une fenêtre de `count` entrées à partir de l'entrée `start`
+    ///
+    /// Les indices sont ceux de `list_dir` (entrées supprimées, noms longs et
+    /// nom de volume exclus). Le booléen indique s'il reste des entrées après
+    /// la fenêtre. Les clusters du répertoire situés après la fenêtre ne sont
+    /// pas lus, sauf pour trouver la prochaine entrée.
+    pub fn list_dir_page(
+        &mut self,
+        path: Option<&str>,
+        start: usize,
+        count: usize,
+    ) -> Result<(Vec<DirectoryEntry>, bool), D::Error> {
+        let mut cluster = match path {
+            Some(p) => self.resolve_path(p)?,
+            None => self.current_directory,
+        };
+
+        let mut page = Vec::new();
+        let mut index = 0;
+        let mut cluster_index = 0;
+        loop {
+            let data = self.read_cluster(cluster)?;
+            for chunk in data.chunks_exact(DirectoryEntry::SIZE) {
+                let entry = unsafe { DirectoryEntry::from_bytes(chunk) };
+                if entry.is_end() {
+                    return Ok((page, false));
+                }
+                if !is_listed(&entry) {
+                    continue;
+                }
+
+                if index >= start {
+                    if page.len() == count {
+                        return Ok((page, true));
+                    }
+                    page.push(entry);
+                }
+                index += 1;
+            }
+
+            // Suivre la chaîne un cluster à la fois
+            match self.next_dir_cluster(cluster, &mut cluster_index)? {
+                Some(next) => cluster = next,
+                None => return Ok((page, false)),
+            }
+        }
+    }
+
+    /// Toute l'arborescence sous un répertoire, en une liste triée par chemin
+    ///
+    /// Chaque fichier et dossier apparaît avec son chemin absolu en noms 8.3
+    /// (comme `path_of_cluster`) ; les dossiers ont un `/` final. Le
+    /// répertoire de départ (`path`, ou le répertoire courant) n'est pas dans
+    /// la liste. Un dossier déjà visité (FAT corrompue) n'est pas reparcouru.
+    pub fn list_dir_recursive_to_flat(
+        &mut self,
+        path: Option<&str>,
+    ) -> Result<Vec<(String, DirectoryEntry)>, D::Error> {
+        // Le préfixe vient des dossiers traversés en résolvant `path`
+        let path = path.unwrap_or("");
+        let (absolute, _) = lookup_steps(path);
+        let mut names = if absolute {
+            Vec::new()
+        } else {
+            match self.dir_names(self.current_directory)? {
+                Some(names) => names,
+                None => return Err(Fat32Error::not_found(path)),
+            }
+        };
+        let start = self.walk_path_from(self.current_directory, path, |entry| match entry {
+            Some(entry) => names.push(entry.short_name()),
+            None => {
+                names.pop();
+            }
+        })?;
+        let prefix: String = names.iter().map(|name| alloc::format!("/{}", name)).collect();
+
+        let mut flat = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut pending = alloc::vec![(start, prefix)];
+        while let Some((dir_cluster, dir_path)) = pending.pop() {
+            if !visited.insert(dir_cluster) {
+                continue;
+            }
+            for entry in self.read_directory(dir_cluster)? {
+                if entry.is_dot() || entry.is_dot_dot() {
+                    continue;
+                }
+                let path = alloc::format!("{}/{}", dir_path, entry.short_name());
+                if entry.attributes().is_directory() {
+                    if entry.first_cluster() >= 2 {
+                        pending.push((entry.first_cluster(), path.clone()));
+                    }
+                    flat.push((path + "/", entry));
+                } else {
+                    flat.push((path, entry));
+                }
+            }
+        }
+
+        flat.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(flat)
+    }
+
+    /// Compter les entrées d'un répertoire sans les garder en mémoire
+    ///
+    /// Un seul passage sur la chaîne avec un seul tampon de cluster.
+    pub fn count_entries(&mut self, path: Option<&str>) -> Result<DirCounts, D::Error> {
+        let mut cluster = match path {
+            Some(p) => self.resolve_path(p)?,
+            None => self.current_directory,
+        };
+
+        let mut counts = DirCounts::default();
+        let mut buffer = self.cluster_buffer();
+        let mut cluster_index = 0;
+        let mut ended = false;
+        loop {
+            if ended {
+                // Après la marque de fin, tout le reste de la chaîne est libre
+                counts.free += (buffer.len() / DirectoryEntry::SIZE) as u32;
+            } else {
+                self.read_cluster_into(cluster, &mut buffer)?;
+                for chunk in buffer.chunks_exact(DirectoryEntry::SIZE) {
+                    let entry = unsafe { DirectoryEntry::from_bytes(chunk) };
+                    if ended || entry.is_end() {
+                        ended = true;
+                        counts.free += 1;
+                    } else if entry.is_free() {
+                        counts.deleted += 1;
+                    } else if !is_listed(&entry) || entry.is_dot() || entry.is_dot_dot() {
+                        continue;
+                    } else if entry.attributes().is_directory() {
+                        counts.directories += 1;
+                    } else {
+                        counts.files += 1;
+                    }
+                }
+            }
+
+            match self.next_dir_cluster(cluster, &mut cluster_index)? {
+                Some(next) => cluster = next,
+                None => return Ok(counts),
+            }
+        }
+    }
+
+    /// Chercher une entrée dans le répertoire qui commence au cluster `dir_cluster`
+    ///
+    /// Le nom est comparé au nom 8.3 (casse ASCII ignorée) et au nom long
+    /// (casse ignorée). Retourne `Ok(None)` si rien ne correspond.
+    pub fn find_entry_in_dir(
+        &mut self,
+        dir_cluster: u32,
+        name: &str,
+    ) -> Result<Option<DirectoryEntry>, D::Error> {
+        Ok(self.find_entry(dir_cluster, name)?.map(|(_, entry)| entry))
+    }
+
+    /// Lire le contenu d'un fichier
+    pub fn read_file(&mut self, path: &str) -> Result<Vec<u8>, D::Error> {
+        let cwd = DirRef::Cluster(self.current_directory);
+        self.read_file_at(cwd, path)
+    }
+
+    /// Lire un fichier dans `buf` sans allouer de tampon pour son contenu
+    ///
+    /// Retourne le nombre d'octets écrits (la taille du fichier), ou
+    /// `BufferTooSmall` si `buf` est plus court que le fichier. Les clusters
+    /// entiers sont lus directement dans `buf`, le dernier seulement jusqu'à
+    /// la fin du fichier.
+    pub fn read_file_to_fixed_buf(&mut self, path: &str, buf: &mut [u8]) -> Result<usize, D::Error> {
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let entry = self
+            .find_entry_in_dir(dir_cluster, filename)?
+            .filter(|e| !e.attributes().is_directory())
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        let size = entry.file_size() as usize;
+        if buf.len() < size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+
+        if size > 0 && entry.first_cluster() < 2 {
+            return Err(Fat32Error::bad_entry(EntryDefect::MissingCluster));
+        }
+
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let mut cluster = entry.first_cluster();
+        let mut offset = 0;
+        while offset < size {
+            let len = (size - offset).min(cluster_size);
+            let out = &mut buf[offset..offset + len];
+            if len == cluster_size {
+                self.read_cluster_into(cluster, out)?;
+            } else {
+                self.read_cluster_prefix(cluster, out)?;
+            }
+            offset += len;
+
+            if offset < size {
+                // Chaîne plus courte que la taille annoncée
+                cluster = self.fat().next_cluster(cluster).map_err(|err| match err {
+                    Fat32Error::EndOfChain => Fat32Error::bad_entry(EntryDefect::ChainTooShort),
+                    err => err,
+                })?;
+            }
+        }
+        Ok(size)
+    }
+
+    /// Lire un fichier texte UTF-8
+    ///
+    /// Retourne `Fat32Error::InvalidUtf8` si le contenu n'est pas de l'UTF-8
+    /// valide. Une éventuelle marque BOM est conservée.
+    pub fn read_to_string(&mut self, path: &str) -> Result<String, D::Error> {
+        let data = self.read_file(path)?;
+        String::from_utf8(data).map_err(|_| Fat32Error::InvalidUtf8)
+    }
+
+    /// Parcourir un fichier texte ligne par ligne sans le charger en entier
+    pub fn read_lines(&mut self, path: &str) -> Result<Lines<'_, D>, D::Error> {
+        let file = self.open_file(path)?;
+        Ok(Lines::new(self, file))
+    }
+
+    /// Comparer le contenu de deux fichiers octet par octet
+    ///
+    /// Les tailles sont comparées d'abord ; ensuite les deux fichiers sont lus
+    /// en parallèle, un cluster à la fois, sans être chargés en entier.
+    pub fn compare_files(&mut self, path1: &str, path2: &str) -> Result<bool, D::Error> {
+        let mut file1 = self.open_file(path1)?;
+        let mut file2 = self.open_file(path2)?;
+        if file1.size() != file2.size() {
+            return Ok(false);
+        }
+
+        let mut buffer1 = self.cluster_buffer();
+        let mut buffer2 = self.cluster_buffer();
+        while !file1.is_eof() {
+            let read1 = self.read_handle(&mut file1, &mut buffer1)?;
+            let read2 = self.read_handle(&mut file2, &mut buffer2)?;
+            if buffer1[..read1] != buffer2[..read2] {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Ouvrir un répertoire à partir de `dir`, sans toucher au répertoire courant
+    pub fn open_dir(&mut self, dir: DirRef, path: &str) -> Result<DirRef, D::Error> {
+        let start = self.dir_cluster(dir);
+        let cluster = self.resolve_path_from(start, path)?;
+
+        // Vérifier que c'est bien un dossier
+        let _ = self.read_directory(cluster)?;
+
+        if cluster == self.boot_sector.root_cluster() {
+            Ok(DirRef::Root)
+        } else {
+            Ok(DirRef::Cluster(cluster))
+        }
+    }
+
+    /// Lister un répertoire relatif à `dir` (`""` pour `dir` lui-même)
+    pub fn list_dir_at(
+        &mut self,
+        dir: DirRef,
+        path: &str,
+    ) -> Result<Vec<DirectoryEntry>, D::Error> {
+        let start = self.dir_cluster(dir);
+        let cluster = self.resolve_path_from(start, path)?;
+        self.read_directory(cluster)
+    }
+
+    /// Lire le contenu d'un fichier relatif à `dir`
+    pub fn read_file_at(&mut self, dir: DirRef, path: &str) -> Result<Vec<u8>, D::Error> {
+        // Séparer le chemin et le nom du fichier
+        let start = self.dir_cluster(dir);
+        let (dir_cluster, filename) = self.parse_path_from(start, path)?;
+
+        // Trouver le fichier
+        let entry = self
+            .find_entry_in_dir(dir_cluster, filename)?
+            .filter(|e| !e.attributes().is_directory())
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        // Fichier vide
+        if entry.file_size() == 0 {
+            return Ok(Vec::new());
+        }
+
+        if entry.first_cluster() < 2 {
+            return Err(Fat32Error::bad_entry(EntryDefect::MissingCluster));
+        }
+
+        // Lire les clusters couverts par la taille (pas ceux réservés après)
+        let cluster_size = self.boot_sector.cluster_size();
+        let count = entry.file_size().div_ceil(cluster_size) as usize;
+        let mut fat = self.fat();
+        let clusters = fat.cluster_chain(entry.first_cluster())?;
+        if clusters.len() < count {
+            return Err(Fat32Error::bad_entry(EntryDefect::ChainTooShort));
+        }
+
+        // Chaque suite de clusters consécutifs est lue d'un coup
+        let mut data = alloc::vec![0u8; count * cluster_size as usize];
+        let mut index = 0;
+        while index < count {
+            let first = clusters[index];
+            let mut run = 1;
+            while index + run < count && clusters[index + run] == first + run as u32 {
+                run += 1;
+            }
+            let range = index * cluster_size as usize..(index + run) * cluster_size as usize;
+            self.read_clusters_into(first, run as u32, &mut data[range])?;
+            index += run;
+        }
+
+        // Tronquer à la vraie taille
+        data.truncate(entry.file_size() as usize);
+        Ok(data)
+    }
+
+    /// Ouvrir un fichier en lecture
+    ///
+    /// Voir `FatFile` pour le comportement si le fichier change ensuite.
+    pub fn open_file(&mut self, path: &str) -> Result<FatFile, D::Error> {
+        self.open_with(path, OpenOptions::new().read(true))
+    }
+
+    /// Ouvrir un fichier selon `options` (lecture, écriture, création...)
+    ///
+    /// `truncate` sans `create` sur un fichier absent donne `NotFound`,
+    /// `create_new` sur un fichier existant donne `AlreadyExists`.
+    pub fn open_with(&mut self, path: &str, options: &OpenOptions) -> Result<FatFile, D::Error> {
+        options.check().map_err(Fat32Error::widen)?;
+        if options.write || options.append {
+            self.check_writable()?;
+        }
+
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let (location, mut entry) = match self.find_entry(dir_cluster, filename)? {
+            Some(_) if options.create_new => return Err(Fat32Error::AlreadyExists),
+            Some(found) => found,
+            None if options.create || options.create_new => {
+                self.write_file(path, &[])?;
+                self.find_entry(dir_cluster, filename)?
+                    .ok_or_else(|| Fat32Error::not_found(path))?
+            }
+            None => return Err(Fat32Error::not_found(path)),
+        };
+
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
+
+        if options.truncate && (entry.file_size() > 0 || entry.first_cluster() >= 2) {
+            let first_cluster = entry.first_cluster();
+            entry.set_first_cluster(0);
+            entry.set_file_size(0);
+            if let Some((date, time)) = self.now() {
+                entry.set_modified(date, time);
+            }
+            self.write_entry_at(location, &entry)?;
+            if first_cluster >= 2 {
+                self.fat().free_chain(first_cluster)?;
+            }
+        }
+
+        // La chaîne peut dépasser la taille (clusters réservés par `reserve`)
+        let clusters = if entry.first_cluster() < 2 {
+            Vec::new()
+        } else {
+            self.fat().cluster_chain(entry.first_cluster())?
+        };
+
+        Ok(FatFile {
+            clusters,
+            size: entry.file_size(),
+            position: if options.append { entry.file_size() } else { 0 },
+            entry: location,
+            readable: options.read,
+            writable: options.write || options.append,
+            append: options.append,
+        })
+    }
+
+    /// Lire depuis un handle à sa position courante
+    ///
+    /// Retourne le nombre d'octets lus (0 en fin de fichier).
+    pub fn read_handle(&mut self, file: &mut FatFile, buf: &mut [u8]) -> Result<usize, D::Error> {
+        if !file.readable {
+            return Err(Fat32Error::InvalidInput);
+        }
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let mut read = 0;
+
+        while read < buf.len() && !file.is_eof() {
+            let position = file.position as usize;
+            let cluster = *file
+                .clusters
+                .get(position / cluster_size)
+                .ok_or(Fat32Error::bad_entry(EntryDefect::ChainTooShort))?;
+            let offset = position % cluster_size;
+
+            // Clusters entiers consécutifs : lus d'un coup dans `buf`
+            let whole = (buf.len() - read).min(file.remaining() as usize) / cluster_size;
+            if offset == 0 && whole > 0 {
+                let index = position / cluster_size;
+                let mut run = 1;
+                while run < whole && file.clusters.get(index + run) == Some(&(cluster + run as u32)) {
+                    run += 1;
+                }
+                let len = run * cluster_size;
+                self.read_clusters_into(cluster, run as u32, &mut buf[read..read + len])?;
+                read += len;
+                file.position += len as u32;
+                continue;
+            }
+
+            let count = (cluster_size - offset)
+                .min(buf.len() - read)
+                .min(file.remaining() as usize);
+
+            let data = self.read_cluster(cluster)?;
+            buf[read..read + count].copy_from_slice(&data[offset..offset + count]);
+
+            read += count;
+            file.position += count as u32;
+        }
+
+        Ok(read)
+    }
+
+    /// Écrire dans un handle à sa position courante (en fin de fichier en mode append)
+    ///
+    /// Le fichier grandit si besoin ; l'entrée du répertoire est mise à jour
+    /// à chaque appel. Retourne le nombre d'octets écrits.
+    pub fn write_handle(&mut self, file: &mut FatFile, data: &[u8]) -> Result<usize, D::Error> {
+        self.check_writable()?;
+        if !file.writable {
+            return Err(Fat32Error::InvalidInput);
+        }
+        if file.append {
+            file.position = file.size;
+        }
+        let end = u32::try_from(data.len())
+            .ok()
+            .and_then(|len| file.position.checked_add(len))
+            .ok_or(Fat32Error::DiskFull)?;
+
+        // Agrandir la chaîne
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let needed = (end as usize).div_ceil(cluster_size);
+        if needed > file.clusters.len() {
+            let mut fat = self.fat();
+            let first_new = fat.allocate_chain((needed - file.clusters.len()) as u32)?;
+            if let Some(&last) = file.clusters.last() {
+                fat.write_entry(last, first_new)?;
+            }
+            let new_clusters = fat.cluster_chain(first_new)?;
+            file.clusters.extend(new_clusters);
+        }
+
+        let mut written = 0;
+        while written < data.len() {
+            let position = file.position as usize;
+            let cluster = file.clusters[position / cluster_size];
+            let offset = position % cluster_size;
+            let count = (cluster_size - offset).min(data.len() - written);
+
+            // Cluster partiel : relire ce qui ne change pas
+            let mut buffer = if count == cluster_size {
+                self.cluster_buffer()
+            } else {
+                self.read_cluster(cluster)?
+            };
+            buffer[offset..offset + count].copy_from_slice(&data[written..written + count]);
+            self.write_cluster(cluster, &buffer)?;
+
+            written += count;
+            file.position += count as u32;
+        }
+        file.size = file.size.max(end);
+
+        // Mettre à jour l'entrée
+        let data = self.read_cluster(file.entry.cluster)?;
+        let offset = file.entry.offset;
+        let mut entry = unsafe { DirectoryEntry::from_bytes(&data[offset..]) };
+        entry.set_first_cluster(file.clusters.first().copied().unwrap_or(0));
+        entry.set_file_size(file.size);
+        if let Some((date, time)) = self.now() {
+            entry.set_modified(date, time);
+        }
+        self.write_entry_at(file.entry, &entry)?;
+
+        Ok(written)
+    }
+
+    /// Créer un fichier et y écrire `data`
+    pub fn write_file(&mut self, path: &str, data: &[u8]) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let size = u32::try_from(data.len()).map_err(|_| Fat32Error::DiskFull)?;
+        let mut chunks = data.chunks(self.boot_sector.cluster_size() as usize);
+
+        self.write_file_with(path, size, |buffer: &mut [u8]| {
+            if let Some(chunk) = chunks.next() {
+                buffer.copy_from_slice(chunk);
+            }
+            Ok::<(), Fat32Error<D::Error>>(())
+        })
+    }
+
+    /// Remplacer le contenu d'un fichier par `data`, ou le créer
+    ///
+    /// La chaîne existante est gardée et ajustée au nouveau nombre de
+    /// clusters (`FatTable::resize_cluster_chain`) : ni suppression ni
+    /// nouvelle entrée, la date de création reste, celle de modification
+    /// change. Une coupure en cours d'écriture laisse un mélange de l'ancien
+    /// et du nouveau contenu : voir `write_file_atomic` pour l'éviter. Si le
+    /// fichier n'existe pas, même chose que `write_file`.
+    pub fn overwrite_file(&mut self, path: &str, data: &[u8]) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let Some((location, mut entry)) = self.find_entry(dir_cluster, filename)? else {
+            return self.write_file(path, data);
+        };
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
+
+        let size = u32::try_from(data.len()).map_err(|_| Fat32Error::DiskFull)?;
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let count = data.len().div_ceil(cluster_size) as u32;
+        let mut fat = self.fat();
+        let first = fat.resize_cluster_chain(entry.first_cluster(), count)?;
+        let clusters = if first >= 2 { fat.cluster_chain(first)? } else { Vec::new() };
+
+        let mut buffer = self.cluster_buffer();
+        for (cluster, chunk) in clusters.into_iter().zip(data.chunks(cluster_size)) {
+            buffer[..chunk.len()].copy_from_slice(chunk);
+            buffer[chunk.len()..].fill(0);
+            self.write_cluster(cluster, &buffer)?;
+        }
+
+        entry.set_first_cluster(first);
+        entry.set_file_size(size);
+        if let Some((date, time)) = self.now() {
+            entry.set_modified(date, time);
+        }
+        self.write_entry_at(location, &entry)
+    }
+
+    /// Créer un fichier de `size` octets rempli cluster par cluster
+    ///
+    /// `fill` reçoit un tampon de la taille d'un cluster (plus court pour le
+    /// dernier) et doit le remplir entièrement : le fichier n'est jamais
+    /// entièrement en mémoire. Si `fill` échoue, les clusters déjà alloués
+    /// sont libérés et aucune entrée n'est créée.
+    pub fn write_file_with<E, F>(
+        &mut self,
+        path: &str,
+        size: u32,
+        fill: F,
+    ) -> core::result::Result<(), E>
+    where
+        E: From<Fat32Error<D::Error>>,
+        F: FnMut(&mut [u8]) -> core::result::Result<(), E>,
+    {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        self.create_file_with(dir_cluster, filename, last_component(path), size, fill)?;
+        Ok(())
+    }
+
+    /// Copier le fichier `src_path` vers `dst_path` d'un autre système de fichiers
+    ///
+    /// Les données passent cluster de destination par cluster de destination,
+    /// lues directement dans le tampon de `write_file_with` : les deux volumes
+    /// peuvent avoir des tailles de cluster différentes et le fichier n'est
+    /// jamais entièrement en mémoire. Les deux devices doivent avoir le même
+    /// type d'erreur. `NotFound` si la source n'existe pas.
+    pub fn copy_across<D2>(
+        &mut self,
+        src_path: &str,
+        dst_fs: &mut Fat32FileSystem<D2>,
+        dst_path: &str,
+    ) -> Result<(), D::Error>
+    where
+        D2: BlockDevice<Error = D::Error>,
+    {
+        let mut handle = self.open_file(src_path)?;
+
+        dst_fs.write_file_with(dst_path, handle.size(), |buffer: &mut [u8]| {
+            let mut filled = 0;
+            while filled < buffer.len() {
+                let read = self.read_handle(&mut handle, &mut buffer[filled..])?;
+                if read == 0 {
+                    // Chaîne plus courte que la taille de l'entrée
+                    return Err(Fat32Error::bad_entry(EntryDefect::ChainTooShort));
+                }
+                filled += read;
+            }
+            Ok(())
+        })
+    }
+
+    /// Remplacer le contenu d'un fichier sans jamais exposer un fichier tronqué
+    ///
+    /// Le nouveau contenu est écrit sous un nom temporaire `~ATOMnnn.TMP` du
+    /// même répertoire, puis tout est flushé. Ensuite, chaque étape est suivie
+    /// d'un `flush` :
+    /// 1. l'ancienne entrée est marquée supprimée,
+    /// 2. l'entrée temporaire prend le nom du fichier,
+    /// 3. les clusters de l'ancien contenu sont libérés.
+    ///
+    /// Si les deux entrées sont dans le même secteur, les étapes 1 et 2 sont
+    /// une seule écriture de secteur. Une coupure laisse donc :
+    /// - avant la fin du flush du fichier temporaire : l'ancien fichier, plus
+    ///   éventuellement des clusters perdus ou le fichier temporaire complet ;
+    /// - entre les étapes 1 et 2 : le nouveau contenu sous le nom temporaire,
+    ///   sans fichier au nom demandé ;
+    /// - après l'étape 2 : le nouveau fichier, plus éventuellement les
+    ///   clusters perdus de l'ancien.
+    ///
+    /// Le fichier au nom demandé n'a jamais un contenu partiel. Le disque doit
+    /// avoir la place pour l'ancien et le nouveau contenu en même temps.
+    pub fn write_file_atomic(&mut self, path: &str, data: &[u8]) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        validate_component(filename)?;
+        let name = DirectoryEntry::encode_short_name(filename)
+            .map_err(|e| e.at_component(last_component(path)).widen())?;
+
+        let old = self.find_entry(dir_cluster, filename)?;
+        if let Some((_, entry)) = &old {
+            if entry.attributes().is_directory() {
+                return Err(Fat32Error::invalid_path(path));
+            }
+        }
+
+        // Nom temporaire libre (un essai précédent interrompu a pu en laisser un)
+        let mut temp_name = None;
+        for counter in 0..1000 {
+            let candidate = alloc::format!("~ATOM{:03}.TMP", counter);
+            if self.find_entry(dir_cluster, &candidate)?.is_none() {
+                temp_name = Some(candidate);
+                break;
+            }
+        }
+        let temp_name = temp_name.ok_or(Fat32Error::AlreadyExists)?;
+
+        let size = u32::try_from(data.len()).map_err(|_| Fat32Error::DiskFull)?;
+        let mut chunks = data.chunks(self.boot_sector.cluster_size() as usize);
+        let component = last_component(path);
+        let (temp_location, mut entry) =
+            self.create_file_with(dir_cluster, &temp_name, component, size, |buffer: &mut [u8]| {
+                if let Some(chunk) = chunks.next() {
+                    buffer.copy_from_slice(chunk);
+                }
+                Ok::<(), Fat32Error<D::Error>>(())
+            })?;
+        self.flush()?;
+
+        let old_cluster = match old {
+            Some((location, mut old_entry)) => {
+                self.delete_entry_at(location, &mut old_entry)?;
+                if !self.same_sector(location, temp_location) {
+                    self.flush()?;
+                }
+                old_entry.first_cluster()
+            }
+            None => 0,
+        };
+
+        entry.set_name(name);
+        self.write_entry_at(temp_location, &entry)?;
+        self.flush()?;
+
+        if old_cluster >= 2 {
+            self.fat().free_chain(old_cluster)?;
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Créer l'entrée `filename` dans `dir_cluster` (voir `write_file_with`)
+    ///
+    /// `component` est l'index du nom dans le chemin, pour les erreurs.
+    fn create_file_with<E, F>(
+        &mut self,
+        dir_cluster: u32,
+        filename: &str,
+        component: u16,
+        size: u32,
+        mut fill: F,
+    ) -> core::result::Result<(EntryLocation, DirectoryEntry), E>
+    where
+        E: From<Fat32Error<D::Error>>,
+        F: FnMut(&mut [u8]) -> core::result::Result<(), E>,
+    {
+        validate_component(filename).map_err(Fat32Error::from)?;
+        let name = DirectoryEntry::encode_short_name(filename)
+            .map_err(|e| e.at_component(component).widen())?;
+
+        if self.find_entry(dir_cluster, filename)?.is_some() {
+            return Err(Fat32Error::AlreadyExists.into());
+        }
+
+        // Réserver l'emplacement de l'entrée avant d'allouer les données
+        let location = self.find_free_slot(dir_cluster)?;
+
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let count = (size as usize).div_ceil(cluster_size) as u32;
+        let first_cluster = if count == 0 {
+            0
+        } else {
+            let mut fat = self.fat();
+            let first = fat.allocate_chain(count)?;
+            let clusters = fat.cluster_chain(first)?;
+
+            let mut buffer = self.cluster_buffer();
+            let mut remaining = size as usize;
+            for cluster in clusters {
+                let len = remaining.min(cluster_size);
+                buffer[len..].fill(0);
+                let written = match fill(&mut buffer[..len]) {
+                    Ok(()) => self.write_cluster(cluster, &buffer).map_err(E::from),
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = written {
+                    self.fat().free_chain(first)?;
+                    return Err(err);
+                }
+                remaining -= len;
+            }
+            first
+        };
+
+        let mut entry = DirectoryEntry::new(name, FileAttributes::ARCHIVE, first_cluster, size);
+        if let Some((date, time)) = self.now() {
+            entry.set_created(date, time);
+        }
+        self.write_entry_at(location, &entry)?;
+        Ok((location, entry))
+    }
+
+    /// Créer un répertoire vide
+    pub fn create_dir(&mut self, path: &str) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (parent, dirname) = self.parse_path(path)?;
+        validate_component(dirname)?;
+        let name = DirectoryEntry::encode_short_name(dirname)
+            .map_err(|e| e.at_component(last_component(path)).widen())?;
+
+        if self.find_entry(parent, dirname)?.is_some() {
+            return Err(Fat32Error::AlreadyExists);
+        }
+
+        let location = self.find_free_slot(parent)?;
+        let cluster = self.fat().allocate_chain(1)?;
+
+        // Entrées "." et ".." (0 désigne la racine)
+        let now = self.now();
+        let parent_ref = if parent == self.boot_sector.root_cluster() { 0 } else { parent };
+        let mut entries = [
+            DirectoryEntry::new(*b".          ", FileAttributes::DIRECTORY, cluster, 0),
+            DirectoryEntry::new(*b"..         ", FileAttributes::DIRECTORY, parent_ref, 0),
+            DirectoryEntry::new(name, FileAttributes::DIRECTORY, cluster, 0),
+        ];
+        if let Some((date, time)) = now {
+            entries.iter_mut().for_each(|entry| entry.set_created(date, time));
+        }
+
+        let mut buffer = self.cluster_buffer();
+        buffer[..DirectoryEntry::SIZE].copy_from_slice(&entries[0].to_bytes());
+        buffer[DirectoryEntry::SIZE..2 * DirectoryEntry::SIZE].copy_from_slice(&entries[1].to_bytes());
+        self.write_cluster(cluster, &buffer)?;
+
+        self.write_entry_at(location, &entries[2])
+    }
+
+    /// Mettre à jour la date de modification d'un fichier, le créer vide s'il n'existe pas
+    ///
+    /// Sans source de temps, un fichier existant n'est pas modifié.
+    pub fn touch(&mut self, path: &str) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        match self.find_entry(dir_cluster, filename)? {
+            Some((location, mut entry)) => match self.now() {
+                Some((date, time)) => {
+                    entry.set_modified(date, time);
+                    self.write_entry_at(location, &entry)
+                }
+                None => Ok(()),
+            },
+            None => self.write_file(path, &[]),
+        }
+    }
+
+    /// Réserver des clusters pour que le fichier puisse atteindre `bytes` octets
+    ///
+    /// La chaîne est allongée, d'un seul bloc de clusters consécutifs si
+    /// possible, sans changer la taille du fichier : les écritures suivantes
+    /// d'un handle ouvert après la réservation (`write_handle`, mode append
+    /// compris) avancent dans ces clusters au lieu d'en allouer. Les lectures
+    /// s'arrêtent toujours à la taille. Sans assez de place, retourne
+    /// `DiskFull` sans rien allouer.
+    pub fn reserve(&mut self, path: &str, bytes: u64) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let (location, mut entry) = self
+            .find_entry(dir_cluster, filename)?
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
+        if bytes > u32::MAX as u64 {
+            return Err(Fat32Error::DiskFull);
+        }
+
+        let needed = bytes.div_ceil(self.boot_sector.cluster_size() as u64) as u32;
+        let first_cluster = entry.first_cluster();
+        let mut fat = self.fat();
+        let chain = if first_cluster < 2 { Vec::new() } else { fat.cluster_chain(first_cluster)? };
+        if needed <= chain.len() as u32 {
+            return Ok(());
+        }
+
+        let missing = needed - chain.len() as u32;
+        if chain.is_empty() {
+            entry.set_first_cluster(fat.allocate_contiguous_chain(missing)?);
+            return self.write_entry_at(location, &entry);
+        }
+        fat.extend_chain_by(first_cluster, missing).map(drop)
+    }
+
+    /// Supprimer un fichier et libérer ses clusters
+    pub fn delete_file(&mut self, path: &str) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let (location, mut entry) = self
+            .find_entry(dir_cluster, filename)?
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
+
+        // L'entrée disparaît d'abord : une coupure laisse au pire des clusters perdus
+        let first_cluster = entry.first_cluster();
+        self.delete_entry_at(location, &mut entry)?;
+
+        if first_cluster >= 2 {
+            self.fat().free_chain(first_cluster)?;
+        }
+        Ok(())
+    }
+
+    /// Supprimer un répertoire vide et libérer ses clusters
+    ///
+    /// `DirectoryNotEmpty` s'il contient autre chose que "." et "..",
+    /// `NotADirectory` pour un fichier. La racine et le répertoire courant
+    /// ne peuvent pas être supprimés (`InvalidPath`).
+    pub fn remove_dir(&mut self, path: &str) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (parent, dirname) = self.parse_path(path)?;
+        let (location, mut entry) = self
+            .find_entry(parent, dirname)?
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        if !entry.attributes().is_directory() {
+            return Err(Fat32Error::NotADirectory);
+        }
+        let cluster = entry.first_cluster();
+        if cluster == self.boot_sector.root_cluster() || cluster == self.current_directory {
+            return Err(Fat32Error::invalid_path(path));
+        }
+        if cluster >= 2 {
+            let entries = self.read_directory(cluster)?;
+            if entries.iter().any(|e| !e.is_dot() && !e.is_dot_dot()) {
+                return Err(Fat32Error::DirectoryNotEmpty);
+            }
+        }
+
+        // Comme `delete_file` : l'entrée d'abord, les clusters ensuite
+        self.delete_entry_at(location, &mut entry)?;
+        if cluster >= 2 {
+            self.fat().free_chain(cluster)?;
+        }
+        Ok(())
+    }
+
+    /// Compacter un répertoire et libérer ses clusters devenus inutiles
+    ///
+    /// Les entrées vivantes (noms longs compris, dans leur ordre) sont
+    /// réécrites à la suite dans les premiers clusters du répertoire, suivies
+    /// d'une marque de fin, puis la chaîne est coupée. Retourne le nombre de
+    /// clusters libérés. Un répertoire, même la racine, garde au moins un
+    /// cluster.
+    ///
+    /// Le premier cluster du répertoire et ceux de ses sous-répertoires ne
+    /// changent pas : le répertoire courant et les ".." restent valides. Les
+    /// entrées, elles, changent de place : un `FatFile` ouvert sur un fichier
+    /// de ce répertoire doit être rouvert avant d'y écrire.
+    ///
+    /// Une coupure pendant la réécriture peut dupliquer des entrées (copiées
+    /// vers l'avant, pas encore effacées à leur ancienne place), jamais en
+    /// perdre ; la chaîne n'est coupée qu'une fois les entrées sur le disque.
+    pub fn compact_dir(&mut self, path: &str) -> Result<u32, D::Error> {
+        self.check_writable()?;
+        let dir_cluster = self.resolve_path(path)?;
+        self.flush()?;
+
+        // Chaîne et entrées vivantes
+        let mut chain = alloc::vec![dir_cluster];
+        let mut live = Vec::new();
+        let mut cluster = dir_cluster;
+        let mut cluster_index = 0;
+        'read: loop {
+            let data = self.read_cluster(cluster)?;
+            for chunk in data.chunks_exact(DirectoryEntry::SIZE) {
+                match chunk[0] {
+                    0x00 => break 'read,
+                    0xE5 => {}
+                    _ => live.extend_from_slice(chunk),
+                }
+            }
+            match self.next_dir_cluster(cluster, &mut cluster_index)? {
+                Some(next) => {
+                    cluster = next;
+                    chain.push(next);
+                }
+                None => break,
+            }
+        }
+        // Clusters après une marque de fin
+        while let Some(next) = self.next_dir_cluster(cluster, &mut cluster_index)? {
+            cluster = next;
+            chain.push(next);
+        }
+
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let needed = live.len().div_ceil(cluster_size).max(1);
+
+        // Réécrire les entrées à la suite, le reste du dernier cluster à zéro
+        live.resize(needed * cluster_size, 0);
+        for (&cluster, data) in chain.iter().zip(live.chunks(cluster_size)) {
+            self.write_cluster(cluster, data)?;
+        }
+        self.device.flush()?;
+
+        if needed == chain.len() {
+            return Ok(0);
+        }
+        let mut fat = self.fat();
+        fat.write_entry(chain[needed - 1], FAT_EOC)?;
+        let freed = fat.free_chain(chain[needed])?;
+        self.flush()?;
+        Ok(freed)
+    }
+
+    /// Charger la FAT en cache (voir `FatTable::preload`)
+    pub fn preload_fat(&mut self) -> Result<(), D::Error> {
+        self.fat().preload()
+    }
+
+    /// Vérifier les références de chaque cluster (voir `FatTable::check_integrity`)
+    ///
+    /// Les modifications en attente sont d'abord écrites sur le device.
+    pub fn check_integrity(&mut self) -> Result<IntegrityReport, D::Error> {
+        self.flush()?;
+        self.fat().check_integrity()
+    }
+
+    /// Espace libre en octets
+    pub fn free_space(&mut self) -> Result<u64, D::Error> {
+        let free = self.fat().count_free_clusters()?;
+        Ok(free as u64 * self.boot_sector.cluster_size() as u64)
+    }
+
+    /// Toutes les informations du volume en un appel (à la manière de `statvfs`)
+    ///
+    /// Les clusters libres sont comptés dans la FAT, pas lus dans FSInfo.
+    pub fn volume_info(&mut self) -> Result<VolumeInfo, D::Error> {
+        let free_clusters = self.fat().count_free_clusters()?;
+        let bs = &self.boot_sector;
+        let text = |bytes: &[u8]| String::from(String::from_utf8_lossy(bytes).trim_end());
+
+        Ok(VolumeInfo {
+            label: bs.volume_label_str(),
+            serial: bs.volume_id(),
+            total_sectors: bs.total_sectors(),
+            cluster_size: bs.cluster_size(),
+            free_clusters,
+            used_clusters: bs.num_clusters() - free_clusters,
+            fs_version: bs.fs_version(),
+            oem_name: text(&bs.oem_name),
+            root_cluster: bs.root_cluster(),
+            backup_boot_sector: bs.backup_boot_sector(),
+            fs_info_sector: bs.fs_info(),
+        })
+    }
+
+    /// Changer le nom du volume (voir `DirectoryEntry::encode_volume_label`)
+    ///
+    /// Le nom est écrit dans l'entrée « volume » de la racine (créée au
+    /// besoin) et dans le boot sector et sa copie de secours. Un nom vide
+    /// supprime l'entrée et remet `NO NAME` dans le boot sector.
+    pub fn rename_volume(&mut self, new_label: &str) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let label = DirectoryEntry::encode_volume_label(new_label).map_err(Fat32Error::widen)?;
+        let clear = label == [b' '; 11];
+
+        let root = self.boot_sector.root_cluster();
+        match self.find_volume_entry(root)? {
+            Some((location, mut entry)) => {
+                if clear {
+                    entry.mark_deleted();
+                } else {
+                    entry.set_name(label);
+                    if let Some((date, time)) = self.now() {
+                        entry.set_modified(date, time);
+                    }
+                }
+                self.write_entry_at(location, &entry)?;
+            }
+            None if !clear => {
+                let location = self.find_free_slot(root)?;
+                let mut entry = DirectoryEntry::new(label, FileAttributes::VOLUME_ID, 0, 0);
+                if let Some((date, time)) = self.now() {
+                    entry.set_created(date, time);
+                }
+                self.write_entry_at(location, &entry)?;
+            }
+            None => {}
+        }
+
+        self.write_boot_label(if clear { *b"NO NAME    " } else { label })?;
+        self.flush()
+    }
+
+    /// Nombre de clusters de la chaîne commençant à `start_cluster`
+    pub fn cluster_chain_length(&mut self, start_cluster: u32) -> Result<u32, D::Error> {
+        self.fat().chain_length(start_cluster)
+    }
+
+    /// Nombre de clusters occupés par un fichier
+    pub fn used_clusters_by_file(&mut self, path: &str) -> Result<u32, D::Error> {
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let (_, entry) = self
+            .find_entry(dir_cluster, filename)?
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        if entry.first_cluster() < 2 {
+            return Ok(0);
+        }
+        self.cluster_chain_length(entry.first_cluster())
+    }
+
+    /// Rendre un fichier contigu sur le disque
+    ///
+    /// Les données sont copiées dans une zone libre contiguë, puis la nouvelle
+    /// chaîne est écrite dans la FAT, puis l'entrée du répertoire est mise à
+    /// jour, et enfin l'ancienne chaîne est libérée. Une coupure de courant
+    /// laisse donc soit l'ancien fichier intact, soit une copie non
+    /// référencée (clusters perdus), jamais un fichier cassé. Chaque étape
+    /// est écrite sur le device avant la suivante, la dernière comprise, même
+    /// dans un lot.
+    ///
+    /// Si aucune zone assez grande n'existe, rien n'est modifié et
+    /// `contiguous` vaut `false`.
+    pub fn defragment_file(&mut self, path: &str) -> Result<DefragStats, D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let (location, mut entry) = self
+            .find_entry(dir_cluster, filename)?
+            .ok_or_else(|| Fat32Error::not_found(path))?;
+
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
+        if entry.first_cluster() < 2 {
+            return Ok(DefragStats { clusters_moved: 0, contiguous: true });
+        }
+
+        let mut fat = self.fat();
+        let old_chain = fat.cluster_chain(entry.first_cluster())?;
+        if old_chain.windows(2).all(|w| w[1] == w[0] + 1) {
+            return Ok(DefragStats { clusters_moved: 0, contiguous: true });
+        }
+
+        let count = old_chain.len() as u32;
+        let Some(new_first) = fat.find_free_run(count)? else {
+            return Ok(DefragStats { clusters_moved: 0, contiguous: false });
+        };
+
+        // 1. Copier les données
+        for (i, &cluster) in old_chain.iter().enumerate() {
+            let data = self.read_cluster(cluster)?;
+            self.write_cluster(new_first + i as u32, &data)?;
+        }
+
+        // 2. Écrire la nouvelle chaîne
+        let mut fat = self.fat();
+        for i in 0..count {
+            let cluster = new_first + i;
+            let next = if i + 1 == count { FAT_EOC } else { cluster + 1 };
+            fat.write_entry(cluster, next)?;
+        }
+
+        self.flush()?;
+
+        // 3. Pointer l'entrée vers la nouvelle chaîne
+        entry.set_first_cluster(new_first);
+        self.write_entry_at(location, &entry)?;
+        self.flush()?;
+
+        // 4. Libérer l'ancienne chaîne
+        let mut fat = self.fat();
+        for cluster in old_chain {
+            fat.write_entry(cluster, 0)?;
+        }
+        self.flush()?;
+
+        Ok(DefragStats { clusters_moved: count, contiguous: true })
+    }
+
+    /// Trouver le chemin de l'entrée qui commence au cluster donné
+    ///
+    /// Retourne `Ok(None)` si aucune entrée ne commence à ce cluster.
+    /// Voir `path_of_cluster_with` pour chercher dans les chaînes complètes.
+    pub fn path_of_cluster(&mut self, cluster: u32) -> Result<Option<String>, D::Error> {
+        self.path_of_cluster_with(cluster, ClusterLookup::FirstCluster)
+    }
+
+    /// Trouver le chemin du fichier ou dossier qui possède un cluster
+    ///
+    /// En mode `FullChain`, chaque chaîne n'est parcourue qu'une fois : un
+    /// cluster déjà vu (chaînes croisées) arrête le parcours.
+    pub fn path_of_cluster_with(
+        &mut self,
+        cluster: u32,
+        mode: ClusterLookup,
+    ) -> Result<Option<String>, D::Error> {
+        let root = self.boot_sector.root_cluster();
+        let mut seen = BTreeSet::new();
+        if self.chain_owns(root, cluster, mode, &mut seen)? {
+            return Ok(Some("/".into()));
+        }
+
+        let mut visited_dirs = BTreeSet::new();
+        let mut pending = alloc::vec![(root, String::new())];
+
+        while let Some((dir_cluster, dir_path)) = pending.pop() {
+            if !visited_dirs.insert(dir_cluster) {
+                continue;
+            }
+
+            for entry in self.read_directory(dir_cluster)? {
+                if entry.is_dot() || entry.is_dot_dot() {
+                    continue;
+                }
+
+                let path = alloc::format!("{}/{}", dir_path, entry.short_name());
+                let first = entry.first_cluster();
+                if first >= 2 && self.chain_owns(first, cluster, mode, &mut seen)? {
+                    return Ok(Some(path));
+                }
+
+                if entry.attributes().is_directory() && first >= 2 {
+                    pending.push((first, path));
+                }
+            }
+        }
+
+        Ok(None)
+    }
 
-        let boot_sector = unsafe { BootSector::from_bytes(&buffer) };
-        boot_sector.validate()?;
+    /// La chaîne commençant à `start` contient-elle `cluster` ?
+    fn chain_owns(
+        &mut self,
+        start: u32,
+        cluster: u32,
+        mode: ClusterLookup,
+        seen: &mut BTreeSet<u32>,
+    ) -> Result<bool, D::Error> {
+        if start == cluster {
+            return Ok(true);
+        }
+        if mode == ClusterLookup::FirstCluster {
+            return Ok(false);
+        }
 
-        let current_directory = boot_sector.root_cluster;
+        let mut fat = self.fat();
+        let mut current = start;
+        while seen.insert(current) {
+            match fat.next_cluster(current) {
+                Ok(next) if next == cluster => return Ok(true),
+                Ok(next) => current = next,
+                Err(Fat32Error::EndOfChain)
+                | Err(Fat32Error::InvalidCluster)
+                | Err(Fat32Error::Corrupted(_)) => break,
+                Err(e) => return Err(e),
+            }
+        }
 
-        Ok(Self {
-            device,
-            boot_sector,
-            current_directory,
-        })
+        Ok(false)
     }
 
-    /// Obtenir le cluster du répertoire courant
-    pub fn current_dir(&self) -> u32 {
-        self.current_directory
-    }
+    /// Corriger la taille d'un fichier qui ne correspond pas à sa chaîne
+    ///
+    /// Une taille est cohérente si la chaîne compte au moins le nombre de
+    /// clusters nécessaires pour la contenir : des clusters en plus sont
+    /// réservés (voir `reserve`) et leur contenu n'est pas exposé. Une taille
+    /// trop grande pour la chaîne devient `longueur de la chaîne * taille
+    /// d'un cluster` (0 sans chaîne) : c'est une borne haute, la fin du
+    /// dernier cluster peut contenir des déchets.
+    pub fn sync_directory_entry_size(&mut self, path: &str) -> Result<(), D::Error> {
+        self.check_writable()?;
+        let (dir_cluster, filename) = self.parse_path(path)?;
+        let (location, mut entry) = self
+            .find_entry(dir_cluster, filename)?
+            .ok_or_else(|| Fat32Error::not_found(path))?;
 
-    /// Changer de répertoire
-    pub fn change_dir(&mut self, path: &str) -> Result<()> {
-        let cluster = self.resolve_path(path)?;
-        
-        // Vérifier que c'est bien un dossier
-        let _ = self.read_directory(cluster)?;
-        
-        self.current_directory = cluster;
-        Ok(())
-    }
+        if entry.attributes().is_directory() {
+            return Err(Fat32Error::invalid_path(path));
+        }
 
-    /// Lister les fichiers d'un répertoire
-    pub fn list_dir(&mut self, path: Option<&str>) -> Result<Vec<DirectoryEntry>> {
-        let cluster = if let Some(p) = path {
-            self.resolve_path(p)?
+        let cluster_size = self.boot_sector.cluster_size() as u64;
+        let chain_length = if entry.first_cluster() < 2 {
+            0
         } else {
-            self.current_directory
+            self.cluster_chain_length(entry.first_cluster())? as u64
         };
 
-        self.read_directory(cluster)
+        let size = entry.file_size() as u64;
+        if size.div_ceil(cluster_size) <= chain_length {
+            return Ok(());
+        }
+
+        let actual_size = (chain_length * cluster_size).min(u32::MAX as u64) as u32;
+        entry.set_file_size(actual_size);
+        self.write_entry_at(location, &entry)
     }
 
-    /// Lire le contenu d'un fichier
-    pub fn read_file(&mut self, path: &str) -> Result<Vec<u8>> {
-        // Séparer le chemin et le nom du fichier
-        let (dir_cluster, filename) = self.parse_path(path)?;
-        let entries = self.read_directory(dir_cluster)?;
+    /// Secteur `sector` du device en hexadécimal (feature `debug`)
+    ///
+    /// 16 octets par ligne, à la manière de `hexdump -C` : offset, octets,
+    /// puis les caractères ASCII imprimables (`.` pour les autres). Le
+    /// secteur est lu sur le device, sans voir les modifications encore en
+    /// cache : appeler `flush()` avant pour les inclure.
+    #[cfg(feature = "debug")]
+    pub fn read_sector_as_string(&mut self, sector: u32) -> Result<String, D::Error> {
+        use core::fmt::Write;
 
-        // Trouver le fichier
-        let entry = entries
-            .iter()
-            .find(|e| {
-                !e.attributes().is_directory() 
-                    && e.short_name().eq_ignore_ascii_case(filename)
-            })
-            .ok_or(Fat32Error::NotFound)?;
+        check_bounds(&self.device, IoOp::Read, sector, 1)?;
+        let mut buffer = SectorBuf::new(self.device.sector_size());
+        read_sector_aligned(&mut self.device, sector, &mut buffer)?;
 
-        // Fichier vide
-        if entry.file_size() == 0 {
-            return Ok(Vec::new());
+        let mut dump = String::new();
+        for (i, line) in buffer.chunks(16).enumerate() {
+            let _ = write!(dump, "{:08x} ", i * 16);
+            for (j, byte) in line.iter().enumerate() {
+                let sep = if j == 8 { "  " } else { " " };
+                let _ = write!(dump, "{}{:02x}", sep, byte);
+            }
+            let ascii: String = line
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            let _ = writeln!(dump, "  |{}|", ascii);
         }
+        Ok(dump)
+    }
 
-        // Lire tous les clusters
-        let mut fat = FatTable::new(&mut self.device, &self.boot_sector);
-        let clusters = fat.cluster_chain(entry.first_cluster())?;
-
-        let mut data = Vec::new();
-        for cluster in clusters {
-            let cluster_data = self.read_cluster(cluster)?;
-            data.extend_from_slice(&cluster_data);
-        }
+    /// Résoudre un chemin vers un numéro de cluster
+    fn resolve_path(&mut self, path: &str) -> Result<u32, D::Error> {
+        self.resolve_path_from(self.current_directory, path)
+    }
 
-        // Tronquer à la vraie taille
-        data.truncate(entry.file_size() as usize);
-        Ok(data)
+    /// Résoudre un chemin relatif au répertoire `start`
+    fn resolve_path_from(&mut self, start: u32, path: &str) -> Result<u32, D::Error> {
+        self.walk_path_from(start, path, |_| ())
     }
 
-    /// Résoudre un chemin vers un numéro de cluster
-    fn resolve_path(&mut self, path: &str) -> Result<u32> {
+    /// Résoudre un chemin en passant chaque étape à `visit`
+    ///
+    /// `visit` reçoit l'entrée du dossier où l'on descend, ou `None` pour
+    /// une remontée par "..".
+    fn walk_path_from(
+        &mut self,
+        start: u32,
+        path: &str,
+        mut visit: impl FnMut(Option<&DirectoryEntry>),
+    ) -> Result<u32, D::Error> {
         // Chemin absolu ou relatif ?
-        let (mut current, remaining) = if path.starts_with('/') {
-            (self.boot_sector.root_cluster, &path[1..])
-        } else {
-            (self.current_directory, path)
-        };
-
-        if remaining.is_empty() {
-            return Ok(current);
-        }
+        let (absolute, steps) = lookup_steps(path);
+        let mut current = if absolute { self.boot_sector.root_cluster() } else { start };
 
         // Parcourir chaque composant du chemin
-        for component in remaining.split('/') {
-            if component.is_empty() {
-                continue;
-            }
-
-            if component == "." {
-                continue;
-            }
-
-            if component == ".." {
-                current = self.find_parent(current)?;
-                continue;
-            }
+        for (index, step) in steps.enumerate() {
+            let index = index.min(u16::MAX as usize) as u16;
+            let component = match step? {
+                Step::Parent => {
+                    current = self.find_parent(current).map_err(|e| e.at_component(index))?;
+                    visit(None);
+                    continue;
+                }
+                Step::Child(component) => component,
+            };
 
             // Chercher dans le répertoire courant
-            let entries = self.read_directory(current)?;
-            let entry = entries
-                .iter()
-                .find(|e| {
-                    e.attributes().is_directory()
-                        && !e.is_dot()
-                        && !e.is_dot_dot()
-                        && e.short_name().eq_ignore_ascii_case(component)
-                })
-                .ok_or(Fat32Error::NotFound)?;
+            let entry = self
+                .find_entry_in_dir(current, component)?
+                .filter(|e| e.attributes().is_directory() && !e.is_dot() && !e.is_dot_dot())
+                .ok_or_else(|| Fat32Error::not_found_at(index, component))?;
 
             current = entry.first_cluster();
+            visit(Some(&entry));
         }
 
         Ok(current)
     }
 
+    /// Noms 8.3 des dossiers de la racine jusqu'à `dir`, en remontant les ".."
+    ///
+    /// Chaque niveau ne lit que le dossier et son parent. `None` si un parent
+    /// ne contient pas le dossier ou si la remontée boucle (FAT corrompue).
+    fn dir_names(&mut self, mut dir: u32) -> Result<Option<Vec<String>>, D::Error> {
+        let root = self.boot_sector.root_cluster();
+        let mut visited = BTreeSet::new();
+        let mut names = Vec::new();
+        while dir != root {
+            if !visited.insert(dir) {
+                return Ok(None);
+            }
+            let parent = self.find_parent(dir)?;
+            let entry = self.read_directory(parent)?.into_iter().find(|e| {
+                e.attributes().is_directory()
+                    && !e.is_dot()
+                    && !e.is_dot_dot()
+                    && e.first_cluster() == dir
+            });
+            match entry {
+                Some(entry) => names.push(entry.short_name()),
+                None => return Ok(None),
+            }
+            dir = parent;
+        }
+        names.reverse();
+        Ok(Some(names))
+    }
+
     /// Séparer un chemin en dossier + nom de fichier
-    fn parse_path<'a>(&mut self, path: &'a str) -> Result<(u32, &'a str)> {
-        let (dir, name) = if let Some(pos) = path.rfind('/') {
-            let (dir_path, name) = path.split_at(pos);
-            (dir_path, &name[1..])
-        } else {
-            ("", path)
-        };
+    fn parse_path<'a>(&mut self, path: &'a str) -> Result<(u32, &'a str), D::Error> {
+        self.parse_path_from(self.current_directory, path)
+    }
+
+    /// Séparer un chemin relatif au répertoire `start` en dossier + nom
+    fn parse_path_from<'a>(
+        &mut self,
+        start: u32,
+        path: &'a str,
+    ) -> Result<(u32, &'a str), D::Error> {
+        let (dir, name) = split_parent(path);
+        validate_lookup_component(name)?;
 
         let dir_cluster = if dir.is_empty() {
-            self.current_directory
+            start
         } else {
-            self.resolve_path(dir)?
+            self.resolve_path_from(start, dir)?
         };
 
         Ok((dir_cluster, name))
     }
 
+    /// Cluster d'un répertoire de départ
+    fn dir_cluster(&self, dir: DirRef) -> u32 {
+        match dir {
+            DirRef::Root => self.boot_sector.root_cluster(),
+            DirRef::Cluster(cluster) => cluster,
+        }
+    }
+
     /// Trouver le dossier parent
-    fn find_parent(&mut self, cluster: u32) -> Result<u32> {
+    fn find_parent(&mut self, cluster: u32) -> Result<u32, D::Error> {
         let entries = self.read_directory(cluster)?;
 
         for entry in entries {
             if entry.is_dot_dot() {
                 let parent = entry.first_cluster();
                 return Ok(if parent == 0 {
-                    self.boot_sector.root_cluster
+                    self.boot_sector.root_cluster()
                 } else {
                     parent
                 });
             }
         }
 
-        Err(Fat32Error::NotFound)
+        Err(Fat32Error::not_found_at(0, ".."))
     }
 
     /// Lire toutes les entrées d'un répertoire
-    fn read_directory(&mut self, cluster: u32) -> Result<Vec<DirectoryEntry>> {
-        let mut fat = FatTable::new(&mut self.device, &self.boot_sector);
-        let clusters = fat.cluster_chain(cluster)?;
+    fn read_directory(&mut self, cluster: u32) -> Result<Vec<DirectoryEntry>, D::Error> {
+        let entries = self.read_directory_named(cluster)?;
+        Ok(entries.into_iter().map(|(entry, _)| entry).collect())
+    }
 
+    /// Lire un répertoire en associant à chaque entrée son nom long
+    ///
+    /// La chaîne est lue d'un coup dans la FAT avant de parcourir les
+    /// clusters, plutôt qu'un `next_cluster` par cluster.
+    fn read_directory_named(
+        &mut self,
+        dir_cluster: u32,
+    ) -> Result<Vec<(DirectoryEntry, Option<String>)>, D::Error> {
         let mut entries = Vec::new();
+        let mut scanner = DirScanner::default();
+
+        let chain = self.fat().cluster_chain(dir_cluster)?;
+        if chain.len() > self.max_dir_clusters() {
+            let clusters = chain.len() as u32;
+            return Err(Fat32Error::Corrupted(CorruptionKind::DirectoryTooLarge { clusters }));
+        }
 
-        for cluster in clusters {
+        for cluster in chain {
             let data = self.read_cluster(cluster)?;
 
             // Parser les entrées (32 octets chacune)
             for chunk in data.chunks_exact(DirectoryEntry::SIZE) {
-                let entry = unsafe { DirectoryEntry::from_bytes(chunk) };
+                match scanner.scan(chunk) {
+                    DirSlot::End => return Ok(entries),
+                    DirSlot::Entry(entry, long) => entries.push((entry, long)),
+                    DirSlot::Skip => {}
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Chercher une entrée par nom et retourner sa position
+    ///
+    /// Voir `find_entry_in_dir` pour la comparaison des noms.
+    fn find_entry(
+        &mut self,
+        dir_cluster: u32,
+        name: &str,
+    ) -> Result<Option<(EntryLocation, DirectoryEntry)>, D::Error> {
+        let mut cluster = dir_cluster;
+        let mut cluster_index = 0;
+        let mut scanner = DirScanner::default();
+        // Slot qui ouvre le nom long en cours (bit 0x40 : dernière partie, placée en tête)
+        let mut long_start = None;
+
+        loop {
+            let data = self.read_cluster(cluster)?;
+
+            for (i, chunk) in data.chunks_exact(DirectoryEntry::SIZE).enumerate() {
+                let offset = i * DirectoryEntry::SIZE;
+                let attributes = FileAttributes(chunk[11]);
+                if chunk[0] != 0xE5 && chunk[0] & 0x40 != 0 && attributes.is_long_name() {
+                    long_start = Some((cluster, offset));
+                }
+                let (entry, long) = match scanner.scan(chunk) {
+                    DirSlot::End => return Ok(None),
+                    DirSlot::Entry(entry, long) => (entry, long),
+                    DirSlot::Skip => continue,
+                };
+
+                let long_name = long_start.take().filter(|_| long.is_some());
+                if matches_name(&entry, long.as_deref(), name) {
+                    let location = EntryLocation { cluster, offset, long_name };
+                    return Ok(Some((location, entry)));
+                }
+            }
+
+            match self.next_dir_cluster(cluster, &mut cluster_index)? {
+                Some(next) => cluster = next,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Trouver un emplacement libre dans un répertoire (l'agrandit si besoin)
+    ///
+    /// Retourne `DiskFull` si le répertoire a déjà 65 536 entrées.
+    fn find_free_slot(&mut self, dir_cluster: u32) -> Result<EntryLocation, D::Error> {
+        let mut cluster = dir_cluster;
+        let mut cluster_index = 0;
+
+        loop {
+            let data = self.read_cluster(cluster)?;
+
+            for (i, chunk) in data.chunks_exact(DirectoryEntry::SIZE).enumerate() {
+                if chunk[0] == 0x00 || chunk[0] == 0xE5 {
+                    return Ok(EntryLocation {
+                        cluster,
+                        offset: i * DirectoryEntry::SIZE,
+                        long_name: None,
+                    });
+                }
+            }
+
+            match self.next_dir_cluster(cluster, &mut cluster_index)? {
+                Some(next) => cluster = next,
+                None => break,
+            }
+        }
+        if cluster_index + 1 >= self.max_dir_clusters() {
+            return Err(Fat32Error::DiskFull);
+        }
+
+        // Répertoire plein : ajouter un cluster vide
+        let new_cluster = self.fat()
+            .extend_chain(dir_cluster)?;
+        let zeros = self.cluster_buffer();
+        self.write_cluster(new_cluster, &zeros)?;
+
+        Ok(EntryLocation {
+            cluster: new_cluster,
+            offset: 0,
+            long_name: None,
+        })
+    }
+
+    /// Entrée « volume » d'un répertoire (en pratique la racine)
+    fn find_volume_entry(
+        &mut self,
+        dir_cluster: u32,
+    ) -> Result<Option<(EntryLocation, DirectoryEntry)>, D::Error> {
+        let mut cluster = dir_cluster;
+        let mut cluster_index = 0;
 
+        loop {
+            let data = self.read_cluster(cluster)?;
+
+            for (i, chunk) in data.chunks_exact(DirectoryEntry::SIZE).enumerate() {
+                let entry = unsafe { DirectoryEntry::from_bytes(chunk) };
                 if entry.is_end() {
-                    return Ok(entries);
+                    return Ok(None);
+                }
+                let attributes = entry.attributes();
+                if entry.is_valid() && attributes.is_volume_id() && !attributes.is_long_name() {
+                    let location = EntryLocation {
+                        cluster,
+                        offset: i * DirectoryEntry::SIZE,
+                        long_name: None,
+                    };
+                    return Ok(Some((location, entry)));
                 }
+            }
+
+            match self.next_dir_cluster(cluster, &mut cluster_index)? {
+                Some(next) => cluster = next,
+                None => return Ok(None),
+            }
+        }
+    }
 
-                if entry.is_valid()
-                    && !entry.attributes().is_long_name()
-                    && !entry.attributes().is_volume_id()
-                {
-                    entries.push(entry);
+    /// Cluster suivant d'un répertoire, `None` en fin de chaîne
+    ///
+    /// `index` compte les clusters déjà parcourus : une chaîne qui dépasse
+    /// 65 536 entrées (boucle, chaîne partie dans des données) est corrompue.
+    fn next_dir_cluster(
+        &mut self,
+        cluster: u32,
+        index: &mut usize,
+    ) -> Result<Option<u32>, D::Error> {
+        match self.fat().next_cluster(cluster) {
+            Ok(next) => {
+                *index += 1;
+                if *index >= self.max_dir_clusters() {
+                    let kind = CorruptionKind::DirectoryTooLarge { clusters: *index as u32 + 1 };
+                    return Err(Fat32Error::Corrupted(kind));
                 }
+                Ok(Some(next))
             }
+            Err(Fat32Error::EndOfChain) => Ok(None),
+            Err(e) => Err(e),
         }
+    }
 
-        Ok(entries)
+    /// Nombre maximum de clusters d'un répertoire
+    fn max_dir_clusters(&self) -> usize {
+        (MAX_DIR_ENTRIES * DirectoryEntry::SIZE).div_ceil(self.boot_sector.cluster_size() as usize)
+    }
+
+    /// Marquer supprimées une entrée et les slots de son nom long
+    ///
+    /// Sans cela, les slots de nom long resteraient orphelins dans le
+    /// répertoire (et seraient gardés par `compact_dir`).
+    fn delete_entry_at(
+        &mut self,
+        location: EntryLocation,
+        entry: &mut DirectoryEntry,
+    ) -> Result<(), D::Error> {
+        if let Some((mut cluster, mut offset)) = location.long_name {
+            let cluster_size = self.boot_sector.cluster_size() as usize;
+            while (cluster, offset) != (location.cluster, location.offset) {
+                let slot = EntryLocation { cluster, offset, long_name: None };
+                self.edit_slot(slot, |raw| raw[0] = 0xE5)?;
+                offset += DirectoryEntry::SIZE;
+                if offset == cluster_size {
+                    cluster = self.fat().next_cluster(cluster)?;
+                    offset = 0;
+                }
+            }
+        }
+        entry.mark_deleted();
+        self.write_entry_at(location, entry)
     }
 
-    /// Lire un cluster complet
-    fn read_cluster(&mut self, cluster: u32) -> Result<Vec<u8>> {
-        let first_sector = self.cluster_to_sector(cluster);
-        let mut buffer = alloc::vec![0u8; self.boot_sector.cluster_size() as usize];
+    /// Écrire une entrée à sa position (dans le cache des répertoires)
+    fn write_entry_at(
+        &mut self,
+        location: EntryLocation,
+        entry: &DirectoryEntry,
+    ) -> Result<(), D::Error> {
+        let bytes = entry.to_bytes();
+        self.edit_slot(location, |raw| raw.copy_from_slice(&bytes))
+    }
+
+    /// Modifier les 32 octets bruts d'un slot (dans le cache des répertoires)
+    fn edit_slot(
+        &mut self,
+        location: EntryLocation,
+        edit: impl FnOnce(&mut [u8]),
+    ) -> Result<(), D::Error> {
+        let bytes_per_sector = self.boot_sector.bytes_per_sector() as usize;
+        let sector = self.data_sector(location.cluster)? + (location.offset / bytes_per_sector) as u32;
+        let offset = location.offset % bytes_per_sector;
+
+        if !self.dir_cache.contains(sector) {
+            check_bounds(&self.device, IoOp::Read, sector, 1)?;
+            let mut buffer = SectorBuf::new(bytes_per_sector);
+            read_sector_aligned(&mut self.device, sector, &mut buffer)?;
+            if let Some((evicted, data)) = self.dir_cache.insert(sector, buffer, false) {
+                // Respecter l'ordre de flush : la FAT avant les répertoires
+                self.fat().flush()?;
+                write_sector_aligned(&mut self.device, evicted, &data)?;
+            }
+        }
+
+        match self.dir_cache.get_mut(sector) {
+            Some(buffer) => {
+                edit(&mut buffer[offset..offset + DirectoryEntry::SIZE]);
+                Ok(())
+            }
+            None => unreachable!(),
+        }
+    }
+
+    /// Les deux entrées sont-elles dans le même secteur ?
+    fn same_sector(&self, a: EntryLocation, b: EntryLocation) -> bool {
+        let bytes_per_sector = self.boot_sector.bytes_per_sector() as usize;
+        a.cluster == b.cluster && a.offset / bytes_per_sector == b.offset / bytes_per_sector
+    }
+
+    /// Remplacer le nom de volume du boot sector et de sa copie de secours
+    ///
+    /// Le secteur est relu pour garder le code de démarrage intact.
+    fn write_boot_label(&mut self, label: [u8; 11]) -> Result<(), D::Error> {
+        let offset = core::mem::offset_of!(BootSector, volume_label);
+        let mut sector = SectorBuf::new(self.boot_sector.bytes_per_sector() as usize);
+        read_sector_aligned(&mut self.device, 0, &mut sector)?;
+        sector[offset..offset + 11].copy_from_slice(&label);
+        write_sector_aligned(&mut self.device, 0, &sector)?;
+
+        let backup = self.boot_sector.backup_boot_sector() as u32;
+        if backup != 0 && backup < self.boot_sector.reserved_sector_count() as u32 {
+            check_bounds(&self.device, IoOp::Write, backup, 1)?;
+            write_sector_aligned(&mut self.device, backup, &sector)?;
+        }
+        self.boot_sector.volume_label = label;
+        Ok(())
+    }
+
+    /// Écrire un cluster complet (directement sur le disque)
+    fn write_cluster(&mut self, cluster: u32, data: &[u8]) -> Result<(), D::Error> {
+        let first_sector = self.data_sector(cluster)?;
+        let count = (data.len() / self.boot_sector.bytes_per_sector() as usize) as u32;
+        check_bounds(&self.device, IoOp::Write, first_sector, count)?;
+
+        // Un ancien secteur de répertoire en cache ne doit pas écraser les données
+        for sector in first_sector..first_sector + count {
+            self.dir_cache.remove(sector);
+        }
+        write_sectors_aligned(&mut self.device, first_sector, count, data)
+    }
+
+    /// Premier secteur d'un cluster de données
+    ///
+    /// `ClusterOutOfRange` si le cluster est hors du volume : une FAT ou une
+    /// entrée corrompue ne doit pas faire lire n'importe où.
+    fn data_sector(&self, cluster: u32) -> Result<u32, D::Error> {
+        if cluster < 2 || cluster > self.boot_sector.num_clusters() + 1 {
+            return Err(Fat32Error::Corrupted(CorruptionKind::ClusterOutOfRange { value: cluster }));
+        }
+        Ok(self.boot_sector.cluster_to_sector(cluster))
+    }
+
+    /// `ReadOnlyFilesystem` si le système de fichiers est en lecture seule
+    ///
+    /// Appelé au début de chaque modification. La première efface le bit
+    /// « propre » du volume et l'écrit sur le device avant toute autre
+    /// métadonnée : une coupure avant `unmount` se voit au montage suivant.
+    fn check_writable(&mut self) -> Result<(), D::Error> {
+        if self.readonly {
+            return Err(Fat32Error::ReadOnlyFilesystem);
+        }
+        if !self.in_use {
+            let mut fat = self.fat();
+            let clean = fat.is_volume_clean()?;
+            if clean {
+                fat.set_volume_clean(false)?;
+                fat.flush()?;
+                self.device.flush()?;
+            }
+            self.restore_clean = clean;
+            self.in_use = true;
+        }
+        Ok(())
+    }
+
+    /// Heure courante, si une source de temps est configurée
+    fn now(&self) -> Option<(FatDate, FatTime)> {
+        self.time_source.as_ref().map(|ts| ts.now())
+    }
+
+    /// Accès à la FAT à travers le cache du système de fichiers
+    fn fat(&mut self) -> FatTable<'_, D> {
+        FatTable::new(&mut self.device, &self.boot_sector, &mut self.fat_cache)
+    }
+
+    /// Lire les compteurs du secteur FSInfo, s'il est valide
+    fn read_fs_info(&mut self) -> Result<(), D::Error> {
+        let sector = self.boot_sector.fs_info() as u32;
+        let outside = check_bounds(&self.device, IoOp::Read, sector, 1).is_err();
+        if sector == 0 || sector == 0xFFFF || outside {
+            return Ok(());
+        }
+
+        let mut buffer = SectorBuf::new(self.boot_sector.bytes_per_sector() as usize);
+        read_sector_aligned(&mut self.device, sector, &mut buffer)?;
+        if !fs_info_valid(&buffer) {
+            return Ok(());
+        }
+
+        let known = |value: u32| if value == 0xFFFFFFFF { None } else { Some(value) };
+        let free_count = u32::from_le_bytes([buffer[488], buffer[489], buffer[490], buffer[491]]);
+        let next_free = u32::from_le_bytes([buffer[492], buffer[493], buffer[494], buffer[495]]);
+        self.fat_cache.set_fs_info(known(free_count), known(next_free));
+
+        Ok(())
+    }
+
+    /// Comparer le compteur FSInfo aux clusters libres de la FAT, et le corriger
+    ///
+    /// En mode strict, `FreeCountMismatch` si l'écart dépasse 1 % des clusters.
+    fn check_free_count(&mut self, strict: bool) -> Result<(), D::Error> {
+        let free = self.fat().free_count_from_fat()?;
+        if let Some(recorded) = self.fat_cache.free_count() {
+            let clusters = self.boot_sector.num_clusters() as u64;
+            if strict && recorded.abs_diff(free) as u64 * 100 > clusters {
+                let kind = CorruptionKind::FreeCountMismatch { recorded, actual: free };
+                return Err(Fat32Error::Corrupted(kind));
+            }
+        }
+        self.fat_cache.correct_free_count(free);
+        Ok(())
+    }
+
+    /// Écrire les compteurs dans le secteur FSInfo, s'il est valide
+    fn write_fs_info(&mut self) -> Result<(), D::Error> {
+        let sector = self.boot_sector.fs_info() as u32;
+        let outside = check_bounds(&self.device, IoOp::Read, sector, 1).is_err();
+        if sector == 0 || sector == 0xFFFF || outside {
+            return Ok(());
+        }
 
-        for i in 0..self.boot_sector.sectors_per_cluster as u32 {
-            let offset = i * self.boot_sector.bytes_per_sector as u32;
-            self.device.read_sector(
-                first_sector + i,
-                &mut buffer[offset as usize..(offset + self.boot_sector.bytes_per_sector as u32) as usize],
-            )?;
+        let mut buffer = SectorBuf::new(self.boot_sector.bytes_per_sector() as usize);
+        read_sector_aligned(&mut self.device, sector, &mut buffer)?;
+        if !fs_info_valid(&buffer) {
+            return Ok(());
         }
 
+        let free_count = self.fat_cache.free_count().unwrap_or(0xFFFFFFFF);
+        let next_free = self.fat_cache.next_free().unwrap_or(0xFFFFFFFF);
+        buffer[488..492].copy_from_slice(&free_count.to_le_bytes());
+        buffer[492..496].copy_from_slice(&next_free.to_le_bytes());
+        write_sector_aligned(&mut self.device, sector, &buffer)
+    }
+
+    /// Lire un cluster complet (en tenant compte des répertoires en cache)
+    fn read_cluster(&mut self, cluster: u32) -> Result<AlignedBuf, D::Error> {
+        let mut buffer = self.cluster_buffer();
+        self.read_cluster_into(cluster, &mut buffer)?;
         Ok(buffer)
     }
 
-    /// Convertir un numéro de cluster en numéro de secteur
-    fn cluster_to_sector(&self, cluster: u32) -> u32 {
-        ((cluster - 2) * self.boot_sector.sectors_per_cluster as u32)
-            + self.boot_sector.first_data_sector()
+    /// Tampon d'un cluster à zéro, aligné pour le device
+    fn cluster_buffer(&self) -> AlignedBuf {
+        AlignedBuf::zeroed(self.boot_sector.cluster_size() as usize, self.device.buffer_alignment())
+    }
+
+    /// Lire un cluster dans un tampon existant (taille d'un cluster)
+    fn read_cluster_into(&mut self, cluster: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.read_clusters_into(cluster, 1, buffer)
+    }
+
+    /// Lire le début d'un cluster de données (`out` plus court qu'un cluster)
+    fn read_cluster_prefix(&mut self, cluster: u32, out: &mut [u8]) -> Result<(), D::Error> {
+        let bytes_per_sector = self.boot_sector.bytes_per_sector() as usize;
+        let first_sector = self.data_sector(cluster)?;
+        let full = out.len() / bytes_per_sector;
+        let sectors = out.len().div_ceil(bytes_per_sector) as u32;
+        check_bounds(&self.device, IoOp::Read, first_sector, sectors)?;
+
+        let (whole, rest) = out.split_at_mut(full * bytes_per_sector);
+        if full > 0 {
+            read_sectors_aligned(&mut self.device, first_sector, full as u32, whole)?;
+        }
+        if !rest.is_empty() {
+            let mut sector = SectorBuf::new(bytes_per_sector);
+            read_sector_aligned(&mut self.device, first_sector + full as u32, &mut sector)?;
+            rest.copy_from_slice(&sector[..rest.len()]);
+        }
+        Ok(())
+    }
+
+    /// Lire `count` clusters consécutifs (tampon de `count` clusters)
+    ///
+    /// Les secteurs de répertoire en cache sont copiés, les autres sont lus
+    /// par blocs avec `BlockDevice::read_sectors`.
+    fn read_clusters_into(
+        &mut self,
+        first_cluster: u32,
+        count: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), D::Error> {
+        let first_sector = self.data_sector(first_cluster)?;
+        let sectors = count * self.boot_sector.sectors_per_cluster() as u32;
+        check_bounds(&self.device, IoOp::Read, first_sector, sectors)?;
+        let bytes_per_sector = self.boot_sector.bytes_per_sector() as usize;
+        let range = |from: u32, to: u32| from as usize * bytes_per_sector..to as usize * bytes_per_sector;
+
+        let mut run_start = 0;
+        for i in 0..=sectors {
+            let cached = if i < sectors { self.dir_cache.peek(first_sector + i) } else { None };
+            if i < sectors && cached.is_none() {
+                continue;
+            }
+            if i > run_start {
+                let run = &mut buffer[range(run_start, i)];
+                read_sectors_aligned(&mut self.device, first_sector + run_start, i - run_start, run)?;
+            }
+            if let Some(data) = cached {
+                buffer[range(i, i + 1)].copy_from_slice(data);
+            }
+            run_start = i + 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Un système de fichiers détruit sans `unmount` écrit au mieux ce qui est en
+/// attente, sans pouvoir signaler d'erreur. En debug (avec `std`), un message
+/// le signale sur la sortie d'erreur.
+impl<D: BlockDevice> Drop for Fat32FileSystem<D> {
+    fn drop(&mut self) {
+        #[cfg(all(feature = "std", debug_assertions))]
+        if self.is_dirty() {
+            std::eprintln!("fat32: système de fichiers détruit avec des écritures en attente, utiliser unmount()");
+        }
+        let _ = self.close();
     }
 }
 
+/// Vérifier les trois signatures d'un secteur FSInfo
+fn fs_info_valid(data: &[u8]) -> bool {
+    let read = |offset: usize| {
+        u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+    };
+    read(0) == FS_INFO_LEAD_SIG && read(484) == FS_INFO_STRUC_SIG && read(508) == FS_INFO_TRAIL_SIG
+}
+
+/// Images de test construites avec le code de formatage de la crate
 #[cfg(test)]
-mod tests {
-    use super::*;
-    use alloc::vec;
+pub mod test_helpers {
+    use alloc::vec::Vec;
+    use crate::{BlockDevice, Fat32FileSystem, MemoryDevice, ReadBlockDevice, Result};
+
+    /// Taille des images de test (34 MiB) : au-dessus des 65525 clusters
+    /// d'un secteur qu'il faut à FAT32
+    pub const IMAGE_SECTORS: u32 = 69_632;
+
+    /// Image FAT32 valide en mémoire, remplie par le système de fichiers
+    pub struct FatImage {
+        pub data: Vec<u8>,
+    }
+
+    impl FatImage {
+        /// Image formatée de `size_sectors` secteurs de 512 octets
+        pub fn new(size_sectors: u32, spc: u8) -> Self {
+            Self { data: build_fat32_image(size_sectors, spc) }
+        }
+
+        /// Ajouter un fichier (chemin absolu, répertoires existants)
+        pub fn add_file(&mut self, path: &str, data: &[u8]) -> &mut Self {
+            add_file(&mut self.data, path, data);
+            self
+        }
+
+        /// Ajouter un répertoire vide (parent existant)
+        pub fn add_dir(&mut self, path: &str) -> &mut Self {
+            self.with_fs(|fs| fs.create_dir(path).unwrap());
+            self
+        }
+
+        /// Device qui lit et écrit directement dans l'image
+        pub fn as_device(&mut self) -> MockDevice<'_> {
+            MockDevice { data: &mut self.data }
+        }
+
+        /// Monter l'image le temps de `f`, puis la démonter
+        fn with_fs(&mut self, f: impl FnOnce(&mut Fat32FileSystem<MemoryDevice>)) {
+            let device = MemoryDevice::from_vec(core::mem::take(&mut self.data));
+            let mut fs = Fat32FileSystem::new(device).unwrap();
+            f(&mut fs);
+            self.data = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+        }
+    }
 
-    struct MockDevice {
-        data: Vec<u8>,
+    /// Device de 512 octets par secteur sur une image empruntée
+    ///
+    /// Pour compter les accès, l'envelopper dans un `TraceDevice`.
+    pub struct MockDevice<'a> {
+        pub data: &'a mut Vec<u8>,
     }
 
-    impl BlockDevice for MockDevice {
+    impl ReadBlockDevice for MockDevice<'_> {
+        type Error = core::convert::Infallible;
+
         fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
             let offset = sector as usize * 512;
             buffer.copy_from_slice(&self.data[offset..offset + buffer.len()]);
             Ok(())
         }
 
-        fn write_sector(&mut self, _: u32, _: &[u8]) -> Result<()> {
-            Ok(())
-        }
-
         fn sector_size(&self) -> usize {
             512
         }
+
+        fn num_sectors(&self) -> u64 {
+            (self.data.len() / 512) as u64
+        }
     }
 
+    impl BlockDevice for MockDevice<'_> {
+        fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
+            let offset = sector as usize * 512;
+            self.data[offset..offset + buffer.len()].copy_from_slice(buffer);
+            Ok(())
+        }
+    }
+
+    /// Image FAT32 formatée de `size_sectors` secteurs de 512 octets
+    ///
+    /// Boot sector, FSInfo et FATs écrits par `BootSector::format`, racine
+    /// vide au cluster 2 (marqué fin de chaîne dans la FAT).
+    pub fn build_fat32_image(size_sectors: u32, sectors_per_cluster: u8) -> Vec<u8> {
+        let device = MemoryDevice::new(size_sectors);
+        let fs = Fat32FileSystem::format(device, size_sectors, sectors_per_cluster).unwrap();
+        fs.unmount().map_err(|(_, err)| err).unwrap().into_inner()
+    }
+
+    /// Ajouter un fichier à une image (chemin absolu, répertoires existants)
+    pub fn add_file(img: &mut Vec<u8>, name: &str, data: &[u8]) {
+        let device = MemoryDevice::from_vec(core::mem::take(img));
+        let mut fs = Fat32FileSystem::new(device).unwrap();
+        fs.write_file(name, data).unwrap();
+        *img = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_helpers::{add_file, build_fat32_image, FatImage, IMAGE_SECTORS};
+    use super::*;
+    use crate::devices::TraceDevice;
+    use crate::MemoryDevice;
+
     #[test]
     fn test_filesystem_creation() {
-        let mut device = MockDevice { data: vec![0; 1024 * 512] };
-        device.data[66] = 0x29;
-        device.data[11..13].copy_from_slice(&512u16.to_le_bytes());
-        device.data[13] = 1;
-        device.data[14..16].copy_from_slice(&32u16.to_le_bytes());
-        device.data[16] = 2;
-        device.data[36..40].copy_from_slice(&8u32.to_le_bytes());
-        device.data[44..48].copy_from_slice(&2u32.to_le_bytes());
-
-        let fs = Fat32FileSystem::new(device);
-        assert!(fs.is_ok());
-    }
-}
\ No newline at end of file
+        let mut image = build_fat32_image(IMAGE_SECTORS, 1);
+        add_file(&mut image, "/a.txt", b"abc");
+
+        let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(image)).unwrap();
+        assert_eq!(fs.read_file("/a.txt").unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_fat_image_builder() {
+        let mut image = FatImage::new(IMAGE_SECTORS, 1);
+        image.add_dir("/docs").add_file("/docs/a.txt", b"abc");
+
+        // Le device emprunte l'image : les écritures y restent
+        let mut fs = Fat32FileSystem::new(TraceDevice::new(image.as_device())).unwrap();
+        assert_eq!(fs.read_file("/docs/a.txt").unwrap(), b"abc");
+        fs.write_file("/b.txt", b"de").unwrap();
+        let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+        assert!(device.summary().reads > 0);
+
+        let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(image.data)).unwrap();
+        assert_eq!(fs.read_file("/b.txt").unwrap(), b"de");
+    }
+
+    #[test]
+    fn test_initialize_root_directory() {
+        let mut device = MemoryDevice::from_vec(build_fat32_image(IMAGE_SECTORS, 1));
+        let bs = unsafe { BootSector::from_bytes(&device.data()[..512]) };
+
+        // Racine remplie de déchets, cluster 2 libre dans la FAT
+        let root = bs.first_data_sector() as usize * 512;
+        device.data_mut()[root..root + 512].fill(0x41);
+        device.data_mut()[32 * 512 + 8..32 * 512 + 12].fill(0);
+
+        let mut fs = Fat32FileSystem::new(device).unwrap();
+        fs.initialize_root_directory().unwrap();
+        fs.flush().unwrap();
+
+        assert!(fs.list_dir(None).unwrap().is_empty());
+        assert_eq!(fs.fat().read_entry(2).unwrap(), FAT_EOC);
+        assert!(fs.device().data()[root..root + 512].iter().all(|&b| b == 0));
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_read_sector_as_string() {
+        let device = MemoryDevice::from_vec(build_fat32_image(IMAGE_SECTORS, 1));
+        let mut fs = Fat32FileSystem::new(device).unwrap();
+        let dump = fs.read_sector_as_string(0).unwrap();
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(lines.len(), 32);
+        assert_eq!(
+            lines[0],
+            "00000000  eb 58 90 4d 53 57 49 4e  34 2e 31 00 02 01 20 00  |.X.MSWIN4.1... .|"
+        );
+        assert!(lines[31].starts_with("000001f0 "));
+        assert!(lines[31].ends_with("00 00 55 aa  |..............U.|"));
+    }
+}
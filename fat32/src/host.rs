@@ -0,0 +1,153 @@
+//! Échanges de fichiers entre l'hôte et l'image (feature `std`)
+//!
+//! Les copies se font par blocs de la taille d'un cluster : un fichier n'est
+//! jamais chargé entièrement en mémoire.
+//!
+//! Les liens symboliques de l'hôte sont refusés (erreur `Host` de type
+//! `InvalidInput`) plutôt que suivis, pour ne pas sortir de l'arborescence
+//! copiée ni boucler. Les dates de l'hôte ne sont pas reportées : les entrées
+//! créées sont datées par la source de temps du système de fichiers, s'il y en a
+//! une (voir `Fat32FileSystem::set_time_source`).
+
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use crate::{BlockDevice, Fat32Error, Fat32FileSystem};
+
+/// Erreur d'une copie : côté hôte ou côté image (`E` : erreur du device)
+#[derive(Debug)]
+pub enum Error<E = Infallible> {
+    /// Erreur d'entrée/sortie sur le système de fichiers de l'hôte
+    Host(io::Error),
+    /// Erreur sur l'image FAT32
+    Image(Fat32Error<E>),
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Host(err) => write!(f, "Erreur hôte : {}", err),
+            Self::Image(err) => write!(f, "Erreur image : {}", err),
+        }
+    }
+}
+
+impl<E: fmt::Debug> std::error::Error for Error<E> {}
+
+impl<E> From<io::Error> for Error<E> {
+    fn from(err: io::Error) -> Self {
+        Self::Host(err)
+    }
+}
+
+impl<E> From<Fat32Error<E>> for Error<E> {
+    fn from(err: Fat32Error<E>) -> Self {
+        Self::Image(err)
+    }
+}
+
+/// Résultat des fonctions de ce module
+pub type Result<T, E = Infallible> = core::result::Result<T, Error<E>>;
+
+/// Copier un fichier ou un répertoire de l'hôte dans l'image
+///
+/// Les répertoires parents manquants dans l'image sont créés. Un répertoire
+/// est copié récursivement. Retourne le nombre d'octets copiés.
+pub fn copy_in<D: BlockDevice>(
+    fs: &mut Fat32FileSystem<D>,
+    host_path: &Path,
+    image_path: &str,
+) -> Result<u64, D::Error> {
+    let metadata = fs::symlink_metadata(host_path)?;
+    if metadata.file_type().is_symlink() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("lien symbolique refusé : {}", host_path.display()),
+        )
+        .into());
+    }
+
+    if metadata.is_dir() {
+        create_dir_all(fs, image_path)?;
+
+        // Ordre stable pour que l'image produite soit reproductible
+        let mut children = fs::read_dir(host_path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        children.sort();
+
+        let mut total = 0;
+        for child in children {
+            let name = child
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "nom non UTF-8"))?;
+            let child_image = format!("{}/{}", image_path.trim_end_matches('/'), name);
+            total += copy_in(fs, &child, &child_image)?;
+        }
+        return Ok(total);
+    }
+
+    if let Some(pos) = image_path.rfind('/') {
+        create_dir_all(fs, &image_path[..pos])?;
+    }
+
+    let size = u32::try_from(metadata.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::FileTooLarge, "fichier de plus de 4 Gio"))?;
+    let mut file = File::open(host_path)?;
+    fs.write_file_with(image_path, size, |buffer: &mut [u8]| {
+        file.read_exact(buffer).map_err(Error::Host)
+    })?;
+
+    Ok(size as u64)
+}
+
+/// Copier un fichier de l'image vers l'hôte
+///
+/// Le fichier de l'hôte est créé ou écrasé. Retourne le nombre d'octets copiés.
+pub fn copy_out<D: BlockDevice>(
+    fs: &mut Fat32FileSystem<D>,
+    image_path: &str,
+    host_path: &Path,
+) -> Result<u64, D::Error> {
+    let mut handle = fs.open_file(image_path)?;
+    let mut file = File::create(host_path)?;
+    let mut buffer = vec![0u8; fs.boot_sector().cluster_size() as usize];
+
+    let mut total = 0;
+    loop {
+        let read = fs.read_handle(&mut handle, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        total += read as u64;
+    }
+    file.flush()?;
+
+    Ok(total)
+}
+
+/// Créer un répertoire de l'image et ses parents manquants
+fn create_dir_all<D: BlockDevice>(fs: &mut Fat32FileSystem<D>, path: &str) -> Result<(), D::Error> {
+    let ends = path.match_indices('/').map(|(i, _)| i).chain(core::iter::once(path.len()));
+    for end in ends {
+        let prefix = &path[..end];
+        let component = prefix.rsplit('/').next().unwrap_or("");
+        if component.is_empty() || component == "." || component == ".." {
+            continue;
+        }
+
+        match fs.create_dir(prefix) {
+            Ok(()) | Err(Fat32Error::AlreadyExists) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(())
+}
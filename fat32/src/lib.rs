@@ -1,20 +1,231 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod error;
+#[cfg(feature = "async")]
+pub mod async_fs;
 pub mod boot_sector;
+pub mod cache;
+pub mod devices;
 pub mod directory;
 pub mod fat_table;
+pub mod file;
+#[cfg(feature = "embedded-io")]
+pub mod file_io;
 pub mod filesystem;
+#[cfg(feature = "std")]
+pub mod host;
+pub mod partition;
+pub mod path;
+pub mod read_only;
 
-pub use error::{Fat32Error, Result};
+pub use error::{ComponentName, CorruptionKind, EntryDefect, ErrorKind, Fat32Error, IoOp, Result};
+#[cfg(feature = "std")]
+pub use error::IoErrorKind;
+#[cfg(feature = "async")]
+pub use async_fs::{AsyncBlockDevice, AsyncFat32FileSystem};
 pub use boot_sector::BootSector;
-pub use directory::{DirectoryEntry, FileAttributes};
-pub use fat_table::FatTable;
-pub use filesystem::Fat32FileSystem;
+pub use devices::MemoryDevice;
+pub use directory::{
+    lfn_checksum, DirectoryEntry, FatDate, FatTime, FatTimestamp, FileAttributes, TimeSource,
+};
+#[cfg(feature = "std")]
+pub use directory::SystemTimeSource;
+pub use fat_table::{
+    ClusterState, FatCache, FatSnapshot, FatTable, IntegrityReport, UsedClusters,
+};
+pub use file::{FatFile, Lines, OpenOptions};
+#[cfg(feature = "embedded-io")]
+pub use file_io::FileReader;
+pub use partition::{Gpt, GptEntry, Guid, Mbr, PartitionDevice, PartitionEntry};
+pub use path::{validate_component, NameError};
+pub use read_only::ReadOnlyFileSystem;
+pub use filesystem::{
+    ClusterLookup, DefragStats, DirCounts, DirRef, Fat32FileSystem, VolumeInfo,
+};
 
-pub trait BlockDevice {
-    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()>;
-    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()>;
+/// Source de secteurs en lecture seule
+///
+/// Suffit pour monter un volume avec `ReadOnlyFileSystem` (image en ROM,
+/// fichier ouvert sans droit d'écriture...).
+///
+/// `Error` est l'erreur propre au device (CRC, timeout, carte absente...) :
+/// elle remonte telle quelle dans `Fat32Error::Device` jusqu'aux opérations
+/// du système de fichiers. Un device qui n'a pas d'erreur à lui prend
+/// `Infallible` et n'utilise que les variantes de `Fat32Error`.
+pub trait ReadBlockDevice {
+    type Error: core::fmt::Debug;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), Self::Error>;
     fn sector_size(&self) -> usize;
-}
\ No newline at end of file
+
+    /// Nombre de secteurs du device
+    ///
+    /// Le montage échoue si le volume est plus grand que le device, et les
+    /// accès calculés à partir des métadonnées (FAT, clusters, FSInfo) qui
+    /// dépasseraient la fin donnent `IoError` sans appeler le device. Par
+    /// défaut `u64::MAX` : aucune vérification.
+    fn num_sectors(&self) -> u64 {
+        u64::MAX
+    }
+
+    /// Alignement demandé pour les tampons passés au device (DMA)
+    ///
+    /// Une puissance de deux. Le système de fichiers ne passe à `read_*` et
+    /// `write_*` que des tampons dont l'adresse en est un multiple : ses
+    /// tampons internes sont alloués ainsi, les autres (tampons de
+    /// l'appelant) passent par une copie alignée. Un transfert de plusieurs
+    /// secteurs commence au début d'un tampon aligné, donc chaque secteur
+    /// aussi tant que l'alignement divise la taille de secteur. Les devices
+    /// de la bibliothèque gardent leurs tampons internes alignés jusqu'à
+    /// `cache::MAX_ALIGNMENT` (64). Par défaut 1 : aucune contrainte.
+    ///
+    /// C'est une méthode et non une constante associée pour que le trait
+    /// reste utilisable en objet (`Box<dyn BlockDevice>`).
+    fn buffer_alignment(&self) -> usize {
+        1
+    }
+
+    /// Lire `count` secteurs consécutifs à partir de `first`
+    ///
+    /// `buffer` doit faire exactement `count * sector_size()` octets. Par
+    /// défaut, lit secteur par secteur : à redéfinir si le device sait faire
+    /// de grands transferts.
+    fn read_sectors(
+        &mut self,
+        first: u32,
+        count: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let size = self.sector_size();
+        if size == 0 || buffer.len() != count as usize * size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        for (i, chunk) in buffer.chunks_mut(size).enumerate() {
+            self.read_sector(first + i as u32, chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Device en lecture/écriture, nécessaire à `Fat32FileSystem`
+pub trait BlockDevice: ReadBlockDevice {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), Self::Error>;
+
+    /// Écrire `count` secteurs consécutifs à partir de `first`
+    ///
+    /// Même contrat que `read_sectors`.
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), Self::Error> {
+        let size = self.sector_size();
+        if size == 0 || buffer.len() != count as usize * size {
+            return Err(Fat32Error::BufferTooSmall);
+        }
+        for (i, chunk) in buffer.chunks(size).enumerate() {
+            self.write_sector(first + i as u32, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Rendre durables les écritures déjà acceptées
+    ///
+    /// Contrat : quand `flush` retourne `Ok`, tous les `write_sector` (et
+    /// `write_sectors`) qui ont retourné `Ok` avant l'appel doivent survivre à
+    /// une coupure. Le système de fichiers s'appuie dessus pour ordonner ses
+    /// écritures.
+    ///
+    /// Appelé une fois à la fin de chaque `Fat32FileSystem::flush`, après les
+    /// métadonnées, donc aussi par `unmount`, `end_batch` et les opérations
+    /// qui flushent elles-mêmes. Ne fait rien par défaut.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Prêter un device au système de fichiers : `Fat32FileSystem::new(&mut sd)`
+///
+/// Toutes les méthodes sont transmises, y compris celles qui ont une
+/// implémentation par défaut, pour garder les redéfinitions du device.
+impl<D: ReadBlockDevice + ?Sized> ReadBlockDevice for &mut D {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        (**self).read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        (**self).sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        (**self).num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        (**self).buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        (**self).read_sectors(first, count, buffer)
+    }
+}
+
+impl<D: BlockDevice + ?Sized> BlockDevice for &mut D {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        (**self).write_sector(sector, buffer)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        (**self).write_sectors(first, count, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        (**self).flush()
+    }
+}
+
+/// Device choisi à l'exécution : `Box<dyn BlockDevice<Error = E>>`
+///
+/// Les traits n'ont ni méthode générique ni `Self` en valeur : ils restent
+/// utilisables en objet, et un `Fat32FileSystem<Box<dyn BlockDevice<Error = E>>>`
+/// monte indifféremment une carte SD, une clé USB ou un disque en mémoire, à
+/// condition qu'ils partagent le même type d'erreur. Comme pour `&mut D`,
+/// toutes les méthodes sont transmises.
+impl<D: ReadBlockDevice + ?Sized> ReadBlockDevice for alloc::boxed::Box<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        (**self).read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        (**self).sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        (**self).num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        (**self).buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        (**self).read_sectors(first, count, buffer)
+    }
+}
+
+impl<D: BlockDevice + ?Sized> BlockDevice for alloc::boxed::Box<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        (**self).write_sector(sector, buffer)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        (**self).write_sectors(first, count, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        (**self).flush()
+    }
+}
@@ -1,48 +1,11 @@
 // src/main.rs - CLI pour FAT32
 use std::env;
-use std::fs::File;
-use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::error::Error;
+use std::io::{self, Write};
 use std::process;
 
-use fat32::{BlockDevice, Fat32FileSystem, Fat32Error, Result};
-
-/// Device basé sur un fichier
-struct FileDevice {
-    file: File,
-}
-
-impl FileDevice {
-    fn open(path: &str) -> io::Result<Self> {
-        let file = File::options().read(true).write(true).open(path)?;
-        Ok(Self { file })
-    }
-}
-
-impl BlockDevice for FileDevice {
-    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
-        self.file
-            .seek(SeekFrom::Start(sector as u64 * 512))
-            .map_err(|_| Fat32Error::IoError)?;
-        self.file
-            .read_exact(buffer)
-            .map_err(|_| Fat32Error::IoError)?;
-        Ok(())
-    }
-
-    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
-        self.file
-            .seek(SeekFrom::Start(sector as u64 * 512))
-            .map_err(|_| Fat32Error::IoError)?;
-        self.file
-            .write_all(buffer)
-            .map_err(|_| Fat32Error::IoError)?;
-        Ok(())
-    }
-
-    fn sector_size(&self) -> usize {
-        512
-    }
-}
+use fat32::devices::FileDevice;
+use fat32::{ErrorKind, Fat32FileSystem, Fat32Error, FatTimestamp, IoErrorKind, Result};
 
 fn print_help(program: &str) {
     println!("FAT32 Filesystem");
@@ -54,14 +17,96 @@ fn print_help(program: &str) {
     println!("  cat <fichier>    Affiche un fichier");
     println!("  cd <chemin>      Change de dossier");
     println!("  pwd              Affiche le dossier courant");
+    println!("  mkfs <taille> [--force]  Crée et formate une image (ex. 64M)");
+    #[cfg(feature = "debug")]
+    println!("  hexdump <secteur> Affiche un secteur en hexadécimal");
     println!();
     println!("Exemples:");
     println!("  {} disk.img ls", program);
     println!("  {} disk.img cat /readme.txt", program);
     println!("  {} disk.img cd /dossier", program);
+    println!("  {} disk.img mkfs 64M", program);
+    println!();
+    println!("Codes de sortie:");
+    println!("  1 usage, 64 argument invalide, 65 volume corrompu, 66 fichier absent,");
+    println!("  73 disque plein, 74 erreur d'entrée/sortie, 77 lecture seule");
+}
+
+/// Code de sortie pour une erreur, selon sa catégorie (valeurs de sysexits.h)
+fn exit_code<E>(error: &Fat32Error<E>) -> i32 {
+    match error.kind() {
+        ErrorKind::InvalidInput => 64,
+        ErrorKind::Corruption => 65,
+        ErrorKind::NotFound => 66,
+        ErrorKind::NoSpace => 73,
+        ErrorKind::Io => 74,
+        ErrorKind::ReadOnly => 77,
+        _ => 1,
+    }
 }
 
-fn main() -> Result<()> {
+/// Afficher l'erreur et quitter avec le code de sa catégorie
+fn fail<E: std::fmt::Debug>(context: &str, error: Fat32Error<E>) -> ! {
+    eprintln!("Erreur: {}{}", context, error);
+    process::exit(exit_code(&error));
+}
+
+/// Taille en octets : nombre avec un suffixe `K`, `M` ou `G` optionnel (puissances de 1024)
+fn parse_size(text: &str) -> Option<u64> {
+    let (digits, unit) = match text.char_indices().last()? {
+        (i, 'K' | 'k') => (&text[..i], 1u64 << 10),
+        (i, 'M' | 'm') => (&text[..i], 1 << 20),
+        (i, 'G' | 'g') => (&text[..i], 1 << 30),
+        _ => (text, 1),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(unit)
+}
+
+/// Taille de cluster par défaut selon la taille du volume (table de Microsoft)
+fn default_sectors_per_cluster(total_sectors: u32) -> u8 {
+    match total_sectors {
+        0..=532_480 => 1,               // jusqu'à 260 Mo
+        532_481..=16_777_216 => 8,      // jusqu'à 8 Go
+        16_777_217..=33_554_432 => 16,  // jusqu'à 16 Go
+        33_554_433..=67_108_864 => 32,  // jusqu'à 32 Go
+        _ => 64,
+    }
+}
+
+/// `mkfs <taille> [--force]` : créer l'image puis la formater
+fn mkfs(program: &str, image_path: &str, args: &[String]) -> Result<(), IoErrorKind> {
+    let Some(size) = args.first().and_then(|s| parse_size(s)) else {
+        eprintln!("Usage: {} {} mkfs <taille> [--force]", program, image_path);
+        process::exit(1);
+    };
+    let force = args.iter().skip(1).any(|arg| arg == "--force");
+
+    let created = if force {
+        FileDevice::create_overwrite(image_path, size)
+    } else {
+        FileDevice::create(image_path, size)
+    };
+    let device = match created {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Erreur: impossible de créer '{}': {}", image_path, e);
+            process::exit(exit_code(&Fat32Error::from(e)));
+        }
+    };
+
+    let sectors = device.sector_count();
+    let fs = Fat32FileSystem::format(device, sectors, default_sectors_per_cluster(sectors))?;
+    println!(
+        "Image '{}' formatée : {} secteurs, {} octets par cluster",
+        image_path,
+        sectors,
+        fs.boot_sector().cluster_size()
+    );
+    fs.unmount().map_err(|(_, e)| e)?;
+    Ok(())
+}
+
+fn main() -> std::result::Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
@@ -71,22 +116,32 @@ fn main() -> Result<()> {
 
     let image_path = &args[1];
 
+    if args.get(2).map(|s| s.as_str()) == Some("mkfs") {
+        if let Err(e) = mkfs(&args[0], image_path, &args[3..]) {
+            fail("", e);
+        }
+        return Ok(());
+    }
+
     // Ouvrir l'image
     let device = match FileDevice::open(image_path) {
         Ok(d) => d,
         Err(e) => {
             eprintln!("Erreur: impossible d'ouvrir '{}': {}", image_path, e);
-            process::exit(1);
+            process::exit(exit_code(&Fat32Error::from(e)));
         }
     };
 
-    // Créer le filesystem
-    let mut fs = match Fat32FileSystem::new(device) {
+    // Créer le filesystem (en lecture seule si l'image n'est pas modifiable)
+    let read_only = device.is_read_only();
+    let mounted = if read_only {
+        Fat32FileSystem::with_readonly(device)
+    } else {
+        Fat32FileSystem::new(device)
+    };
+    let mut fs = match mounted {
         Ok(fs) => fs,
-        Err(e) => {
-            eprintln!("Erreur: filesystem invalide: {}", e);
-            process::exit(1);
-        }
+        Err(e) => fail("filesystem invalide: ", e),
     };
 
     // Commande par défaut = ls
@@ -95,22 +150,25 @@ fn main() -> Result<()> {
     let result = match cmd {
         "ls" => {
             let path = args.get(3).map(|s| s.as_str());
-            match fs.list_dir(path) {
+            match fs.list_dir_with_long_names(path) {
                 Ok(entries) => {
                     if entries.is_empty() {
                         println!("(vide)");
                     } else {
-                        for entry in entries {
+                        for (entry, long_name) in entries {
                             let type_str = if entry.attributes().is_directory() {
                                 "DIR "
                             } else {
                                 "FILE"
                             };
+                            let modified =
+                                FatTimestamp::new(entry.write_date(), entry.write_time());
                             println!(
-                                "{} {:>10}  {}",
+                                "{} {:>10}  {}  {}",
                                 type_str,
                                 entry.file_size(),
-                                entry.short_name()
+                                modified,
+                                long_name.unwrap_or_else(|| entry.short_name())
                             );
                         }
                     }
@@ -122,15 +180,7 @@ fn main() -> Result<()> {
 
         "cat" | "more" => {
             if let Some(file) = args.get(3) {
-                match fs.read_file(file) {
-                    Ok(data) => {
-                        io::stdout()
-                            .write_all(&data)
-                            .map_err(|_| Fat32Error::IoError)?;
-                        Ok(())
-                    }
-                    Err(e) => Err(e),
-                }
+                fs.read_file(file).and_then(|data| Ok(io::stdout().write_all(&data)?))
             } else {
                 eprintln!("Usage: {} {} cat <fichier>", args[0], args[1]);
                 process::exit(1);
@@ -139,10 +189,10 @@ fn main() -> Result<()> {
 
         "cd" => {
             if let Some(path) = args.get(3) {
-                fs.change_dir(path)?;
-                println!("Dossier changé: {}", path);
-                println!("Cluster: {}", fs.current_dir());
-                Ok(())
+                fs.change_dir(path).map(|()| {
+                    println!("Dossier changé: {}", path);
+                    println!("Cluster: {}", fs.current_dir());
+                })
             } else {
                 eprintln!("Usage: {} {} cd <chemin>", args[0], args[1]);
                 process::exit(1);
@@ -154,6 +204,15 @@ fn main() -> Result<()> {
             Ok(())
         }
 
+        #[cfg(feature = "debug")]
+        "hexdump" => match args.get(3).and_then(|s| s.parse::<u32>().ok()) {
+            Some(sector) => fs.read_sector_as_string(sector).map(|dump| print!("{}", dump)),
+            None => {
+                eprintln!("Usage: {} {} hexdump <secteur>", args[0], args[1]);
+                process::exit(1);
+            }
+        },
+
         _ => {
             eprintln!("Commande inconnue: {}", cmd);
             print_help(&args[0]);
@@ -161,10 +220,50 @@ fn main() -> Result<()> {
         }
     };
 
-    if let Err(e) = result {
-        eprintln!("Erreur: {}", e);
-        process::exit(1);
+    match result {
+        Err(e @ Fat32Error::ReadOnlyFilesystem) if read_only => {
+            eprintln!("Erreur: image ouverte en lecture seule");
+            process::exit(exit_code(&e));
+        }
+        Err(e) => fail("", e),
+        Ok(()) => {}
+    }
+
+    if let Err((_, e)) = fs.unmount() {
+        fail("démontage impossible: ", e);
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fat32::ComponentName;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("64M"), Some(64 << 20));
+        assert_eq!(parse_size("512k"), Some(512 << 10));
+        assert_eq!(parse_size("2G"), Some(2 << 30));
+        assert_eq!(parse_size("35651584"), Some(35_651_584));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("12T"), None);
+        assert_eq!(parse_size("99999999999G"), None);
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let name = ComponentName::new("absent");
+        assert_eq!(exit_code(&Fat32Error::<IoErrorKind>::NotFound { component: 0, name }), 66);
+        assert_eq!(exit_code(&Fat32Error::Device(IoErrorKind(io::ErrorKind::Other))), 74);
+        assert_eq!(exit_code(&Fat32Error::<IoErrorKind>::ReadOnlyFilesystem), 77);
+
+        // Image qui ne s'ouvre pas : code de l'io::Error convertie
+        let open_code = |error: io::Error| exit_code(&Fat32Error::from(error));
+        assert_eq!(open_code(io::Error::from(io::ErrorKind::NotFound)), 66);
+        assert_eq!(open_code(io::Error::from(io::ErrorKind::PermissionDenied)), 74);
+        assert_eq!(open_code(io::Error::from(Fat32Error::<IoErrorKind>::DiskFull)), 73);
+    }
+}
@@ -0,0 +1,460 @@
+//! Tables de partitions MBR et GPT
+//!
+//! MBR : seules les quatre entrées primaires sont lues ; les partitions
+//! étendues ne sont pas suivies. GPT : l'en-tête principal (LBA 1) et sa table
+//! sont vérifiés par CRC32 ; l'en-tête de secours n'est pas lu.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::devices::{read_sector_aligned, read_sectors_aligned};
+use crate::{BlockDevice, Fat32Error, Fat32FileSystem, IoOp, ReadBlockDevice, Result};
+
+/// Offset de la table dans le secteur 0
+const TABLE_OFFSET: usize = 446;
+
+/// Type MBR de la partition qui protège un disque GPT
+const PROTECTIVE_TYPE: u8 = 0xEE;
+
+/// Signature de l'en-tête GPT
+const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+
+/// Taille maximale acceptée pour la table des entrées GPT
+const GPT_MAX_TABLE_SIZE: usize = 1024 * 1024;
+
+/// Une entrée de la table de partitions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PartitionEntry {
+    /// Partition active (octet d'état à 0x80)
+    pub bootable: bool,
+    /// Type de partition
+    pub partition_type: u8,
+    /// Premier secteur (LBA)
+    pub start_lba: u32,
+    /// Nombre de secteurs
+    pub sector_count: u32,
+}
+
+impl PartitionEntry {
+    /// Entrée inutilisée
+    pub fn is_empty(&self) -> bool {
+        self.partition_type == 0 || self.sector_count == 0
+    }
+
+    /// Type FAT32 (0x0B CHS ou 0x0C LBA)
+    pub fn is_fat32(&self) -> bool {
+        matches!(self.partition_type, 0x0B | 0x0C)
+    }
+
+    fn from_bytes(raw: &[u8]) -> Self {
+        let read = |offset: usize| {
+            u32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]])
+        };
+        Self {
+            bootable: raw[0] == 0x80,
+            partition_type: raw[4],
+            start_lba: read(8),
+            sector_count: read(12),
+        }
+    }
+}
+
+/// Master Boot Record : les quatre partitions primaires
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mbr {
+    /// Entrées primaires, dans l'ordre de la table
+    pub entries: [PartitionEntry; 4],
+}
+
+impl Mbr {
+    /// Lire la table du secteur 0
+    ///
+    /// Retourne `InvalidPartition` sans la signature 0x55AA.
+    pub fn parse<D: ReadBlockDevice>(device: &mut D) -> Result<Self, D::Error> {
+        let mut buffer = alloc::vec![0u8; device.sector_size().max(512)];
+        read_sector_aligned(device, 0, &mut buffer)?;
+        Self::from_bytes(&buffer).map_err(Fat32Error::widen)
+    }
+
+    /// Lire la table depuis le contenu du secteur 0
+    pub fn from_bytes(sector: &[u8]) -> Result<Self> {
+        if sector.len() < 512 || sector[510..512] != [0x55, 0xAA] {
+            return Err(Fat32Error::InvalidPartition);
+        }
+        let mut entries = [PartitionEntry::default(); 4];
+        for (i, entry) in entries.iter_mut().enumerate() {
+            let offset = TABLE_OFFSET + i * 16;
+            *entry = PartitionEntry::from_bytes(&sector[offset..offset + 16]);
+        }
+        Ok(Self { entries })
+    }
+}
+
+/// GUID tel que stocké sur le disque (trois premiers champs en little-endian)
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    /// Type de la partition système EFI (C12A7328-F81F-11D2-BA4B-00A0C93EC93B)
+    pub const EFI_SYSTEM_PARTITION: Guid = Guid([
+        0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9,
+        0x3B,
+    ]);
+
+    /// GUID nul (entrée inutilisée)
+    pub fn is_nil(&self) -> bool {
+        self.0 == [0; 16]
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:08X}-{:04X}-{:04X}-",
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            u16::from_le_bytes([b[4], b[5]]),
+            u16::from_le_bytes([b[6], b[7]])
+        )?;
+        for (i, byte) in b[8..].iter().enumerate() {
+            if i == 2 {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Guid({})", self)
+    }
+}
+
+/// Une partition d'une table GPT
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GptEntry {
+    /// Type de partition
+    pub type_guid: Guid,
+    /// Identifiant propre à la partition
+    pub unique_guid: Guid,
+    /// Premier secteur (LBA)
+    pub first_lba: u64,
+    /// Dernier secteur (LBA, inclus)
+    pub last_lba: u64,
+    /// Drapeaux d'attributs
+    pub attributes: u64,
+    /// Nom de la partition (UTF-16 décodé)
+    pub name: String,
+}
+
+impl GptEntry {
+    /// Nombre de secteurs
+    ///
+    /// 0 pour une entrée incohérente (`last_lba` avant `first_lba`, ou au
+    /// maximum de `u64`).
+    pub fn sector_count(&self) -> u64 {
+        self.last_lba.checked_add(1).map_or(0, |end| end.saturating_sub(self.first_lba))
+    }
+
+    /// Partition système EFI
+    pub fn is_esp(&self) -> bool {
+        self.type_guid == Guid::EFI_SYSTEM_PARTITION
+    }
+
+    fn from_bytes(raw: &[u8]) -> Self {
+        let guid = |offset: usize| Guid(raw[offset..offset + 16].try_into().unwrap());
+        let units: Vec<u16> = raw[56..128]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        Self {
+            type_guid: guid(0),
+            unique_guid: guid(16),
+            first_lba: read_u64(raw, 32),
+            last_lba: read_u64(raw, 40),
+            attributes: read_u64(raw, 48),
+            name: String::from_utf16_lossy(&units),
+        }
+    }
+}
+
+/// Table de partitions GPT
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gpt {
+    /// Identifiant du disque
+    pub disk_guid: Guid,
+    /// Premier secteur utilisable par les partitions
+    pub first_usable_lba: u64,
+    /// Dernier secteur utilisable par les partitions
+    pub last_usable_lba: u64,
+    /// Entrées utilisées, dans l'ordre de la table
+    pub entries: Vec<GptEntry>,
+}
+
+impl Gpt {
+    /// Lire la table GPT d'un disque
+    ///
+    /// `Ok(None)` s'il n'y a pas de GPT (pas de MBR protecteur ou pas de
+    /// signature), pour se replier sur `Mbr::parse`. Un en-tête ou une table
+    /// dont le CRC32 ne correspond pas donne `InvalidPartition`.
+    pub fn parse<D: ReadBlockDevice>(device: &mut D) -> Result<Option<Self>, D::Error> {
+        let sector_size = device.sector_size().max(512);
+        let mut sector = alloc::vec![0u8; sector_size];
+
+        let protective = match Mbr::parse(device) {
+            Ok(mbr) => mbr.entries.iter().any(|e| e.partition_type == PROTECTIVE_TYPE),
+            Err(Fat32Error::InvalidPartition) => false,
+            Err(e) => return Err(e),
+        };
+        if !protective {
+            return Ok(None);
+        }
+
+        read_sector_aligned(device, 1, &mut sector)?;
+        if &sector[..8] != GPT_SIGNATURE {
+            return Ok(None);
+        }
+
+        // CRC de l'en-tête, calculé avec son propre champ à zéro
+        let header_size = read_u32(&sector, 12) as usize;
+        if !(92..=sector_size).contains(&header_size) {
+            return Err(Fat32Error::InvalidPartition);
+        }
+        let mut header = sector[..header_size].to_vec();
+        header[16..20].fill(0);
+        if crc32(&header) != read_u32(&sector, 16) {
+            return Err(Fat32Error::InvalidPartition);
+        }
+
+        // Table des entrées
+        let table_lba =
+            u32::try_from(read_u64(&sector, 72)).map_err(|_| Fat32Error::InvalidPartition)?;
+        let count = read_u32(&sector, 80) as usize;
+        let entry_size = read_u32(&sector, 84) as usize;
+        let table_size = count.saturating_mul(entry_size);
+        if entry_size < 128 || !entry_size.is_multiple_of(8) || table_size > GPT_MAX_TABLE_SIZE {
+            return Err(Fat32Error::InvalidPartition);
+        }
+        let sectors = table_size.div_ceil(sector_size);
+        let mut table = alloc::vec![0u8; sectors * sector_size];
+        read_sectors_aligned(device, table_lba, sectors as u32, &mut table)?;
+        if crc32(&table[..table_size]) != read_u32(&sector, 88) {
+            return Err(Fat32Error::InvalidPartition);
+        }
+
+        let entries = table[..table_size]
+            .chunks_exact(entry_size)
+            .map(GptEntry::from_bytes)
+            .filter(|entry| !entry.type_guid.is_nil())
+            .collect();
+
+        Ok(Some(Self {
+            disk_guid: Guid(sector[56..72].try_into().unwrap()),
+            first_usable_lba: read_u64(&sector, 40),
+            last_usable_lba: read_u64(&sector, 48),
+            entries,
+        }))
+    }
+
+    /// Première partition système EFI
+    pub fn find_esp(&self) -> Option<&GptEntry> {
+        self.entries.iter().find(|entry| entry.is_esp())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// CRC32 IEEE 802.3 (celui de GPT), bit par bit
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Vue d'une partition : les numéros de secteur partent du début de la partition
+///
+/// Un accès au-delà de la fin de la partition donne `IoError`.
+pub struct PartitionDevice<D: ReadBlockDevice> {
+    device: D,
+    start: u32,
+    sectors: u32,
+}
+
+impl<D: ReadBlockDevice> PartitionDevice<D> {
+    /// Partition de `sectors` secteurs commençant au secteur `start`
+    pub fn new(device: D, start: u32, sectors: u32) -> Self {
+        Self { device, start, sectors }
+    }
+
+    /// Partition décrite par une entrée de la table
+    pub fn from_entry(device: D, entry: &PartitionEntry) -> Self {
+        Self::new(device, entry.start_lba, entry.sector_count)
+    }
+
+    /// Partition décrite par une entrée GPT
+    ///
+    /// `InvalidPartition` si elle est vide ou dépasse les secteurs adressables
+    /// en 32 bits.
+    pub fn from_gpt_entry(device: D, entry: &GptEntry) -> Result<Self, D::Error> {
+        let start = u32::try_from(entry.first_lba).map_err(|_| Fat32Error::InvalidPartition)?;
+        let sectors =
+            u32::try_from(entry.sector_count()).map_err(|_| Fat32Error::InvalidPartition)?;
+        if sectors == 0 {
+            return Err(Fat32Error::InvalidPartition);
+        }
+        start.checked_add(sectors).ok_or(Fat32Error::InvalidPartition)?;
+        Ok(Self::new(device, start, sectors))
+    }
+
+    /// Premier secteur de la partition sur le device
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    /// Nombre de secteurs de la partition
+    pub fn sector_count(&self) -> u32 {
+        self.sectors
+    }
+
+    /// Device sous-jacent
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Récupérer le device sous-jacent
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Secteur du device pour un accès de `len` octets au secteur `sector`
+    fn translate(&self, op: IoOp, sector: u32, len: usize) -> Result<u32, D::Error> {
+        let size = self.device.sector_size().max(1);
+        let count = len.div_ceil(size) as u64;
+        let error = Fat32Error::IoError { op, sector };
+        if sector as u64 + count > self.sectors as u64 {
+            return Err(error);
+        }
+        self.start.checked_add(sector).ok_or(error)
+    }
+}
+
+impl<D: ReadBlockDevice> ReadBlockDevice for PartitionDevice<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        let sector = self.translate(IoOp::Read, sector, buffer.len())?;
+        self.device.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.device.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.sectors as u64
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.device.buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        let first = self.translate(IoOp::Read, first, buffer.len())?;
+        self.device.read_sectors(first, count, buffer)
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for PartitionDevice<D> {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        let sector = self.translate(IoOp::Write, sector, buffer.len())?;
+        self.device.write_sector(sector, buffer)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<(), D::Error> {
+        let first = self.translate(IoOp::Write, first, buffer.len())?;
+        self.device.write_sectors(first, count, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), D::Error> {
+        self.device.flush()
+    }
+}
+
+impl<D: BlockDevice> Fat32FileSystem<PartitionDevice<D>> {
+    /// Monter la partition `index` d'un disque partitionné
+    ///
+    /// Sur un disque GPT, `index` compte les entrées utilisées de la table ;
+    /// sinon c'est une partition primaire MBR (0 à 3). Le type de la partition
+    /// n'est pas vérifié, seul le secteur de boot compte. `InvalidPartition`
+    /// si l'entrée est vide ou hors table.
+    pub fn new_from_partition(mut device: D, index: usize) -> Result<Self, D::Error> {
+        if let Some(gpt) = Gpt::parse(&mut device)? {
+            let entry = gpt.entries.get(index).ok_or(Fat32Error::InvalidPartition)?;
+            return Self::new(PartitionDevice::from_gpt_entry(device, entry)?);
+        }
+
+        let mbr = Mbr::parse(&mut device)?;
+        let entry = mbr.entries.get(index).ok_or(Fat32Error::InvalidPartition)?;
+        if entry.is_empty() {
+            return Err(Fat32Error::InvalidPartition);
+        }
+        Self::new(PartitionDevice::from_entry(device, entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let mut sector = [0u8; 512];
+        assert_eq!(Mbr::from_bytes(&sector), Err(Fat32Error::InvalidPartition));
+
+        sector[510] = 0x55;
+        sector[511] = 0xAA;
+        let first = &mut sector[TABLE_OFFSET..TABLE_OFFSET + 16];
+        first[0] = 0x80;
+        first[4] = 0x0C;
+        first[8..12].copy_from_slice(&2048u32.to_le_bytes());
+        first[12..16].copy_from_slice(&100_000u32.to_le_bytes());
+        sector[TABLE_OFFSET + 16 + 4] = 0x83;
+        sector[TABLE_OFFSET + 16 + 12] = 1;
+
+        let mbr = Mbr::from_bytes(&sector).unwrap();
+        let expected = PartitionEntry {
+            bootable: true,
+            partition_type: 0x0C,
+            start_lba: 2048,
+            sector_count: 100_000,
+        };
+        assert_eq!(mbr.entries[0], expected);
+        assert!(mbr.entries[0].is_fat32());
+        assert!(!mbr.entries[1].is_fat32() && !mbr.entries[1].is_empty());
+        assert!(mbr.entries[2].is_empty() && mbr.entries[3].is_empty());
+    }
+
+    #[test]
+    fn test_crc32_and_guid_display() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(
+            alloc::format!("{}", Guid::EFI_SYSTEM_PARTITION),
+            "C12A7328-F81F-11D2-BA4B-00A0C93EC93B"
+        );
+    }
+}
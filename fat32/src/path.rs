@@ -0,0 +1,159 @@
+//! Validation des composants de chemin
+
+use core::fmt;
+
+/// Longueur maximale d'un nom (limite des noms longs)
+pub const MAX_NAME_LEN: usize = 255;
+
+/// Raison du rejet d'un nom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NameError {
+    /// Nom vide
+    Empty,
+    /// Plus de 255 caractères
+    TooLong,
+    /// Contient un octet nul
+    NulByte,
+    /// Contient un caractère de contrôle (< 0x20)
+    ControlChar,
+    /// Contient un caractère interdit par FAT (`" * / : < > ? \ |`)
+    IllegalChar(char),
+    /// Composé uniquement de points
+    OnlyDots,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "nom vide"),
+            Self::TooLong => write!(f, "nom trop long"),
+            Self::NulByte => write!(f, "octet nul dans le nom"),
+            Self::ControlChar => write!(f, "caractère de contrôle dans le nom"),
+            Self::IllegalChar(c) => write!(f, "caractère interdit '{}'", c),
+            Self::OnlyDots => write!(f, "nom composé uniquement de points"),
+        }
+    }
+}
+
+/// Vérifier un nom avant de le créer (règles strictes)
+pub fn validate_component(name: &str) -> Result<(), NameError> {
+    validate_lookup_component(name)?;
+
+    if name.is_empty() {
+        return Err(NameError::Empty);
+    }
+    if name.chars().all(|c| c == '.') {
+        return Err(NameError::OnlyDots);
+    }
+
+    for c in name.chars() {
+        if (c as u32) < 0x20 {
+            return Err(NameError::ControlChar);
+        }
+        if matches!(c, '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|') {
+            return Err(NameError::IllegalChar(c));
+        }
+    }
+
+    Ok(())
+}
+
+/// Vérifier un nom utilisé pour une recherche (règles souples)
+///
+/// Seuls les noms qui ne peuvent jamais exister sont rejetés ; un nom avec
+/// des caractères interdits est accepté et ne trouvera simplement rien.
+pub fn validate_lookup_component(name: &str) -> Result<(), NameError> {
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(NameError::TooLong);
+    }
+    if name.contains('\0') {
+        return Err(NameError::NulByte);
+    }
+    Ok(())
+}
+
+/// Étape de la résolution d'un chemin
+pub(crate) enum Step<'a> {
+    /// ".." : remonter au dossier parent
+    Parent,
+    /// Descendre dans le sous-dossier nommé
+    Child(&'a str),
+}
+
+/// Découper un chemin en étapes, en indiquant s'il part de la racine
+///
+/// Les composants vides et "." sont sautés ; chaque nom est vérifié avec
+/// `validate_lookup_component` au moment où son étape est produite.
+pub(crate) fn lookup_steps(
+    path: &str,
+) -> (bool, impl Iterator<Item = Result<Step<'_>, NameError>>) {
+    let (absolute, rest) = match path.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, path),
+    };
+    let steps = components(rest).map(|component| match component {
+        ".." => Ok(Step::Parent),
+        name => validate_lookup_component(name).map(|()| Step::Child(name)),
+    });
+    (absolute, steps)
+}
+
+/// Composants d'un chemin, sans les vides ni les "."
+///
+/// Ce sont les étapes de `lookup_steps`, dans l'ordre : leur index est
+/// celui de `Fat32Error::NotFound` et `InvalidPath`.
+pub(crate) fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|component| !component.is_empty() && *component != ".")
+}
+
+/// Index du dernier composant d'un chemin (0 s'il n'y en a aucun)
+pub(crate) fn last_component(path: &str) -> u16 {
+    components(path).count().saturating_sub(1).min(u16::MAX as usize) as u16
+}
+
+/// Séparer un chemin en dossier + dernier composant
+///
+/// Pour "/fichier", le dossier est "/" (la racine, pas le répertoire courant).
+pub(crate) fn split_parent(path: &str) -> (&str, &str) {
+    match path.rfind('/') {
+        Some(0) => ("/", &path[1..]),
+        Some(pos) => (&path[..pos], &path[pos + 1..]),
+        None => ("", path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    type Check = Result<(), NameError>;
+
+    #[test]
+    fn test_validate_component_table() {
+        let long: String = core::iter::repeat_n('a', 256).collect();
+        let max: String = core::iter::repeat_n('a', 255).collect();
+
+        let cases: [(&str, Check, Check); 12] = [
+            // (nom, création, recherche)
+            ("readme.txt", Ok(()), Ok(())),
+            ("a", Ok(()), Ok(())),
+            (".hidden", Ok(()), Ok(())),
+            (&max, Ok(()), Ok(())),
+            ("", Err(NameError::Empty), Ok(())),
+            (&long, Err(NameError::TooLong), Err(NameError::TooLong)),
+            ("a\0b", Err(NameError::NulByte), Err(NameError::NulByte)),
+            ("a\tb", Err(NameError::ControlChar), Ok(())),
+            ("what?", Err(NameError::IllegalChar('?')), Ok(())),
+            ("a:b", Err(NameError::IllegalChar(':')), Ok(())),
+            ("...", Err(NameError::OnlyDots), Ok(())),
+            ("a|b", Err(NameError::IllegalChar('|')), Ok(())),
+        ];
+
+        for (name, create, lookup) in cases {
+            assert_eq!(validate_component(name), create, "création de {:?}", name);
+            assert_eq!(validate_lookup_component(name), lookup, "recherche de {:?}", name);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Système de fichiers monté en lecture seule
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{
+    BlockDevice, BootSector, ClusterLookup, DirCounts, DirRef, DirectoryEntry, Fat32Error,
+    Fat32FileSystem, FatFile, IntegrityReport, Lines, ReadBlockDevice, Result, VolumeInfo,
+};
+
+/// Device vu par le `Fat32FileSystem` d'un `ReadOnlyFileSystem`
+///
+/// Ne se construit qu'à travers `ReadOnlyFileSystem`, qui n'expose aucune
+/// opération d'écriture : `write_sector` n'est jamais appelé, il répond
+/// `ReadOnly` par sécurité.
+pub struct ReadOnlyDevice<D: ReadBlockDevice>(D);
+
+impl<D: ReadBlockDevice> ReadBlockDevice for ReadOnlyDevice<D> {
+    type Error = D::Error;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.0.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.0.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.0.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        self.0.buffer_alignment()
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<(), D::Error> {
+        self.0.read_sectors(first, count, buffer)
+    }
+}
+
+impl<D: ReadBlockDevice> BlockDevice for ReadOnlyDevice<D> {
+    fn write_sector(&mut self, _sector: u32, _buffer: &[u8]) -> Result<(), D::Error> {
+        Err(Fat32Error::ReadOnly)
+    }
+}
+
+/// Système de fichiers FAT32 monté sur une source en lecture seule
+///
+/// Le device n'a besoin que de `ReadBlockDevice` (image en ROM, fichier
+/// ouvert sans droit d'écriture...). Seules les opérations de lecture de
+/// `Fat32FileSystem` sont disponibles : aucune écriture n'est jamais faite,
+/// pas même au démontage.
+pub struct ReadOnlyFileSystem<D: ReadBlockDevice> {
+    fs: Fat32FileSystem<ReadOnlyDevice<D>>,
+}
+
+impl<D: ReadBlockDevice> ReadOnlyFileSystem<D> {
+    /// Monter le système de fichiers
+    pub fn new(device: D) -> Result<Self, D::Error> {
+        Ok(Self { fs: Fat32FileSystem::with_readonly(ReadOnlyDevice(device))? })
+    }
+
+    /// Boot sector du volume monté
+    pub fn boot_sector(&self) -> &BootSector {
+        self.fs.boot_sector()
+    }
+
+    /// Accéder au device sous-jacent
+    pub fn device(&self) -> &D {
+        &self.fs.device().0
+    }
+
+    /// Démonter le système de fichiers et récupérer le device
+    pub fn into_device(self) -> D {
+        self.fs.into_device().0
+    }
+
+    /// Voir `Fat32FileSystem::current_dir`
+    pub fn current_dir(&self) -> u32 {
+        self.fs.current_dir()
+    }
+
+    /// Voir `Fat32FileSystem::change_dir`
+    pub fn change_dir(&mut self, path: &str) -> Result<(), D::Error> {
+        self.fs.change_dir(path)
+    }
+
+    /// Voir `Fat32FileSystem::list_dir`
+    pub fn list_dir(&mut self, path: Option<&str>) -> Result<Vec<DirectoryEntry>, D::Error> {
+        self.fs.list_dir(path)
+    }
+
+    /// Voir `Fat32FileSystem::list_dir_with_long_names`
+    pub fn list_dir_with_long_names(
+        &mut self,
+        path: Option<&str>,
+    ) -> Result<Vec<(DirectoryEntry, Option<String>)>, D::Error> {
+        self.fs.list_dir_with_long_names(path)
+    }
+
+    /// Voir `Fat32FileSystem::list_dir_page`
+    pub fn list_dir_page(
+        &mut self,
+        path: Option<&str>,
+        start: usize,
+        count: usize,
+    ) -> Result<(Vec<DirectoryEntry>, bool), D::Error> {
+        self.fs.list_dir_page(path, start, count)
+    }
+
+    /// Voir `Fat32FileSystem::list_dir_recursive_to_flat`
+    pub fn list_dir_recursive_to_flat(
+        &mut self,
+        path: Option<&str>,
+    ) -> Result<Vec<(String, DirectoryEntry)>, D::Error> {
+        self.fs.list_dir_recursive_to_flat(path)
+    }
+
+    /// Voir `Fat32FileSystem::count_entries`
+    pub fn count_entries(&mut self, path: Option<&str>) -> Result<DirCounts, D::Error> {
+        self.fs.count_entries(path)
+    }
+
+    /// Voir `Fat32FileSystem::find_entry_in_dir`
+    pub fn find_entry_in_dir(
+        &mut self,
+        dir_cluster: u32,
+        name: &str,
+    ) -> Result<Option<DirectoryEntry>, D::Error> {
+        self.fs.find_entry_in_dir(dir_cluster, name)
+    }
+
+    /// Voir `Fat32FileSystem::read_file`
+    pub fn read_file(&mut self, path: &str) -> Result<Vec<u8>, D::Error> {
+        self.fs.read_file(path)
+    }
+
+    /// Voir `Fat32FileSystem::read_to_string`
+    pub fn read_to_string(&mut self, path: &str) -> Result<String, D::Error> {
+        self.fs.read_to_string(path)
+    }
+
+    /// Voir `Fat32FileSystem::read_lines`
+    pub fn read_lines(&mut self, path: &str) -> Result<Lines<'_, ReadOnlyDevice<D>>, D::Error> {
+        self.fs.read_lines(path)
+    }
+
+    /// Voir `Fat32FileSystem::compare_files`
+    pub fn compare_files(&mut self, path1: &str, path2: &str) -> Result<bool, D::Error> {
+        self.fs.compare_files(path1, path2)
+    }
+
+    /// Voir `Fat32FileSystem::open_dir`
+    pub fn open_dir(&mut self, dir: DirRef, path: &str) -> Result<DirRef, D::Error> {
+        self.fs.open_dir(dir, path)
+    }
+
+    /// Voir `Fat32FileSystem::list_dir_at`
+    pub fn list_dir_at(
+        &mut self,
+        dir: DirRef,
+        path: &str,
+    ) -> Result<Vec<DirectoryEntry>, D::Error> {
+        self.fs.list_dir_at(dir, path)
+    }
+
+    /// Voir `Fat32FileSystem::read_file_at`
+    pub fn read_file_at(&mut self, dir: DirRef, path: &str) -> Result<Vec<u8>, D::Error> {
+        self.fs.read_file_at(dir, path)
+    }
+
+    /// Ouvrir un fichier en lecture (voir `Fat32FileSystem::open_file`)
+    pub fn open_file(&mut self, path: &str) -> Result<FatFile, D::Error> {
+        self.fs.open_file(path)
+    }
+
+    /// Voir `Fat32FileSystem::read_handle`
+    pub fn read_handle(&mut self, file: &mut FatFile, buf: &mut [u8]) -> Result<usize, D::Error> {
+        self.fs.read_handle(file, buf)
+    }
+
+    /// Voir `Fat32FileSystem::preload_fat`
+    pub fn preload_fat(&mut self) -> Result<(), D::Error> {
+        self.fs.preload_fat()
+    }
+
+    /// Voir `Fat32FileSystem::check_integrity`
+    pub fn check_integrity(&mut self) -> Result<IntegrityReport, D::Error> {
+        self.fs.check_integrity()
+    }
+
+    /// Voir `Fat32FileSystem::free_space`
+    pub fn free_space(&mut self) -> Result<u64, D::Error> {
+        self.fs.free_space()
+    }
+
+    /// Voir `Fat32FileSystem::volume_info`
+    pub fn volume_info(&mut self) -> Result<VolumeInfo, D::Error> {
+        self.fs.volume_info()
+    }
+
+    /// Voir `Fat32FileSystem::cluster_chain_length`
+    pub fn cluster_chain_length(&mut self, start_cluster: u32) -> Result<u32, D::Error> {
+        self.fs.cluster_chain_length(start_cluster)
+    }
+
+    /// Voir `Fat32FileSystem::used_clusters_by_file`
+    pub fn used_clusters_by_file(&mut self, path: &str) -> Result<u32, D::Error> {
+        self.fs.used_clusters_by_file(path)
+    }
+
+    /// Voir `Fat32FileSystem::path_of_cluster`
+    pub fn path_of_cluster(&mut self, cluster: u32) -> Result<Option<String>, D::Error> {
+        self.fs.path_of_cluster(cluster)
+    }
+
+    /// Voir `Fat32FileSystem::path_of_cluster_with`
+    pub fn path_of_cluster_with(
+        &mut self,
+        cluster: u32,
+        mode: ClusterLookup,
+    ) -> Result<Option<String>, D::Error> {
+        self.fs.path_of_cluster_with(cluster, mode)
+    }
+}
@@ -0,0 +1,107 @@
+// Tampons alignés pour un device DMA
+mod common;
+
+use core::convert::Infallible;
+
+use common::image_sectors;
+use fat32::devices::{CachedDevice, TraceDevice};
+use fat32::{BlockDevice, Fat32FileSystem, MemoryDevice, ReadBlockDevice, Result};
+
+const ALIGN: usize = 64;
+
+/// Device qui refuse tout tampon mal aligné, comme un contrôleur DMA
+struct DmaDevice {
+    inner: TraceDevice<MemoryDevice>,
+}
+
+impl DmaDevice {
+    fn check(&mut self, buffer: &[u8]) {
+        assert!(
+            (buffer.as_ptr() as usize).is_multiple_of(ALIGN),
+            "tampon mal aligné : {:p}",
+            buffer.as_ptr()
+        );
+    }
+}
+
+impl ReadBlockDevice for DmaDevice {
+    type Error = Infallible;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
+        self.check(buffer);
+        self.inner.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.inner.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.inner.num_sectors()
+    }
+
+    fn buffer_alignment(&self) -> usize {
+        ALIGN
+    }
+
+    fn read_sectors(&mut self, first: u32, count: u32, buffer: &mut [u8]) -> Result<()> {
+        self.check(buffer);
+        self.inner.read_sectors(first, count, buffer)
+    }
+}
+
+impl BlockDevice for DmaDevice {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
+        self.check(buffer);
+        self.inner.write_sector(sector, buffer)
+    }
+
+    fn write_sectors(&mut self, first: u32, count: u32, buffer: &[u8]) -> Result<()> {
+        self.check(buffer);
+        self.inner.write_sectors(first, count, buffer)
+    }
+}
+
+/// Un peu de tout : création, lecture, suppression, vérification
+fn exercise<D: BlockDevice<Error = Infallible>>(fs: &mut Fat32FileSystem<D>) {
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/docs/a.bin", &data).unwrap();
+    fs.write_file_atomic("/b.txt", b"bonjour").unwrap();
+    for i in 0..40 {
+        fs.write_file(&format!("/docs/f{}.txt", i), b"x").unwrap();
+    }
+    assert_eq!(fs.read_file("/docs/a.bin").unwrap(), data);
+
+    // Tampon de l'appelant volontairement décalé d'un octet
+    let mut storage = vec![0u8; 6000];
+    let misaligned = &mut storage[1..];
+    assert_eq!(fs.read_file_to_fixed_buf("/docs/a.bin", misaligned).unwrap(), 5000);
+    assert_eq!(&misaligned[..5000], &data[..]);
+
+    fs.delete_file("/b.txt").unwrap();
+    fs.flush().unwrap();
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_dma_alignment() {
+    let device = DmaDevice { inner: TraceDevice::new(MemoryDevice::new(image_sectors(2))) };
+    let mut fs = Fat32FileSystem::format(device, image_sectors(2), 2).unwrap();
+    exercise(&mut fs);
+
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert!(!device.inner.trace().is_empty());
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.read_file("/docs/a.bin").unwrap().len(), 5000);
+}
+
+#[test]
+fn test_dma_alignment_through_cache() {
+    // Le cache transmet l'alignement et garde ses secteurs alignés
+    let device = DmaDevice { inner: TraceDevice::new(MemoryDevice::new(image_sectors(2))) };
+    let device = CachedDevice::new(device, 4);
+    assert_eq!(device.buffer_alignment(), ALIGN);
+    let mut fs = Fat32FileSystem::format(device, image_sectors(2), 2).unwrap();
+    exercise(&mut fs);
+}
@@ -0,0 +1,52 @@
+// Opérations ancrées sur un répertoire, indépendantes du répertoire courant
+mod common;
+
+use common::formatted_fs;
+use fat32::DirRef;
+
+#[test]
+fn test_anchored_ops_ignore_cwd() {
+    let mut fs = formatted_fs();
+
+    fs.create_dir("/logs").unwrap();
+    fs.create_dir("/etc").unwrap();
+    fs.write_file("/logs/today.txt", b"logs").unwrap();
+    fs.write_file("/etc/today.txt", b"etc").unwrap();
+    fs.write_file("/today.txt", b"root").unwrap();
+
+    let logs = fs.open_dir(DirRef::Root, "logs").unwrap();
+    let etc = fs.open_dir(DirRef::Root, "/etc").unwrap();
+    assert_eq!(fs.open_dir(logs, "..").unwrap(), DirRef::Root);
+    assert!(fs.open_dir(DirRef::Root, "today.txt").is_err());
+
+    // Deux appelants entrelacés avec des changements de répertoire courant
+    for cwd in ["/etc", "/logs", "/"] {
+        fs.change_dir(cwd).unwrap();
+        assert_eq!(fs.read_file_at(logs, "today.txt").unwrap(), b"logs");
+        fs.change_dir("/logs").unwrap();
+        assert_eq!(fs.read_file_at(etc, "today.txt").unwrap(), b"etc");
+        assert_eq!(fs.read_file_at(DirRef::Root, "logs/today.txt").unwrap(), b"logs");
+        assert_eq!(fs.read_file_at(etc, "/today.txt").unwrap(), b"root");
+    }
+
+    // Le répertoire courant n'a pas bougé à cause des opérations ancrées
+    fs.change_dir("/etc").unwrap();
+    let entries = fs.list_dir_at(logs, "").unwrap();
+    assert!(entries.iter().any(|e| e.short_name() == "TODAY.TXT"));
+    let root = fs.list_dir_at(DirRef::Root, "").unwrap();
+    assert_eq!(root.iter().filter(|e| e.attributes().is_directory()).count(), 2);
+    assert_eq!(fs.read_file("today.txt").unwrap(), b"etc");
+}
+
+#[test]
+fn test_absolute_file_path_ignores_cwd() {
+    let mut fs = formatted_fs();
+
+    fs.create_dir("/sub").unwrap();
+    fs.write_file("/a.txt", b"root").unwrap();
+    fs.write_file("/sub/a.txt", b"sub").unwrap();
+
+    fs.change_dir("/sub").unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"root");
+    assert_eq!(fs.read_file("a.txt").unwrap(), b"sub");
+}
@@ -0,0 +1,125 @@
+// Système de fichiers asynchrone sur une image lue avec tokio (features `std` et `async`)
+mod common;
+
+use std::fs;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+
+use common::{formatted_fs, not_found};
+use fat32::{AsyncBlockDevice, AsyncFat32FileSystem, Fat32Error};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+/// Image dans un fichier de l'hôte, lue et écrite avec `tokio::fs`
+struct TokioFileDevice {
+    file: tokio::fs::File,
+}
+
+impl AsyncBlockDevice for TokioFileDevice {
+    type Error = std::io::ErrorKind;
+
+    async fn read_sector(
+        &mut self,
+        sector: u32,
+        buffer: &mut [u8],
+    ) -> fat32::Result<(), Self::Error> {
+        let offset = sector as u64 * 512;
+        let device_error = |e: std::io::Error| Fat32Error::Device(e.kind());
+        self.file.seek(SeekFrom::Start(offset)).await.map_err(device_error)?;
+        self.file.read_exact(buffer).await.map_err(device_error)?;
+        Ok(())
+    }
+
+    async fn write_sector(
+        &mut self,
+        sector: u32,
+        buffer: &[u8],
+    ) -> fat32::Result<(), Self::Error> {
+        let offset = sector as u64 * 512;
+        let device_error = |e: std::io::Error| Fat32Error::Device(e.kind());
+        self.file.seek(SeekFrom::Start(offset)).await.map_err(device_error)?;
+        self.file.write_all(buffer).await.map_err(device_error)?;
+        Ok(())
+    }
+
+    fn sector_size(&self) -> usize {
+        512
+    }
+}
+
+/// Image formatée avec le code synchrone, écrite dans un fichier temporaire
+fn build_image(name: &str, data: &[u8]) -> PathBuf {
+    let mut fs = formatted_fs();
+    fs.write_file("/hello.txt", b"bonjour").unwrap();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/docs/notes.txt", b"texte").unwrap();
+    fs.write_file("/docs/big.bin", data).unwrap();
+    let image = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+
+    let path = std::env::temp_dir().join(format!("fat32-{}-{}.img", name, std::process::id()));
+    fs::write(&path, image).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_async_list_and_read() {
+    let big: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let path = build_image("async-read", &big);
+
+    let file = tokio::fs::File::open(&path).await.unwrap();
+    let mut fs = AsyncFat32FileSystem::new(TokioFileDevice { file }).await.unwrap();
+
+    let names: Vec<String> =
+        fs.list_dir(None).await.unwrap().iter().map(|e| e.short_name()).collect();
+    assert_eq!(names, ["HELLO.TXT", "DOCS"]);
+    assert_eq!(fs.read_file("/hello.txt").await.unwrap(), b"bonjour");
+
+    // Chemins relatifs et casse ignorée, comme en synchrone
+    fs.change_dir("docs").await.unwrap();
+    let listing = fs.list_dir_with_long_names(None).await.unwrap();
+    assert_eq!(listing.len(), 4); // ".", "..", NOTES.TXT, BIG.BIN
+    assert_eq!(fs.read_to_string("Notes.TXT").await.unwrap(), "texte");
+    assert_eq!(fs.read_file("big.bin").await.unwrap(), big);
+    fs.change_dir("..").await.unwrap();
+    assert_eq!(fs.current_dir(), fs.boot_sector().root_cluster());
+
+    assert_eq!(fs.read_file("/absent.txt").await, Err(not_found(0, "absent.txt")));
+    assert_eq!(fs.read_file("/docs").await, Err(not_found(0, "docs")));
+    assert_eq!(fs.change_dir("/hello.txt").await, Err(not_found(0, "hello.txt")));
+
+    drop(fs);
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_async_streaming_read() {
+    let big: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let path = build_image("async-stream", &big);
+
+    let file = tokio::fs::File::open(&path).await.unwrap();
+    let mut fs = AsyncFat32FileSystem::new(TokioFileDevice { file }).await.unwrap();
+
+    // Morceaux de 300 octets : à cheval sur les secteurs et les clusters
+    let mut handle = fs.open_file("/docs/big.bin").await.unwrap();
+    assert_eq!(handle.size(), 5000);
+    let mut data = Vec::new();
+    let mut chunk = [0u8; 300];
+    loop {
+        let n = fs.read_handle(&mut handle, &mut chunk).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..n]);
+    }
+    assert_eq!(data, big);
+    assert!(handle.is_eof());
+
+    // Reprise au milieu du fichier
+    handle.set_position(4990);
+    assert_eq!(fs.read_handle(&mut handle, &mut chunk).await.unwrap(), 10);
+    assert_eq!(chunk[..10], big[4990..]);
+
+    assert_eq!(fs.open_file("/docs").await.unwrap_err(), Fat32Error::InvalidPath { component: 0 });
+
+    drop(fs);
+    let _ = fs::remove_file(&path);
+}
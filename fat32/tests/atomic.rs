@@ -0,0 +1,90 @@
+// Remplacement atomique d'un fichier, vérifié à chaque point de coupure
+mod common;
+
+use common::formatted_fs;
+use fat32::devices::{Fault, FaultyDevice};
+use fat32::{Fat32FileSystem, Fat32Error, MemoryDevice};
+
+/// Image contenant `/conf/settings.cfg` avec `old`, suivi de `fillers` fichiers
+fn base_image(old: &[u8], fillers: usize) -> Vec<u8> {
+    let mut fs = formatted_fs();
+    fs.create_dir("/conf").unwrap();
+    fs.write_file("/conf/notes.txt", b"voisin").unwrap();
+    fs.write_file("/conf/settings.cfg", old).unwrap();
+    for i in 0..fillers {
+        fs.write_file(&format!("/conf/f{}.txt", i), b"").unwrap();
+    }
+    fs.into_device().into_inner()
+}
+
+#[test]
+fn test_write_file_atomic_every_cut_point() {
+    // Entrée temporaire dans le secteur de l'ancienne, puis dans le suivant
+    check_every_cut_point(0);
+    check_every_cut_point(13);
+}
+
+fn check_every_cut_point(fillers: usize) {
+    let old: Vec<u8> = (0..1500u32).map(|i| (i % 7) as u8).collect();
+    let new: Vec<u8> = (0..2600u32).map(|i| (i % 11) as u8 + 100).collect();
+    let image = base_image(&old, fillers);
+
+    let mut cut = 0;
+    loop {
+        // Coupure de courant à l'écriture numéro `cut`
+        let mut device = FaultyDevice::new(MemoryDevice::from_vec(image.clone()));
+        device.inject(Fault::FailWrite(cut));
+        device.fail_after_trigger(true);
+        let mut fs = Fat32FileSystem::new(device).unwrap();
+        let done = fs.write_file_atomic("/conf/settings.cfg", &new).is_ok();
+
+        // Remonter ce qui a atteint le disque
+        let data = fs.device().inner().data().to_vec();
+        let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(data)).unwrap();
+        let names: Vec<String> = fs
+            .list_dir(Some("/conf"))
+            .unwrap()
+            .iter()
+            .map(|e| e.short_name())
+            .collect();
+
+        match fs.read_file("/conf/settings.cfg") {
+            Ok(content) => assert!(
+                content == old || content == new,
+                "coupure après {} écritures : contenu partiel",
+                cut
+            ),
+            Err(Fat32Error::NotFound { .. }) => {
+                // Seule possibilité : le nouveau contenu sous le nom temporaire
+                assert_eq!(fs.read_file("/conf/~ATOM000.TMP").unwrap(), new, "coupure {}", cut);
+            }
+            Err(err) => panic!("coupure après {} écritures : {:?}", cut, err),
+        }
+        assert_eq!(fs.read_file("/conf/notes.txt").unwrap(), b"voisin");
+
+        if done {
+            assert_eq!(fs.read_file("/conf/settings.cfg").unwrap(), new);
+            assert_eq!(names.len(), 4 + fillers);
+            assert!(!names.iter().any(|name| name.starts_with('~')));
+            assert!(fs.check_integrity().unwrap().is_clean());
+            break;
+        }
+        cut += 1;
+    }
+    assert!(cut > 5);
+}
+
+#[test]
+fn test_write_file_atomic_creates_and_replaces() {
+    let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(base_image(b"v1", 0))).unwrap();
+    let free = fs.free_space().unwrap();
+
+    fs.write_file_atomic("/conf/settings.cfg", b"v2").unwrap();
+    fs.write_file_atomic("/conf/new.cfg", b"cree").unwrap();
+    assert_eq!(fs.read_file("/conf/settings.cfg").unwrap(), b"v2");
+    assert_eq!(fs.read_file("/conf/new.cfg").unwrap(), b"cree");
+
+    // L'ancien contenu est libéré : un seul cluster de plus (new.cfg)
+    assert_eq!(fs.free_space().unwrap(), free - 512);
+    assert_eq!(fs.write_file_atomic("/conf", b"x"), Err(Fat32Error::InvalidPath { component: 0 }));
+}
@@ -0,0 +1,96 @@
+// Mode lot : écritures de métadonnées différées jusqu'à la fin
+mod common;
+
+use common::traced_fs;
+use fat32::devices::{GuardMode, TraceDevice, TraceOp, WriteGuard};
+use fat32::{BlockDevice, Fat32FileSystem, Fat32Error, MemoryDevice, Result};
+
+/// Secteurs écrits depuis le dernier `clear`
+fn sectors_written(fs: &Fat32FileSystem<TraceDevice<MemoryDevice>>) -> usize {
+    fs.device().summary().sectors_written as usize
+}
+
+/// 20 répertoires et 100 petits fichiers répartis entre eux
+fn provision<D: BlockDevice>(fs: &mut Fat32FileSystem<D>) -> Result<(), D::Error> {
+    for d in 0..20 {
+        fs.create_dir(&format!("/dir{}", d))?;
+    }
+    for i in 0..100 {
+        fs.write_file(&format!("/dir{}/file{}.txt", i % 20, i), format!("{}", i).as_bytes())?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_batch_reduces_metadata_writes() {
+    // 20 clusters de répertoire et 100 clusters de données, écrits dans tous les cas
+    const DATA_WRITES: usize = 120;
+
+    let mut fs = traced_fs();
+    provision(&mut fs).unwrap();
+    fs.flush().unwrap();
+    let plain = sectors_written(&fs) - DATA_WRITES;
+
+    let mut fs = traced_fs();
+    fs.batch(provision).unwrap();
+    let batched = sectors_written(&fs) - DATA_WRITES;
+
+    assert!(plain >= 10 * batched, "sans lot {} écritures, en lot {}", plain, batched);
+    assert!(!fs.is_dirty());
+    assert_eq!(fs.read_file("/dir7/file87.txt").unwrap(), b"87");
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_batch_defers_chain_growth() {
+    let mut fs = traced_fs();
+    fs.write_file("/log.bin", &[1u8; 600]).unwrap();
+    fs.write_file("/data.bin", &[2u8; 600]).unwrap();
+    fs.flush().unwrap();
+    let bs = *fs.boot_sector();
+    let fat = bs.first_fat_sector()..bs.first_data_sector();
+
+    // Chaînes allongées par reserve et overwrite_file : FAT en attente
+    fs.begin_batch();
+    fs.device_mut().clear();
+    fs.reserve("/log.bin", 5000).unwrap();
+    fs.overwrite_file("/data.bin", &[3u8; 3000]).unwrap();
+    let trace = fs.device().trace();
+    assert!(!trace.iter().any(|e| e.op == TraceOp::Write && fat.contains(&e.sector)));
+
+    fs.end_batch().unwrap();
+    assert!(fs.device().trace().iter().any(|e| fat.contains(&e.sector)));
+    assert_eq!(fs.read_file("/data.bin").unwrap(), [3u8; 3000]);
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_batch_error_commits_completed_operations() {
+    let mut fs = traced_fs();
+    let result = fs.batch(|fs| {
+        fs.write_file("/a.txt", b"a")?;
+        fs.write_file("/b.txt", b"b")?;
+        fs.write_file("/a.txt", b"again")
+    });
+    assert_eq!(result, Err(Fat32Error::AlreadyExists));
+    assert!(!fs.is_dirty());
+
+    // Remonter l'image : les deux premières créations sont sur le disque
+    let data = fs.device().inner().data().to_vec();
+    let device = WriteGuard::new(MemoryDevice::from_vec(data), GuardMode::Count);
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"a");
+    assert_eq!(fs.read_file("/b.txt").unwrap(), b"b");
+
+    // Échec de l'écriture finale : son erreur est retournée, un flush réessaie
+    fs.begin_batch();
+    fs.write_file("/c.txt", b"c").unwrap();
+    fs.device_mut().set_mode(GuardMode::Deny);
+    fs.write_file("/d.txt", b"d").unwrap_err();
+    assert_eq!(fs.end_batch(), Err(Fat32Error::ReadOnly));
+    assert!(fs.is_dirty());
+    fs.device_mut().set_mode(GuardMode::Count);
+    fs.flush().unwrap();
+    assert_eq!(fs.read_file("/c.txt").unwrap(), b"c");
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
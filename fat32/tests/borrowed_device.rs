@@ -0,0 +1,34 @@
+// Device prêté au système de fichiers par `&mut`
+mod common;
+
+use common::image_sectors;
+use fat32::devices::{TraceDevice, TraceOp};
+use fat32::{Fat32FileSystem, MemoryDevice, ReadBlockDevice};
+
+#[test]
+fn test_mount_borrowed_device() {
+    let device = TraceDevice::new(MemoryDevice::new(image_sectors(2)));
+    let mut fs = Fat32FileSystem::format(device, image_sectors(2), 2).unwrap();
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    fs.write_file("/a.bin", &data).unwrap();
+    let mut sd = fs.into_device();
+    sd.clear();
+
+    {
+        let mut fs = Fat32FileSystem::new(&mut sd).unwrap();
+        assert_eq!(fs.read_file("/a.bin").unwrap(), data);
+        fs.write_file("/b.txt", b"prete").unwrap();
+    }
+
+    // Les méthodes redéfinies passent par la référence ; un flush pour le
+    // bit « propre » effacé à la première écriture, un pour le drop
+    assert!(sd.trace().iter().any(|access| access.op == TraceOp::Read && access.len > 1));
+    assert_eq!(sd.summary().flushes, 2);
+
+    // Le device est de nouveau utilisable directement
+    let mut boot = [0u8; 512];
+    sd.read_sector(0, &mut boot).unwrap();
+    assert_eq!(&boot[510..512], &[0x55, 0xAA]);
+    let mut fs = Fat32FileSystem::new(sd).unwrap();
+    assert_eq!(fs.read_file("/b.txt").unwrap(), b"prete");
+}
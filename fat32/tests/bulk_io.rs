@@ -0,0 +1,65 @@
+// Transferts de plusieurs secteurs (read_sectors / write_sectors)
+mod common;
+
+use common::{image_sectors, PlainDevice};
+use fat32::devices::{TraceDevice, TraceOp};
+use fat32::{BlockDevice, Fat32FileSystem, Fat32Error, MemoryDevice, ReadBlockDevice};
+
+#[test]
+fn test_contiguous_reads_use_bulk_transfers() {
+    let device = TraceDevice::new(MemoryDevice::new(image_sectors(4)));
+    let mut fs = Fat32FileSystem::format(device, image_sectors(4), 4).unwrap();
+
+    // 10 clusters de 2 KiB, contigus sur un volume neuf
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 253) as u8).collect();
+    fs.device_mut().clear();
+    fs.write_file("/big.bin", &data).unwrap();
+    let writes: Vec<u32> = fs
+        .device()
+        .trace()
+        .iter()
+        .filter(|e| e.op == TraceOp::Write)
+        .map(|e| e.len)
+        .collect();
+    assert_eq!(writes, [4; 10]);
+    fs.flush().unwrap();
+    fs.clear_caches();
+
+    fs.device_mut().clear();
+    assert_eq!(fs.read_file("/big.bin").unwrap(), data);
+    // Un cluster de répertoire, le secteur de FAT (remplissage du cache),
+    // puis les 10 clusters du fichier d'un coup
+    let bs = *fs.boot_sector();
+    let data_start = bs.first_data_sector();
+    let reads: Vec<(u32, u32)> = fs.device().trace().iter().map(|e| (e.sector, e.len)).collect();
+    assert_eq!(reads, [(data_start, 4), (bs.first_fat_sector(), 1), (data_start + 4, 40)]);
+
+    // Lecture par handle : cluster partiel, puis clusters entiers groupés
+    let mut handle = fs.open_file("/big.bin").unwrap();
+    let mut buf = vec![0u8; 100];
+    fs.read_handle(&mut handle, &mut buf).unwrap();
+    fs.device_mut().clear();
+    let mut rest = vec![0u8; 20_000];
+    let mut total = 100;
+    total += fs.read_handle(&mut handle, &mut rest).unwrap();
+    assert_eq!(total, 20_000);
+    assert_eq!(&rest[..19_900], &data[100..]);
+    let lens: Vec<u32> = fs.device().trace().iter().map(|e| e.len).collect();
+    assert_eq!(lens, [4, 32, 4]);
+    assert_eq!(fs.device().summary().read_runs, 1);
+}
+
+#[test]
+fn test_default_bulk_methods() {
+    let mut device = PlainDevice(MemoryDevice::new(64));
+    let block: Vec<u8> = (0..3 * 512).map(|i| (i % 7) as u8).collect();
+    device.write_sectors(5, 3, &block).unwrap();
+
+    let mut read = vec![0u8; 3 * 512];
+    device.read_sectors(5, 3, &mut read).unwrap();
+    assert_eq!(read, block);
+    assert_eq!(&device.0.data()[5 * 512..8 * 512], &block[..]);
+
+    assert_eq!(device.read_sectors(5, 2, &mut read), Err(Fat32Error::BufferTooSmall));
+    assert_eq!(device.write_sectors(5, 4, &block), Err(Fat32Error::BufferTooSmall));
+}
@@ -0,0 +1,105 @@
+// Cache de secteurs générique devant un device
+mod common;
+
+use common::{journal, Op, IMAGE_SECTORS};
+use fat32::devices::{CacheStats, CachedDevice, Fault, FaultyDevice, TraceDevice};
+use fat32::{BlockDevice, Fat32FileSystem, Fat32Error, IoOp, MemoryDevice, ReadBlockDevice};
+
+fn cached(capacity: usize) -> CachedDevice<TraceDevice<MemoryDevice>> {
+    CachedDevice::new(TraceDevice::new(MemoryDevice::new(64)), capacity)
+}
+
+#[test]
+fn test_read_cache_and_write_back() {
+    let mut device = cached(2);
+    let mut buffer = [0u8; 512];
+
+    // Deuxième lecture servie par le cache
+    device.read_sector(1, &mut buffer).unwrap();
+    device.read_sector(1, &mut buffer).unwrap();
+    assert_eq!(journal(device.inner()), [Op::Read(1)]);
+
+    // Écriture différée, relue depuis le cache
+    device.write_sector(5, &[5; 512]).unwrap();
+    device.write_sector(3, &[3; 512]).unwrap();
+    assert_eq!(journal(device.inner()), [Op::Read(1)]);
+    device.read_sector(5, &mut buffer).unwrap();
+    assert_eq!(buffer, [5; 512]);
+
+    // Le secteur 1 est évincé sans écriture ; le 3 (dirty) est écrit en sortant
+    device.write_sector(7, &[7; 512]).unwrap();
+    assert_eq!(journal(device.inner()), [Op::Read(1), Op::Write(3)]);
+    assert_eq!(
+        device.stats(),
+        CacheStats { hits: 2, misses: 1, evictions: 2, write_backs: 1 }
+    );
+
+    // Flush : secteurs modifiés par numéro croissant, puis flush du device
+    device.write_sector(2, &[2; 512]).unwrap();
+    assert_eq!(journal(device.inner()).last(), Some(&Op::Write(5)));
+    let before = journal(device.inner()).len();
+    device.flush().unwrap();
+    assert_eq!(journal(device.inner())[before..], [Op::Write(2), Op::Write(7), Op::Flush]);
+    assert!(!device.is_dirty());
+    assert_eq!(device.stats().write_backs, 4);
+
+    let inner = device.into_inner().unwrap();
+    assert_eq!(inner.inner().data()[7 * 512], 7);
+    assert_eq!(inner.inner().data()[2 * 512], 2);
+}
+
+#[test]
+fn test_failed_write_back_keeps_line() {
+    let mut faulty = FaultyDevice::new(MemoryDevice::new(64));
+    faulty.inject(Fault::FailWrite(0));
+    let mut device = CachedDevice::new(faulty, 1);
+    device.write_sector(3, &[3; 512]).unwrap();
+
+    // L'écriture du secteur 3 évincé échoue : il reste en cache, à écrire
+    let error = Fat32Error::IoError { op: IoOp::Write, sector: 3 };
+    assert_eq!(device.write_sector(4, &[4; 512]), Err(error));
+    assert!(device.is_dirty());
+    let mut buffer = [0u8; 512];
+    device.read_sector(3, &mut buffer).unwrap();
+    assert_eq!(buffer, [3; 512]);
+    assert_eq!(device.stats().write_backs, 0);
+
+    // Nouvel essai : le secteur 3 est écrit, puis laisse la place au 4
+    device.write_sector(4, &[4; 512]).unwrap();
+    assert_eq!(device.stats().write_backs, 1);
+    let inner = device.into_inner().unwrap().into_inner();
+    assert_eq!(inner.data()[3 * 512], 3);
+    assert_eq!(inner.data()[4 * 512], 4);
+}
+
+#[test]
+fn test_bulk_transfers_and_filesystem() {
+    let mut device = cached(4);
+    device.write_sector(2, &[2; 512]).unwrap();
+
+    // Lecture groupée : le secteur en attente recouvre celui du device
+    let mut buffer = [0xFFu8; 3 * 512];
+    device.read_sectors(1, 3, &mut buffer).unwrap();
+    assert_eq!(buffer[..512], [0; 512]);
+    assert_eq!(buffer[512..1024], [2; 512]);
+
+    // Écriture groupée : directe, la copie en cache suit et n'est plus à écrire
+    device.write_sectors(2, 2, &[9; 1024]).unwrap();
+    assert!(!device.is_dirty());
+    let mut sector = [0u8; 512];
+    device.read_sector(2, &mut sector).unwrap();
+    assert_eq!(sector, [9; 512]);
+    assert_eq!(device.write_sectors(0, 2, &[0; 512]), Err(Fat32Error::BufferTooSmall));
+
+    // Système de fichiers complet derrière le cache
+    let device = CachedDevice::new(MemoryDevice::new(IMAGE_SECTORS), 32);
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    let data: Vec<u8> = (0..3000u32).map(|i| (i % 253) as u8).collect();
+    fs.create_dir("/d").unwrap();
+    fs.write_file("/d/f.bin", &data).unwrap();
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert!(device.stats().hits > 0);
+
+    let mut fs = Fat32FileSystem::new(device.into_inner().unwrap()).unwrap();
+    assert_eq!(fs.read_file("/d/f.bin").unwrap(), data);
+}
@@ -0,0 +1,105 @@
+// Outils partagés par les tests d'intégration (`mod common;` en tête de fichier)
+//
+// Les devices de test enveloppent tous un `MemoryDevice` ; chaque fichier n'en
+// utilise qu'une partie.
+#![allow(dead_code)]
+
+use std::convert::Infallible;
+
+use fat32::devices::{TraceDevice, TraceOp};
+use fat32::{
+    BlockDevice, BootSector, ComponentName, Fat32Error, Fat32FileSystem, FatCache, FatTable,
+    MemoryDevice, ReadBlockDevice, Result,
+};
+
+/// 34 MiB : au-dessus des 65525 clusters d'un secteur qu'il faut à FAT32
+pub const IMAGE_SECTORS: u32 = 69_632;
+
+/// Bit « propre » du volume tel qu'il est sur le disque, sans passer par les
+/// caches du système de fichiers
+pub fn clean_on_disk(device: &mut MemoryDevice) -> bool {
+    let bs = unsafe { BootSector::from_bytes(&device.data()[..512]) };
+    let mut cache = FatCache::new();
+    FatTable::new(device, &bs, &mut cache).is_volume_clean().unwrap()
+}
+
+/// Volume vide de `IMAGE_SECTORS`, clusters d'un secteur
+pub fn formatted_fs() -> Fat32FileSystem<MemoryDevice> {
+    formatted_fs_with(1)
+}
+
+/// Volume vide de `image_sectors(sectors_per_cluster)` secteurs, clusters de
+/// `sectors_per_cluster` secteurs
+pub fn formatted_fs_with(sectors_per_cluster: u8) -> Fat32FileSystem<MemoryDevice> {
+    let sectors = image_sectors(sectors_per_cluster);
+    let device = MemoryDevice::new(sectors);
+    Fat32FileSystem::format(device, sectors, sectors_per_cluster).unwrap()
+}
+
+/// Taille d'image assez grande pour FAT32 avec des clusters de
+/// `sectors_per_cluster` secteurs
+pub fn image_sectors(sectors_per_cluster: u8) -> u32 {
+    IMAGE_SECTORS * sectors_per_cluster as u32
+}
+
+/// Volume vide sur un `TraceDevice`, trace du formatage effacée
+pub fn traced_fs() -> Fat32FileSystem<TraceDevice<MemoryDevice>> {
+    let device = TraceDevice::new(MemoryDevice::new(IMAGE_SECTORS));
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    fs.device_mut().clear();
+    fs
+}
+
+/// `NotFound` attendu pour le composant `component` d'un chemin, nommé `name`
+pub fn not_found<E>(component: u16, name: &str) -> Fat32Error<E> {
+    Fat32Error::NotFound { component, name: ComponentName::new(name) }
+}
+
+/// Accès au device, secteur par secteur
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Read(u32),
+    Write(u32),
+    Flush,
+}
+
+/// Trace de `device` dépliée secteur par secteur (un transfert groupé donne
+/// un `Op` par secteur)
+pub fn journal<D: BlockDevice>(device: &TraceDevice<D>) -> Vec<Op> {
+    let mut journal = Vec::new();
+    for entry in device.trace() {
+        let sectors = entry.sector..entry.sector + entry.len;
+        match entry.op {
+            TraceOp::Read => journal.extend(sectors.map(Op::Read)),
+            TraceOp::Write => journal.extend(sectors.map(Op::Write)),
+            TraceOp::Flush => journal.push(Op::Flush),
+        }
+    }
+    journal
+}
+
+/// `MemoryDevice` réduit aux méthodes obligatoires : les transferts groupés et
+/// `flush` gardent l'implémentation par défaut du trait
+pub struct PlainDevice(pub MemoryDevice);
+
+impl ReadBlockDevice for PlainDevice {
+    type Error = Infallible;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
+        self.0.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.0.sector_size()
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.0.num_sectors()
+    }
+}
+
+impl BlockDevice for PlainDevice {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
+        self.0.write_sector(sector, buffer)
+    }
+}
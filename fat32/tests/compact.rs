@@ -0,0 +1,66 @@
+// Compactage des répertoires après suppressions
+mod common;
+
+use common::{formatted_fs, not_found};
+use fat32::{Fat32FileSystem, MemoryDevice};
+
+fn names(fs: &mut Fat32FileSystem<MemoryDevice>, path: &str) -> Vec<String> {
+    fs.list_dir(Some(path)).unwrap().iter().map(|e| e.short_name()).collect()
+}
+
+#[test]
+fn test_compact_dir() {
+    let mut fs = formatted_fs();
+
+    // ".", ".." et 100 fichiers : 7 clusters de 16 entrées
+    fs.create_dir("/logs").unwrap();
+    for i in 0..100 {
+        fs.write_file(&format!("/logs/log{}.txt", i), format!("{}", i).as_bytes()).unwrap();
+    }
+    fs.create_dir("/logs/sub").unwrap();
+    fs.write_file("/logs/sub/inner.txt", b"inner").unwrap();
+    for i in (0..100).filter(|i| i % 10 != 3) {
+        fs.delete_file(&format!("/logs/log{}.txt", i)).unwrap();
+    }
+    assert_eq!(fs.used_clusters_by_file("/logs").unwrap(), 7);
+
+    fs.change_dir("/logs/sub").unwrap();
+    let before = names(&mut fs, "/logs");
+    let free = fs.free_space().unwrap();
+
+    // 13 entrées vivantes : un seul cluster
+    assert_eq!(fs.compact_dir("/logs").unwrap(), 6);
+    assert_eq!(fs.used_clusters_by_file("/logs").unwrap(), 1);
+    assert_eq!(fs.free_space().unwrap(), free + 6 * 512);
+    assert_eq!(names(&mut fs, "/logs"), before);
+    assert_eq!(fs.count_entries(Some("/logs")).unwrap().deleted, 0);
+
+    // Le répertoire courant et ".." restent valides
+    assert_eq!(fs.read_file("inner.txt").unwrap(), b"inner");
+    assert_eq!(fs.read_file("../log93.txt").unwrap(), b"93");
+
+    // Le répertoire compacté grandit de nouveau normalement
+    for i in 0..5 {
+        fs.write_file(&format!("/logs/new{}.txt", i), b"n").unwrap();
+    }
+    assert_eq!(fs.used_clusters_by_file("/logs").unwrap(), 2);
+    assert_eq!(fs.compact_dir("/logs").unwrap(), 0);
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_compact_root_keeps_one_cluster() {
+    let mut fs = formatted_fs();
+    for i in 0..40 {
+        fs.write_file(&format!("/f{}.bin", i), b"x").unwrap();
+    }
+    for i in 0..40 {
+        fs.delete_file(&format!("/f{}.bin", i)).unwrap();
+    }
+
+    assert_eq!(fs.compact_dir("/").unwrap(), 2);
+    assert!(fs.list_dir(Some("/")).unwrap().is_empty());
+    fs.write_file("/again.txt", b"ok").unwrap();
+    assert_eq!(fs.read_file("/again.txt").unwrap(), b"ok");
+    assert_eq!(fs.compact_dir("/missing"), Err(not_found(0, "missing")));
+}
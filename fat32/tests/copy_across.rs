@@ -0,0 +1,42 @@
+// Copie d'un fichier entre deux images montées
+mod common;
+
+use common::{formatted_fs_with, not_found};
+use fat32::Fat32Error;
+
+#[test]
+fn test_copy_across_cluster_sizes() {
+    let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+    // 512 octets par cluster vers 4096, puis retour
+    let mut small = formatted_fs_with(1);
+    let mut large = formatted_fs_with(8);
+    small.write_file("/a.bin", &data).unwrap();
+    small.write_file("/vide.txt", b"").unwrap();
+    large.create_dir("/copies").unwrap();
+
+    small.copy_across("/a.bin", &mut large, "/copies/a.bin").unwrap();
+    assert_eq!(large.read_file("/copies/a.bin").unwrap(), data);
+    assert_eq!(large.used_clusters_by_file("/copies/a.bin").unwrap(), 3);
+
+    large.copy_across("/copies/a.bin", &mut small, "/b.bin").unwrap();
+    assert_eq!(small.read_file("/b.bin").unwrap(), data);
+
+    small.copy_across("/vide.txt", &mut large, "/vide.txt").unwrap();
+    assert_eq!(large.read_file("/vide.txt").unwrap(), b"");
+}
+
+#[test]
+fn test_copy_across_errors() {
+    let mut src = formatted_fs_with(1);
+    let mut dst = formatted_fs_with(1);
+    src.write_file("/a.txt", b"texte").unwrap();
+    dst.write_file("/a.txt", b"autre").unwrap();
+
+    let free = dst.free_space().unwrap();
+    let missing = src.copy_across("/absent.txt", &mut dst, "/b.txt");
+    assert_eq!(missing, Err(not_found(0, "absent.txt")));
+    assert_eq!(src.copy_across("/a.txt", &mut dst, "/a.txt"), Err(Fat32Error::AlreadyExists));
+    assert_eq!(dst.free_space().unwrap(), free);
+    assert_eq!(dst.read_file("/a.txt").unwrap(), b"autre");
+}
@@ -0,0 +1,142 @@
+// Diagnostic des corruptions (`Fat32Error::Corrupted`) sur des images modifiées à la main
+mod common;
+
+use fat32::{
+    CorruptionKind, EntryDefect, Fat32Error, Fat32FileSystem, FatCache, FatTable, MemoryDevice,
+};
+
+/// Volume avec /a.bin (3 clusters de 512 octets, 3 à 5)
+fn formatted_fs() -> Fat32FileSystem<MemoryDevice> {
+    let mut fs = common::formatted_fs();
+    fs.write_file("/a.bin", &[9u8; 1500]).unwrap();
+    fs.flush().unwrap();
+    fs
+}
+
+/// Réécrire l'entrée de FAT de `cluster`
+fn set_entry(fs: &mut Fat32FileSystem<MemoryDevice>, cluster: u32, value: u32) {
+    let bs = *fs.boot_sector();
+    let mut cache = FatCache::new();
+    let mut fat = FatTable::new(fs.device_mut(), &bs, &mut cache);
+    fat.write_entry(cluster, value).unwrap();
+    fat.flush().unwrap();
+    fs.clear_caches();
+}
+
+/// Modifier l'entrée de /a.bin dans la racine
+fn patch_entry(fs: &mut Fat32FileSystem<MemoryDevice>, patch: impl FnOnce(&mut [u8])) {
+    let root = fs.boot_sector().first_data_sector() as usize * 512;
+    let data = &mut fs.device_mut().data_mut()[root..root + 512];
+    let offset = data.chunks(32).position(|entry| &entry[..11] == b"A       BIN").unwrap();
+    patch(&mut data[offset * 32..offset * 32 + 32]);
+    fs.clear_caches();
+}
+
+fn corrupted(kind: CorruptionKind) -> Fat32Error {
+    Fat32Error::Corrupted(kind)
+}
+
+#[test]
+fn test_chain_cycle() {
+    let mut fs = formatted_fs();
+    set_entry(&mut fs, 5, 4);
+    assert_eq!(fs.read_file("/a.bin"), Err(corrupted(CorruptionKind::ChainCycle { at: 4 })));
+
+    // Tous les parcours de chaîne s'arrêtent au même endroit
+    let error = corrupted(CorruptionKind::ChainCycle { at: 4 });
+    assert_eq!(fs.cluster_chain_length(3), Err(error));
+    assert_eq!(fs.used_clusters_by_file("/a.bin"), Err(error));
+    assert_eq!(fs.sync_directory_entry_size("/a.bin"), Err(error));
+
+    let bs = *fs.boot_sector();
+    let mut cache = FatCache::new();
+    let mut fat = FatTable::new(fs.device_mut(), &bs, &mut cache);
+    assert_eq!(fat.extend_chain(3), Err(error));
+    assert_eq!(fat.count_used_clusters(), Ok(4));
+}
+
+#[test]
+fn test_chain_broken_and_out_of_range() {
+    let mut fs = formatted_fs();
+    set_entry(&mut fs, 4, 0);
+    assert_eq!(fs.read_file("/a.bin"), Err(corrupted(CorruptionKind::ChainBroken { at: 4 })));
+
+    let value = fs.boot_sector().num_clusters() + 2;
+    set_entry(&mut fs, 4, value);
+    let error = corrupted(CorruptionKind::ClusterOutOfRange { value });
+    assert_eq!(fs.read_file("/a.bin"), Err(error));
+}
+
+#[test]
+fn test_directory_entry_invalid() {
+    let mut fs = formatted_fs();
+    let missing = corrupted(CorruptionKind::DirectoryEntryInvalid {
+        reason: EntryDefect::MissingCluster,
+    });
+    let too_short = corrupted(CorruptionKind::DirectoryEntryInvalid {
+        reason: EntryDefect::ChainTooShort,
+    });
+
+    // Taille de 4 clusters pour une chaîne de 3
+    patch_entry(&mut fs, |entry| entry[28..32].copy_from_slice(&2000u32.to_le_bytes()));
+    assert_eq!(fs.read_file("/a.bin"), Err(too_short));
+    let mut buf = [0u8; 2000];
+    assert_eq!(fs.read_file_to_fixed_buf("/a.bin", &mut buf), Err(too_short));
+
+    // Fichier non vide sans premier cluster
+    patch_entry(&mut fs, |entry| {
+        entry[20..22].fill(0);
+        entry[26..28].fill(0);
+    });
+    assert_eq!(fs.read_file("/a.bin"), Err(missing));
+    assert_eq!(fs.read_file_to_fixed_buf("/a.bin", &mut buf), Err(missing));
+}
+
+#[test]
+fn test_directory_too_large() {
+    let mut fs = formatted_fs();
+    fs.create_dir("/big").unwrap();
+    for i in 0..14 {
+        fs.write_file(&format!("/big/f{}.txt", i), b"x").unwrap();
+    }
+    fs.flush().unwrap();
+    let dir = fs.list_dir(Some("/big")).unwrap()[0].first_cluster();
+
+    // Répertoire qui boucle sur lui-même : trop long avant d'être fini
+    set_entry(&mut fs, dir, dir);
+    let error = corrupted(CorruptionKind::DirectoryTooLarge { clusters: 4097 });
+    assert_eq!(fs.read_file("/big/none.txt"), Err(error));
+}
+
+#[test]
+fn test_strict_mount_checks() {
+    let fs = formatted_fs();
+    let bs = *fs.boot_sector();
+    let mut image = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+
+    // Copie de FAT différente, au troisième secteur
+    let second_fat = (bs.first_fat_sector() + bs.fat_size()) as usize * 512;
+    image[second_fat + 2 * 512] = 0x55;
+    let device = MemoryDevice::from_vec(image.clone());
+    let error = corrupted(CorruptionKind::FatMismatch { copy: 1, sector: 2 });
+    assert_eq!(Fat32FileSystem::new_strict(device).err(), Some(error));
+    let message = "Système de fichiers corrompu : FAT 2 différente de la FAT 1 au secteur 2";
+    assert_eq!(error.to_string(), message);
+
+    // FAT sans miroir (bit 7 de ext_flags) : copies non comparées
+    image[40] |= 0x80;
+    assert!(Fat32FileSystem::new_strict(MemoryDevice::from_vec(image.clone())).is_ok());
+    image[40] &= !0x80;
+    image[second_fat + 2 * 512] = 0;
+
+    // Compteur FSInfo très faux
+    let actual = Fat32FileSystem::new(MemoryDevice::from_vec(image.clone()))
+        .unwrap()
+        .volume_info()
+        .unwrap()
+        .free_clusters;
+    image[512 + 488..512 + 492].copy_from_slice(&7u32.to_le_bytes());
+    let device = MemoryDevice::from_vec(image);
+    let error = corrupted(CorruptionKind::FreeCountMismatch { recorded: 7, actual });
+    assert_eq!(Fat32FileSystem::new_strict(device).err(), Some(error));
+}
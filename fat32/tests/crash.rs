@@ -0,0 +1,81 @@
+// Cohérence du volume après une panne à n'importe quel moment
+mod common;
+
+use common::formatted_fs;
+use fat32::devices::{Fault, FaultyDevice};
+use fat32::{Fat32FileSystem, MemoryDevice};
+
+fn base_image() -> Vec<u8> {
+    let mut fs = formatted_fs();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/docs/a.txt", b"ancien contenu").unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap().into_inner()
+}
+
+/// Rejouer `op` en coupant le courant à chaque écriture tour à tour
+///
+/// Après chaque coupure, l'image est remontée : le montage doit réussir,
+/// aucun cluster ne doit être partagé, et `check` valide le contenu.
+fn crash_at_every_write<F, C>(make_fault: fn(usize) -> Fault, op: F, check: C)
+where
+    F: Fn(&mut Fat32FileSystem<FaultyDevice<MemoryDevice>>) -> bool,
+    C: Fn(&mut Fat32FileSystem<MemoryDevice>),
+{
+    let base = base_image();
+    for n in 0.. {
+        let mut device = FaultyDevice::new(MemoryDevice::from_vec(base.clone()));
+        device.fail_after_trigger(true);
+        device.inject(make_fault(n));
+
+        let mut fs = Fat32FileSystem::new(device).unwrap();
+        let done = op(&mut fs) && fs.flush().is_ok();
+        let device = fs.into_device();
+        let triggered = device.is_triggered();
+
+        let mut fs = Fat32FileSystem::new(device.into_inner())
+            .unwrap_or_else(|err| panic!("montage après la panne {}: {:?}", n, err));
+        let report = fs.check_integrity().unwrap();
+        assert!(report.cross_linked.is_empty(), "panne {}: {:?}", n, report);
+        assert!(report.multiply_referenced.is_empty(), "panne {}: {:?}", n, report);
+        check(&mut fs);
+
+        if !triggered {
+            assert!(done, "aucune panne mais l'opération a échoué");
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_crash_during_write_file() {
+    let data: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+    for fault in [Fault::FailWrite, Fault::TearWrite] {
+        crash_at_every_write(
+            fault,
+            |fs| fs.write_file("/docs/b.bin", &data).is_ok(),
+            |fs| {
+                // Le nouveau fichier est absent ou complet
+                if let Ok(read) = fs.read_file("/docs/b.bin") {
+                    assert_eq!(read, data);
+                }
+                assert_eq!(fs.read_file("/docs/a.txt").unwrap(), b"ancien contenu");
+            },
+        );
+    }
+}
+
+#[test]
+fn test_crash_during_write_file_atomic() {
+    for fault in [Fault::FailWrite, Fault::TearWrite] {
+        crash_at_every_write(
+            fault,
+            |fs| fs.write_file_atomic("/docs/a.txt", b"nouveau contenu").is_ok(),
+            |fs| {
+                // Jamais de contenu partiel sous le nom demandé
+                if let Ok(read) = fs.read_file("/docs/a.txt") {
+                    assert!(read == b"ancien contenu" || read == b"nouveau contenu", "{:?}", read);
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// `defmt::Format` des types publics (feature `defmt`)
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::{formatted_fs, not_found};
+use fat32::{
+    CorruptionKind, EntryDefect, ErrorKind, Fat32Error, FileAttributes, IoOp, NameError,
+};
+
+/// Octets produits par defmt (trames encodées, non décodées ici)
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+#[defmt::global_logger]
+struct CountingLogger;
+
+unsafe impl defmt::Logger for CountingLogger {
+    fn acquire() {}
+
+    unsafe fn flush() {}
+
+    unsafe fn release() {}
+
+    unsafe fn write(bytes: &[u8]) {
+        BYTES.fetch_add(bytes.len(), Ordering::SeqCst);
+    }
+}
+
+defmt::timestamp!("{=u32}", 0);
+
+/// Formater `value` et vérifier que quelque chose a été écrit
+fn logs<T: defmt::Format>(value: T) {
+    let before = BYTES.load(Ordering::SeqCst);
+    defmt::println!("{}", value);
+    assert!(BYTES.load(Ordering::SeqCst) > before);
+}
+
+#[test]
+fn test_defmt_format() {
+    let mut fs = formatted_fs();
+    fs.write_file("/a.txt", b"data").unwrap();
+    fs.create_dir("/dir").unwrap();
+
+    logs(not_found::<Fat32Error>(2, "jan"));
+    logs(Fat32Error::<u8>::Device(5));
+    logs(Fat32Error::<u8>::IoError { op: IoOp::Write, sector: 9 });
+    logs(Fat32Error::<u8>::InvalidName(NameError::IllegalChar('*')));
+    let reason = EntryDefect::ChainTooShort;
+    logs(Fat32Error::<u8>::Corrupted(CorruptionKind::DirectoryEntryInvalid { reason }));
+    logs(ErrorKind::Corruption);
+    logs(FileAttributes::DIRECTORY | FileAttributes::HIDDEN);
+    for entry in fs.list_dir(Some("/")).unwrap() {
+        logs(entry);
+    }
+    logs(*fs.boot_sector());
+    logs(fs.volume_info().unwrap());
+    logs(fs.count_entries(None).unwrap());
+    logs(fs.check_integrity().unwrap());
+}
@@ -0,0 +1,35 @@
+// Défragmentation d'un fichier
+mod common;
+
+use common::formatted_fs;
+use fat32::DefragStats;
+
+#[test]
+fn test_defragment_fragmented_file() {
+    let mut fs = formatted_fs();
+
+    // Créer des trous d'un cluster
+    for i in 0..10 {
+        fs.write_file(&format!("/f{}.bin", i), &[i as u8; 512]).unwrap();
+    }
+    for i in (0..10).step_by(2) {
+        fs.delete_file(&format!("/f{}.bin", i)).unwrap();
+    }
+
+    // Ce fichier remplit les trous : il est fragmenté
+    let data: Vec<u8> = (0..4000u32).map(|i| (i % 199) as u8).collect();
+    fs.write_file("/frag.bin", &data).unwrap();
+    let free_before = fs.free_space().unwrap();
+
+    let stats = fs.defragment_file("/frag.bin").unwrap();
+    assert_eq!(stats, DefragStats { clusters_moved: 8, contiguous: true });
+    // L'ancienne chaîne libérée est déjà sur le disque
+    assert!(!fs.is_dirty());
+
+    assert_eq!(fs.read_file("/frag.bin").unwrap(), data);
+    assert_eq!(fs.free_space().unwrap(), free_before);
+
+    // Déjà contigu : rien à faire
+    let stats = fs.defragment_file("/frag.bin").unwrap();
+    assert_eq!(stats, DefragStats { clusters_moved: 0, contiguous: true });
+}
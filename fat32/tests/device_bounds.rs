@@ -0,0 +1,104 @@
+// Taille annoncée par le device (`num_sectors`) et accès hors bornes
+mod common;
+
+use std::convert::Infallible;
+
+use common::{formatted_fs, IMAGE_SECTORS};
+use fat32::devices::TraceDevice;
+use fat32::{
+    BlockDevice, CorruptionKind, Fat32Error, Fat32FileSystem, IoOp, MemoryDevice, ReadBlockDevice,
+    Result,
+};
+
+/// Device qui annonce `limit` secteurs, accès tracés
+struct Bounded {
+    inner: TraceDevice<MemoryDevice>,
+    limit: u64,
+}
+
+impl Bounded {
+    fn new(image: MemoryDevice, limit: u64) -> Self {
+        Self { inner: TraceDevice::new(image), limit }
+    }
+}
+
+impl ReadBlockDevice for Bounded {
+    type Error = Infallible;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
+        self.inner.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        512
+    }
+
+    fn num_sectors(&self) -> u64 {
+        self.limit
+    }
+}
+
+impl BlockDevice for Bounded {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<()> {
+        self.inner.write_sector(sector, buffer)
+    }
+}
+
+fn image() -> MemoryDevice {
+    let mut fs = formatted_fs();
+    fs.write_file("/a.bin", &[7u8; 3000]).unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap()
+}
+
+#[test]
+fn test_mount_checks_device_size() {
+    assert_eq!(MemoryDevice::new(16).num_sectors(), 16);
+
+    // Volume plus grand que le device : refusé
+    let device = Bounded::new(image(), IMAGE_SECTORS as u64 - 1);
+    assert!(matches!(Fat32FileSystem::new(device), Err(Fat32Error::InvalidBootSector)));
+
+    // Device plus grand que le volume, ou taille inconnue : accepté
+    for limit in [IMAGE_SECTORS as u64 * 2, u64::MAX] {
+        let device = Bounded::new(image(), limit);
+        let mut fs = Fat32FileSystem::new(device).unwrap();
+        assert_eq!(fs.read_file("/a.bin").unwrap(), [7u8; 3000]);
+    }
+}
+
+#[test]
+fn test_mount_checks_fat_size() {
+    // FAT de 100 secteurs pour plus de 65525 clusters : refusée
+    let mut image = image();
+    image.data_mut()[36..40].copy_from_slice(&100u32.to_le_bytes());
+    let device = Bounded::new(image, IMAGE_SECTORS as u64);
+    assert!(matches!(Fat32FileSystem::new(device), Err(Fat32Error::InvalidBootSector)));
+}
+
+#[test]
+fn test_out_of_bounds_access_rejected() {
+    let device = Bounded::new(image(), IMAGE_SECTORS as u64);
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    let data_start = fs.boot_sector().first_data_sector() as u64;
+
+    // Le device rétrécit après le montage : les données ne sont plus lues
+    fs.device_mut().limit = data_start + 1;
+    fs.device_mut().inner.clear();
+    // Le fichier commence au cluster 3, juste après la racine
+    let error = Fat32Error::IoError { op: IoOp::Read, sector: data_start as u32 + 1 };
+    assert_eq!(fs.read_file("/a.bin"), Err(error));
+    assert!(fs.device().inner.trace().iter().all(|e| (e.sector as u64) <= data_start));
+    fs.device_mut().limit = IMAGE_SECTORS as u64;
+
+    // Entrée FAT corrompue qui pointe au-delà du volume
+    let first_fat = fs.boot_sector().first_fat_sector() as usize;
+    let beyond = fs.boot_sector().num_clusters() + 10;
+    let mut image = fs.into_device().inner.into_inner();
+    let fat = &mut image.data_mut()[first_fat * 512..];
+    // Cluster 2 : racine, cluster 3 : début de /a.bin
+    fat[3 * 4..3 * 4 + 4].copy_from_slice(&beyond.to_le_bytes());
+
+    let mut fs = Fat32FileSystem::new(Bounded::new(image, IMAGE_SECTORS as u64)).unwrap();
+    let error = Fat32Error::Corrupted(CorruptionKind::ClusterOutOfRange { value: beyond });
+    assert_eq!(fs.read_file("/a.bin"), Err(error));
+}
@@ -0,0 +1,112 @@
+// Erreurs propres au device, remontées telles quelles
+mod common;
+
+use common::{not_found, IMAGE_SECTORS};
+use fat32::{BlockDevice, Fat32Error, Fat32FileSystem, MemoryDevice, ReadBlockDevice, Result};
+
+/// Erreurs d'un pilote de carte SD
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SdError {
+    Crc { sector: u32 },
+    Timeout,
+}
+
+impl std::fmt::Display for SdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Crc { sector } => write!(f, "CRC faux au secteur {}", sector),
+            Self::Timeout => write!(f, "carte muette"),
+        }
+    }
+}
+
+impl core::error::Error for SdError {}
+
+/// Carte SD simulée : les secteurs de `bad` donnent une erreur CRC
+struct SdCard {
+    inner: MemoryDevice,
+    bad: Vec<u32>,
+    removed: bool,
+}
+
+impl ReadBlockDevice for SdCard {
+    type Error = SdError;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<(), SdError> {
+        if self.bad.contains(&sector) {
+            return Err(Fat32Error::Device(SdError::Crc { sector }));
+        }
+        self.inner.read_sector(sector, buffer).map_err(Fat32Error::widen)
+    }
+
+    fn sector_size(&self) -> usize {
+        512
+    }
+}
+
+impl BlockDevice for SdCard {
+    fn write_sector(&mut self, sector: u32, buffer: &[u8]) -> Result<(), SdError> {
+        if self.removed {
+            return Err(Fat32Error::Device(SdError::Timeout));
+        }
+        self.inner.write_sector(sector, buffer).map_err(Fat32Error::widen)
+    }
+}
+
+#[test]
+fn test_device_error_reaches_caller() {
+    let card = SdCard { inner: MemoryDevice::new(IMAGE_SECTORS), bad: Vec::new(), removed: false };
+    let mut fs = Fat32FileSystem::format(card, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.bin", &[1u8; 2000]).unwrap();
+    fs.flush().unwrap();
+
+    // Premier secteur de données du fichier illisible
+    let bs = fs.boot_sector();
+    let first = bs.first_data_sector() + 1;
+    fs.device_mut().bad.push(first);
+    fs.clear_caches();
+    let err = fs.read_file("/a.bin").unwrap_err();
+    assert_eq!(err, Fat32Error::Device(SdError::Crc { sector: first }));
+    assert_eq!(err.to_string(), format!("Erreur du device : Crc {{ sector: {} }}", first));
+
+    // Les erreurs du système de fichiers restent les mêmes
+    assert_eq!(fs.read_file("/absent"), Err(not_found(0, "absent")));
+
+    fs.device_mut().removed = true;
+    assert_eq!(fs.write_file("/b.bin", b"b"), Err(Fat32Error::Device(SdError::Timeout)));
+    fs.device_mut().removed = false;
+    assert!(fs.unmount().is_ok());
+}
+
+/// `?` vers une erreur générique, sans passer par `std::error::Error`
+type BoxedResult<T> = core::result::Result<T, Box<dyn core::error::Error>>;
+
+fn read_boxed(fs: &mut Fat32FileSystem<SdCard>, path: &str) -> BoxedResult<Vec<u8>> {
+    Ok(fs.read_file(path)?)
+}
+
+#[test]
+fn test_error_trait() {
+    let card = SdCard { inner: MemoryDevice::new(IMAGE_SECTORS), bad: Vec::new(), removed: false };
+    let mut fs = Fat32FileSystem::format(card, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.bin", b"a").unwrap();
+    fs.flush().unwrap();
+
+    let err = read_boxed(&mut fs, "/absent").unwrap_err();
+    assert_eq!(err.to_string(), "Composant 1 ('absent') du chemin non trouvé");
+    assert!(err.source().is_none());
+    assert_eq!(err.downcast_ref::<Fat32Error<SdError>>(), Some(&not_found(0, "absent")));
+
+    // L'erreur du device est la source, et la chaîne s'arrête là
+    let first = fs.boot_sector().first_data_sector() + 1;
+    fs.device_mut().bad.push(first);
+    fs.clear_caches();
+    let err = read_boxed(&mut fs, "/a.bin").unwrap_err();
+    let chain: Vec<String> = core::iter::successors(Some(&*err), |e| e.source())
+        .map(|e| e.to_string())
+        .collect();
+    let device = format!("Erreur du device : Crc {{ sector: {} }}", first);
+    assert_eq!(chain, [device, format!("CRC faux au secteur {}", first)]);
+    let source = err.source().unwrap().downcast_ref::<SdError>();
+    assert_eq!(source, Some(&SdError::Crc { sector: first }));
+}
@@ -0,0 +1,56 @@
+// Lectures de la FAT pendant le parcours d'un répertoire fragmenté
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::IMAGE_SECTORS;
+use fat32::devices::TraceDevice;
+use fat32::{Fat32FileSystem, FatCache, FatTable, MemoryDevice};
+
+#[test]
+fn test_fragmented_directory_fat_reads() {
+    let device = TraceDevice::new(MemoryDevice::new(IMAGE_SECTORS));
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+
+    // Un cluster de répertoire (16 entrées) tous les 130 clusters de données :
+    // chaque cluster du répertoire tombe sur un autre secteur de la FAT
+    fs.create_dir("/d").unwrap();
+    for i in 0..12 * 16 {
+        if i % 16 == 14 {
+            fs.write_file(&format!("/pad{}.bin", i), &vec![0u8; 130 * 512]).unwrap();
+        }
+        fs.write_file(&format!("/d/f{}.txt", i), b"x").unwrap();
+    }
+    fs.flush().unwrap();
+
+    // Secteurs de FAT couverts par la racine et par le répertoire
+    let bs = *fs.boot_sector();
+    let root = fs.list_dir(None).unwrap();
+    let dir = root.iter().find(|e| e.short_name() == "D").unwrap().first_cluster();
+    let mut cache = FatCache::new();
+    let mut fat = FatTable::new(fs.device_mut(), &bs, &mut cache);
+    let root_chain = fat.cluster_chain(bs.root_cluster()).unwrap();
+    let dir_chain = fat.cluster_chain(dir).unwrap();
+    assert!(dir_chain.len() >= 12);
+    let fat_sectors: BTreeSet<u32> = root_chain
+        .iter()
+        .chain(&dir_chain)
+        .map(|&c| bs.first_fat_sector() + c * 4 / 512)
+        .collect();
+    assert!(fat_sectors.len() > 8, "répertoire pas assez fragmenté");
+
+    // Chaque secteur de FAT n'est lu qu'une fois
+    fs.clear_caches();
+    fs.device_mut().clear();
+    assert_eq!(fs.list_dir(Some("/d")).unwrap().len(), 12 * 16 + 2);
+    let fat_range = bs.first_fat_sector()..bs.first_data_sector();
+    let fat_reads: Vec<u32> = fs
+        .device()
+        .sectors_read()
+        .into_iter()
+        .filter(|s| fat_range.contains(s))
+        .collect();
+    let distinct: BTreeSet<u32> = fat_reads.iter().copied().collect();
+    assert_eq!(fat_reads.len(), distinct.len(), "{:?}", fat_reads);
+    assert!(distinct.is_subset(&fat_sectors));
+}
@@ -0,0 +1,63 @@
+// Comptage des entrées et limite de taille des répertoires
+mod common;
+
+use common::formatted_fs;
+use fat32::{
+    CorruptionKind, DirCounts, Fat32Error, FatCache, FatTable,
+};
+
+#[test]
+fn test_count_entries() {
+    let mut fs = formatted_fs();
+
+    // ".", "..", 30 fichiers et 2 dossiers : 34 entrées sur 3 clusters de 16
+    fs.create_dir("/big").unwrap();
+    for i in 0..30 {
+        fs.write_file(&format!("/big/f{}.txt", i), b"x").unwrap();
+    }
+    fs.create_dir("/big/sub1").unwrap();
+    fs.create_dir("/big/sub2").unwrap();
+    for i in 0..4 {
+        fs.delete_file(&format!("/big/f{}.txt", i * 5)).unwrap();
+    }
+
+    let counts = fs.count_entries(Some("/big")).unwrap();
+    assert_eq!(
+        counts,
+        DirCounts { files: 26, directories: 2, deleted: 4, free: 14 }
+    );
+
+    fs.change_dir("/big/sub1").unwrap();
+    let counts = fs.count_entries(None).unwrap();
+    assert_eq!(counts, DirCounts { files: 0, directories: 0, deleted: 0, free: 14 });
+}
+
+#[test]
+fn test_directory_loop_is_corruption() {
+    let mut fs = formatted_fs();
+    fs.create_dir("/loop").unwrap();
+    for i in 0..14 {
+        fs.write_file(&format!("/loop/f{}.txt", i), b"x").unwrap();
+    }
+    let dir = fs.list_dir(Some("/loop")).unwrap()[0].first_cluster();
+
+    // Le cluster plein du répertoire pointe sur lui-même
+    fs.flush().unwrap();
+    let bs = *fs.boot_sector();
+    {
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(fs.device_mut(), &bs, &mut cache);
+        fat.write_entry(dir, dir).unwrap();
+        fat.flush().unwrap();
+    }
+    fs.clear_caches();
+
+    // Chaîne entière (bouclée) ou parcours cluster par cluster (trop long)
+    let cycle = Fat32Error::Corrupted(CorruptionKind::ChainCycle { at: dir });
+    assert_eq!(fs.list_dir(Some("/loop")), Err(cycle));
+    // 65 536 entrées de 32 octets : 4096 clusters de 512 octets au plus
+    let too_large = Fat32Error::Corrupted(CorruptionKind::DirectoryTooLarge { clusters: 4097 });
+    assert_eq!(fs.count_entries(Some("/loop")), Err(too_large));
+    assert_eq!(fs.read_file("/loop/none.txt"), Err(too_large));
+    assert_eq!(fs.list_dir_page(Some("/loop"), 0, 1).unwrap().0.len(), 1);
+}
@@ -0,0 +1,67 @@
+// Système de fichiers sur un device choisi à l'exécution (`Box<dyn BlockDevice>`)
+mod common;
+
+use std::convert::Infallible;
+
+use common::IMAGE_SECTORS;
+use fat32::devices::{CachedDevice, GuardMode, WriteGuard};
+use fat32::{BlockDevice, Fat32Error, Fat32FileSystem, MemoryDevice};
+
+type DynDevice<'a> = Box<dyn BlockDevice<Error = Infallible> + 'a>;
+
+/// Backend choisi par un indice, comme par une option de la ligne de commande
+fn pick(backend: usize, ram: &mut MemoryDevice) -> DynDevice<'_> {
+    match backend {
+        0 => Box::new(ram),
+        1 => Box::new(CachedDevice::new(ram, 16)),
+        _ => Box::new(WriteGuard::new(ram, GuardMode::Count)),
+    }
+}
+
+/// Mêmes opérations que sur un device concret
+fn exercise<D: BlockDevice>(fs: &mut Fat32FileSystem<D>) {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/docs/a.bin", &data).unwrap();
+    fs.write_file("/docs/b.txt", b"bonjour").unwrap();
+    fs.write_file("/c.txt", b"temporaire").unwrap();
+    fs.delete_file("/c.txt").unwrap();
+    fs.flush().unwrap();
+
+    assert_eq!(fs.read_file("/docs/a.bin").unwrap(), data);
+    assert_eq!(fs.read_to_string("/docs/b.txt").unwrap(), "bonjour");
+    assert!(matches!(fs.read_file("/c.txt"), Err(Fat32Error::NotFound { .. })));
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_dyn_device_matches_concrete() {
+    let blank = MemoryDevice::new(IMAGE_SECTORS);
+    let mut expected = Fat32FileSystem::format(blank, IMAGE_SECTORS, 1).unwrap();
+    exercise(&mut expected);
+    let expected = expected.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+
+    for backend in 0..3 {
+        let mut ram = MemoryDevice::new(IMAGE_SECTORS);
+        let mut fs = Fat32FileSystem::format(pick(backend, &mut ram), IMAGE_SECTORS, 1).unwrap();
+        exercise(&mut fs);
+        drop(fs.unmount().map_err(|(_, err)| err).unwrap());
+
+        // Octet pour octet, quel que soit le backend
+        assert_eq!(ram.data(), expected, "backend {}", backend);
+        let mut fs = Fat32FileSystem::new(pick(backend, &mut ram)).unwrap();
+        assert_eq!(fs.read_to_string("/docs/b.txt").unwrap(), "bonjour");
+    }
+}
+
+#[test]
+fn test_dyn_device_owned() {
+    let device: DynDevice<'static> = Box::new(MemoryDevice::new(IMAGE_SECTORS));
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.txt", b"dyn").unwrap();
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert_eq!(device.sector_size(), 512);
+
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"dyn");
+}
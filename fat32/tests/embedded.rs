@@ -0,0 +1,110 @@
+// Adaptateurs embedded-storage / embedded-io (features `embedded-storage` et `embedded-io`)
+mod common;
+
+use common::IMAGE_SECTORS;
+use embedded_io::{Error, ErrorKind, Read, Seek, SeekFrom};
+use embedded_storage::{ReadStorage, Storage};
+use fat32::devices::StorageDevice;
+use fat32::{BlockDevice, Fat32FileSystem, Fat32Error, IoOp, FileReader, ReadBlockDevice};
+
+/// Mémoire en RAM qui note chaque écriture (offset, longueur)
+struct RamStorage {
+    data: Vec<u8>,
+    writes: Vec<(u32, usize)>,
+}
+
+impl RamStorage {
+    fn new(len: usize) -> Self {
+        Self { data: vec![0; len], writes: Vec::new() }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct OutOfRange;
+
+impl ReadStorage for RamStorage {
+    type Error = OutOfRange;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), OutOfRange> {
+        let range = offset as usize..offset as usize + bytes.len();
+        bytes.copy_from_slice(self.data.get(range).ok_or(OutOfRange)?);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl Storage for RamStorage {
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), OutOfRange> {
+        let range = offset as usize..offset as usize + bytes.len();
+        self.data.get_mut(range).ok_or(OutOfRange)?.copy_from_slice(bytes);
+        self.writes.push((offset, bytes.len()));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_storage_device_erase_blocks() {
+    let storage = RamStorage::new(IMAGE_SECTORS as usize * 512);
+    let device = StorageDevice::with_erase_size(storage, 512, 4096);
+    assert_eq!(device.sector_count(), IMAGE_SECTORS);
+
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.bin", &data).unwrap();
+    let mut device = fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    // Toutes les écritures couvrent des blocs d'effacement entiers
+    let writes = &device.storage().writes;
+    assert!(!writes.is_empty());
+    assert!(writes.iter().all(|&(offset, len)| offset % 4096 == 0 && len % 4096 == 0));
+
+    // Un secteur écrit seul ne touche pas ses voisins du même bloc
+    device.write_sector(9, &[0xAA; 512]).unwrap();
+    let mut sector = [0u8; 512];
+    device.read_sector(8, &mut sector).unwrap();
+    assert_eq!(sector, [0; 512]);
+    device.read_sector(9, &mut sector).unwrap();
+    assert_eq!(sector, [0xAA; 512]);
+
+    // Au-delà de la fin : erreur du device avant d'appeler la mémoire
+    let io = |op, sector| Err(Fat32Error::IoError { op, sector });
+    assert_eq!(device.read_sector(IMAGE_SECTORS, &mut sector), io(IoOp::Read, IMAGE_SECTORS));
+    assert_eq!(device.write_sector(IMAGE_SECTORS, &sector), io(IoOp::Write, IMAGE_SECTORS));
+
+    let mut fs = Fat32FileSystem::new(StorageDevice::new(device.into_inner())).unwrap();
+    assert_eq!(fs.read_file("/a.bin").unwrap(), data);
+}
+
+#[test]
+fn test_file_reader_read_and_seek() {
+    let data: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+    let device = StorageDevice::new(RamStorage::new(IMAGE_SECTORS as usize * 512));
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.bin", &data).unwrap();
+
+    let file = fs.open_file("/a.bin").unwrap();
+    let mut reader = FileReader::new(&mut fs, file);
+
+    let mut head = [0u8; 700];
+    reader.read_exact(&mut head).unwrap();
+    assert_eq!(head[..], data[..700]);
+
+    assert_eq!(reader.seek(SeekFrom::End(-10)).unwrap(), 2990);
+    let mut tail = [0u8; 64];
+    assert_eq!(reader.read(&mut tail).unwrap(), 10);
+    assert_eq!(tail[..10], data[2990..]);
+    assert_eq!(reader.read(&mut tail).unwrap(), 0);
+
+    // Bornée à la fin du fichier, refusée avant le début
+    assert_eq!(reader.seek(SeekFrom::Start(10_000)).unwrap(), 3000);
+    assert_eq!(reader.seek(SeekFrom::Current(-3000)).unwrap(), 0);
+    let err = reader.seek(SeekFrom::Current(-1)).unwrap_err();
+    assert_eq!(Error::kind(&err), ErrorKind::InvalidInput);
+    assert_eq!(reader.into_inner().position(), 0);
+
+    let err = fs.open_file("/absent.bin").unwrap_err();
+    assert_eq!(Error::kind(&err), ErrorKind::NotFound);
+}
@@ -0,0 +1,41 @@
+// Catégorie (`ErrorKind`) de chaque variante de `Fat32Error`
+use std::io;
+
+use fat32::{ComponentName, CorruptionKind, ErrorKind, Fat32Error, IoOp, NameError};
+
+#[test]
+fn test_error_kind_of_every_variant() {
+    let name = ComponentName::new("absent");
+    let cases: [(Fat32Error<io::ErrorKind>, ErrorKind); 22] = [
+        (Fat32Error::NotFound { component: 1, name }, ErrorKind::NotFound),
+        (Fat32Error::InvalidPath { component: 0 }, ErrorKind::InvalidInput),
+        (Fat32Error::NotADirectory, ErrorKind::InvalidInput),
+        (Fat32Error::InvalidCluster, ErrorKind::InvalidInput),
+        (Fat32Error::EndOfChain, ErrorKind::InvalidInput),
+        (Fat32Error::BufferTooSmall, ErrorKind::InvalidInput),
+        (Fat32Error::AlreadyExists, ErrorKind::InvalidInput),
+        (Fat32Error::NameTooLong, ErrorKind::InvalidInput),
+        (Fat32Error::InvalidName(NameError::TooLong), ErrorKind::InvalidInput),
+        (Fat32Error::InvalidUtf8, ErrorKind::InvalidInput),
+        (Fat32Error::InvalidInput, ErrorKind::InvalidInput),
+        (Fat32Error::DirectoryNotEmpty, ErrorKind::InvalidInput),
+        (Fat32Error::SectorSizeMismatch { device: 4096, volume: 512 }, ErrorKind::InvalidInput),
+        (Fat32Error::InvalidBootSector, ErrorKind::Corruption),
+        (Fat32Error::InvalidPartition, ErrorKind::Corruption),
+        (Fat32Error::InvalidEntry, ErrorKind::Corruption),
+        (Fat32Error::Corrupted(CorruptionKind::ChainBroken { at: 3 }), ErrorKind::Corruption),
+        (Fat32Error::IoError { op: IoOp::Write, sector: 7 }, ErrorKind::Io),
+        (Fat32Error::Device(io::ErrorKind::Other), ErrorKind::Io),
+        (Fat32Error::DiskFull, ErrorKind::NoSpace),
+        (Fat32Error::ReadOnly, ErrorKind::ReadOnly),
+        (Fat32Error::ReadOnlyFilesystem, ErrorKind::ReadOnly),
+    ];
+
+    for (error, kind) in cases {
+        assert_eq!(error.kind(), kind, "{:?}", error);
+        assert_eq!(error.is_not_found(), kind == ErrorKind::NotFound);
+        assert_eq!(error.is_invalid_input(), kind == ErrorKind::InvalidInput);
+        assert_eq!(error.is_corruption(), kind == ErrorKind::Corruption);
+        assert_eq!(error.is_io(), kind == ErrorKind::Io);
+    }
+}
@@ -0,0 +1,87 @@
+// Fat32Error comme erreur standard
+use core::convert::Infallible;
+use core::error::Error;
+
+use fat32::{Fat32Error, IoOp, NameError};
+
+fn is_error<T: Error + Send + Sync + 'static>() {}
+
+#[test]
+fn test_error_trait() {
+    is_error::<Fat32Error>();
+    is_error::<Fat32Error<Infallible>>();
+    #[cfg(feature = "std")]
+    is_error::<Fat32Error<fat32::IoErrorKind>>();
+
+    // Dans un `Box<dyn Error>`, avec `?`
+    fn fails() -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err(Fat32Error::<Infallible>::IoError { op: IoOp::Read, sector: 9 })?;
+        Ok(())
+    }
+    let error = fails().unwrap_err();
+    assert_eq!(error.to_string(), "Erreur d'entrée/sortie (lecture du secteur 9)");
+    assert!(error.source().is_none());
+    let error = error.downcast::<Fat32Error>().unwrap();
+    assert_eq!(*error, Fat32Error::IoError { op: IoOp::Read, sector: 9 });
+    assert_eq!(Fat32Error::<u8>::from(NameError::TooLong), Fat32Error::NameTooLong);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_io_error() {
+    fn write(out: &mut impl std::io::Write) -> fat32::Result<(), fat32::IoErrorKind> {
+        out.write_all(b"x")?;
+        Ok(())
+    }
+    let mut full = [0u8; 0];
+    let error = write(&mut &mut full[..]).unwrap_err();
+    assert_eq!(error, Fat32Error::Device(fat32::IoErrorKind(std::io::ErrorKind::WriteZero)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_io_error_round_trip() {
+    use fat32::{ComponentName, CorruptionKind, IoErrorKind};
+    use std::io;
+
+    // Vers io::Error : catégorie, erreur d'origine gardée, puis retour
+    let name = ComponentName::new("jan");
+    let cases: [(Fat32Error<IoErrorKind>, io::ErrorKind); 8] = [
+        (Fat32Error::NotFound { component: 2, name }, io::ErrorKind::NotFound),
+        (Fat32Error::NameTooLong, io::ErrorKind::InvalidInput),
+        (Fat32Error::Corrupted(CorruptionKind::ChainCycle { at: 5 }), io::ErrorKind::InvalidData),
+        (Fat32Error::InvalidBootSector, io::ErrorKind::InvalidData),
+        (Fat32Error::ReadOnlyFilesystem, io::ErrorKind::PermissionDenied),
+        (Fat32Error::DiskFull, io::ErrorKind::StorageFull),
+        (Fat32Error::IoError { op: IoOp::Write, sector: 3 }, io::ErrorKind::Other),
+        (Fat32Error::Device(IoErrorKind(io::ErrorKind::TimedOut)), io::ErrorKind::TimedOut),
+    ];
+    for (error, kind) in cases {
+        let converted = io::Error::from(error);
+        assert_eq!(converted.kind(), kind);
+        assert_eq!(converted.to_string(), error.to_string());
+        assert_eq!(converted.get_ref().unwrap().downcast_ref(), Some(&error));
+        assert_eq!(Fat32Error::from(converted), error);
+    }
+
+    // Sans erreur de device : rendue élargie
+    let converted = io::Error::from(Fat32Error::<Infallible>::ReadOnly);
+    assert_eq!(converted.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(Fat32Error::<IoErrorKind>::from(converted), Fat32Error::ReadOnly);
+
+    // io::Error de l'hôte : NotFound reconnu, les autres gardés dans Device
+    let missing = Fat32Error::<IoErrorKind>::from(io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(missing, Fat32Error::NotFound { component: 0, name: ComponentName::new("") });
+    assert!(missing.is_not_found());
+    let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "fin");
+    let device = Fat32Error::Device(IoErrorKind(io::ErrorKind::UnexpectedEof));
+    assert_eq!(Fat32Error::from(eof), device);
+
+    // Pendant un accès à un secteur : fin prématurée = hors limites
+    let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "fin");
+    let error = Fat32Error::from_io_at(eof, IoOp::Read, 12);
+    assert_eq!(error, Fat32Error::IoError { op: IoOp::Read, sector: 12 });
+    let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+    let error = Fat32Error::from_io_at(denied, IoOp::Write, 12);
+    assert_eq!(error, Fat32Error::Device(IoErrorKind(io::ErrorKind::PermissionDenied)));
+}
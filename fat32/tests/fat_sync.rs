@@ -0,0 +1,72 @@
+// Synchronisation FAT1 / FAT2 après chaque écriture
+mod common;
+
+use common::{journal, Op, IMAGE_SECTORS};
+use fat32::devices::TraceDevice;
+use fat32::{BootSector, Fat32FileSystem, MemoryDevice};
+
+/// Vérifier que les deux FATs sont identiques et que chaque écriture
+/// de la FAT2 a été précédée de l'écriture correspondante de la FAT1
+fn check_fats(fs: &mut Fat32FileSystem<TraceDevice<MemoryDevice>>) {
+    fs.flush().unwrap();
+    let data = fs.device().inner().data();
+    let bs = unsafe { BootSector::from_bytes(&data[0..512]) };
+    assert_eq!(bs.num_fats, 2);
+
+    let fat1 = bs.first_fat_sector();
+    let fat2 = fat1 + bs.fat_size();
+
+    // Comparaison secteur par secteur
+    for i in 0..bs.fat_size() {
+        let s1 = (fat1 + i) as usize * 512;
+        let s2 = (fat2 + i) as usize * 512;
+        assert_eq!(
+            data[s1..s1 + 512],
+            data[s2..s2 + 512],
+            "FAT1 et FAT2 diffèrent au secteur {}",
+            i
+        );
+    }
+
+    // Ordre des écritures
+    let mut pending = vec![false; bs.fat_size() as usize];
+    for op in journal(fs.device()) {
+        let Op::Write(sector) = op else { continue };
+        if (fat1..fat2).contains(&sector) {
+            pending[(sector - fat1) as usize] = true;
+        } else if (fat2..fat2 + bs.fat_size()).contains(&sector) {
+            let index = (sector - fat2) as usize;
+            assert!(pending[index], "FAT2 écrite avant FAT1 au secteur {}", index);
+            pending[index] = false;
+        }
+    }
+    fs.device_mut().clear();
+}
+
+#[test]
+fn test_fats_stay_in_sync() {
+    let device = TraceDevice::new(MemoryDevice::new(IMAGE_SECTORS));
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    check_fats(&mut fs);
+
+    // Assez de fichiers pour agrandir le répertoire racine
+    for i in 0..20 {
+        let name = format!("/file{}.bin", i);
+        let data = vec![i as u8; 300 * (i + 1)];
+        fs.write_file(&name, &data).unwrap();
+        check_fats(&mut fs);
+    }
+
+    // Supprimer un fichier sur deux
+    for i in (0..20).step_by(2) {
+        fs.delete_file(&format!("/file{}.bin", i)).unwrap();
+        check_fats(&mut fs);
+    }
+
+    // Réutiliser les clusters libérés
+    for i in 0..5 {
+        let data = vec![0xA5; 2000 * (i + 1)];
+        fs.write_file(&format!("/new{}.bin", i), &data).unwrap();
+        check_fats(&mut fs);
+    }
+}
@@ -0,0 +1,45 @@
+// Refus des volumes FAT12 et FAT16 au montage
+mod common;
+
+use common::formatted_fs;
+use fat32::{Fat32Error, Fat32FileSystem, MemoryDevice};
+
+/// Volume formaté, au boot sector modifié par `patch`
+fn patched(patch: impl Fn(&mut [u8])) -> MemoryDevice {
+    let fs = formatted_fs();
+    let mut device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    patch(&mut device.data_mut()[..512]);
+    device
+}
+
+#[test]
+fn test_mount_rejects_fat16() {
+    let fs = Fat32FileSystem::new(patched(|_| {})).unwrap();
+    assert!(fs.boot_sector().is_fat32());
+
+    // BPB FAT32, mais moins de 65525 clusters : FAT16 d'après la
+    // spécification, refusé
+    let clusters = fs.boot_sector().num_clusters();
+    let total = fs.boot_sector().total_sectors() - (clusters - 65_524);
+    let small = patched(|bs| bs[32..36].copy_from_slice(&total.to_le_bytes()));
+    let result = Fat32FileSystem::new(small);
+    assert!(matches!(result, Err(Fat32Error::InvalidBootSector)));
+
+    // Racine fixe de 512 entrées ou FAT 16 bits : FAT16, refusé
+    let root_entries = patched(|bs| bs[17..19].copy_from_slice(&512u16.to_le_bytes()));
+    let result = Fat32FileSystem::new(root_entries);
+    assert!(matches!(result, Err(Fat32Error::InvalidBootSector)));
+    let fat_size_16 = patched(|bs| bs[22..24].copy_from_slice(&32u16.to_le_bytes()));
+    let result = Fat32FileSystem::new(fat_size_16);
+    assert!(matches!(result, Err(Fat32Error::InvalidBootSector)));
+}
+
+#[test]
+fn test_mount_rejects_bad_cluster_size() {
+    // 0 secteur par cluster (division par zéro) ou pas une puissance de deux
+    for sectors_per_cluster in [0u8, 3] {
+        let device = patched(|bs| bs[13] = sectors_per_cluster);
+        let result = Fat32FileSystem::new(device);
+        assert!(matches!(result, Err(Fat32Error::InvalidBootSector)), "{}", sectors_per_cluster);
+    }
+}
@@ -0,0 +1,159 @@
+// Image dans un fichier de l'hôte (feature `std`)
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use fat32::devices::FileDevice;
+use fat32::{
+    BlockDevice, BootSector, Fat32FileSystem, Fat32Error, IoOp, MemoryDevice, ReadBlockDevice,
+};
+
+const IMAGE_SECTORS: u32 = 69_632; // 34 MiB, au-dessus du minimum FAT32
+
+/// Fichier temporaire propre à un test
+fn temp_image(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("fat32-{}-{}.img", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_create_format_and_reopen() {
+    let path = temp_image("device");
+    let device = FileDevice::create(&path, IMAGE_SECTORS as u64 * 512).unwrap();
+    assert_eq!(device.sector_count(), IMAGE_SECTORS);
+    assert_eq!(fs::metadata(&path).unwrap().len(), IMAGE_SECTORS as u64 * 512);
+
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.bin", &data).unwrap();
+    let mut device = fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    // Accès hors de l'image : erreur, le fichier ne grandit pas
+    let mut sector = [0u8; 512];
+    let io = |op, sector| Err(Fat32Error::IoError { op, sector });
+    assert_eq!(device.read_sector(IMAGE_SECTORS, &mut sector), io(IoOp::Read, IMAGE_SECTORS));
+    assert_eq!(device.write_sector(IMAGE_SECTORS, &sector), io(IoOp::Write, IMAGE_SECTORS));
+    let mut two = [0u8; 1024];
+    assert_eq!(device.read_sectors(0, 1, &mut two), Err(Fat32Error::BufferTooSmall));
+    drop(device);
+    assert_eq!(fs::metadata(&path).unwrap().len(), IMAGE_SECTORS as u64 * 512);
+
+    let mut fs = Fat32FileSystem::new(FileDevice::open(&path).unwrap()).unwrap();
+    assert_eq!(fs.read_file("/a.bin").unwrap(), data);
+    drop(fs);
+
+    // Secteurs de 4096 octets : 34 MiB = 8704 secteurs
+    let device = FileDevice::open_with_sector_size(&path, 4096).unwrap();
+    assert_eq!(device.sector_count(), 8704);
+    assert_eq!(device.sector_size(), 4096);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_open_rejects_partial_sector() {
+    let path = temp_image("partial");
+    fs::write(&path, vec![0u8; 512 * 3 + 100]).unwrap();
+
+    let err = FileDevice::open(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("1636"), "{}", err);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_create_checks() {
+    let path = temp_image("create");
+    let size = IMAGE_SECTORS as u64 * 512;
+
+    // Tailles refusées avant de créer le fichier
+    let err = FileDevice::create(&path, size + 100).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = FileDevice::create(&path, 4 * 1024 * 1024).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(!path.exists());
+
+    // Pas d'écrasement sans le demander
+    fs::write(&path, b"precieux").unwrap();
+    let err = FileDevice::create(&path, size).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read(&path).unwrap(), b"precieux");
+
+    let device = FileDevice::create_overwrite(&path, size).unwrap();
+    assert_eq!(device.sector_count(), IMAGE_SECTORS);
+    assert_eq!(fs::metadata(&path).unwrap().len(), size);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_create_minimum_size() {
+    // 32 secteurs réservés, deux FATs de 512 secteurs, 65525 clusters
+    const MIN_SECTORS: u32 = 66_581;
+    let path = temp_image("minimum");
+
+    let err = FileDevice::create(&path, (MIN_SECTORS as u64 - 1) * 512).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains(&(MIN_SECTORS as u64 * 512).to_string()), "{}", err);
+
+    let device = FileDevice::create(&path, MIN_SECTORS as u64 * 512).unwrap();
+    let fs = Fat32FileSystem::format(device, MIN_SECTORS, 1).unwrap();
+    let bs = *fs.boot_sector();
+    assert_eq!(bs.fat_size(), 512);
+    assert_eq!(bs.num_clusters(), 65_525);
+    assert!(bs.is_fat32());
+    drop(fs);
+
+    let fs = Fat32FileSystem::new(FileDevice::open(&path).unwrap()).unwrap();
+    assert!(fs.boot_sector().is_fat32());
+    drop(fs);
+    let _ = fs::remove_file(&path);
+
+    // Un secteur de moins ne suffit plus pour FAT32 : refusé par le formatage
+    let mut device = MemoryDevice::new(MIN_SECTORS - 1);
+    let result = BootSector::format(&mut device, MIN_SECTORS - 1, 1);
+    assert_eq!(result.err(), Some(Fat32Error::InvalidBootSector));
+}
+
+#[test]
+fn test_read_only_image() {
+    let path = temp_image("readonly");
+    let device = FileDevice::create(&path, IMAGE_SECTORS as u64 * 512).unwrap();
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    fs.write_file("/a.txt", b"lecture").unwrap();
+    drop(fs.unmount().map_err(|(_, err)| err).unwrap());
+    let before = fs::read(&path).unwrap();
+
+    // Les lectures passent, les écritures sont refusées proprement
+    let device = FileDevice::open_read_only(&path).unwrap();
+    assert!(device.is_read_only());
+    let mut fs = Fat32FileSystem::with_readonly(device).unwrap();
+    assert_eq!(fs.list_dir(None).unwrap().len(), 1);
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"lecture");
+    assert_eq!(fs.write_file("/b.txt", b"x"), Err(Fat32Error::ReadOnlyFilesystem));
+    let mut device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert_eq!(device.write_sector(0, &[0; 512]), Err(Fat32Error::ReadOnly));
+    drop(device);
+    assert_eq!(fs::read(&path).unwrap(), before);
+
+    // Fichier sans droit d'écriture : `open` se replie sur la lecture seule
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o444)).unwrap();
+        // Inutile en root : les droits ne sont pas vérifiés
+        if fs::File::options().write(true).open(&path).is_err() {
+            let device = FileDevice::open(&path).unwrap();
+            assert!(device.is_read_only());
+            let mut fs = Fat32FileSystem::with_readonly(device).unwrap();
+            assert_eq!(fs.read_file("/a.txt").unwrap(), b"lecture");
+        }
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+    }
+    assert!(!FileDevice::open(&path).unwrap().is_read_only());
+
+    let _ = fs::remove_file(&path);
+}
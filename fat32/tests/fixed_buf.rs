@@ -0,0 +1,32 @@
+// Lecture d'un fichier dans un tampon fourni par l'appelant
+mod common;
+
+use common::{formatted_fs_with, not_found};
+use fat32::Fat32Error;
+
+#[test]
+fn test_read_file_to_fixed_buf() {
+    let mut fs = formatted_fs_with(2);
+
+    // Clusters de 1024 octets : deux entiers, puis un secteur et 40 octets
+    let data: Vec<u8> = (0..2600u32).map(|i| (i % 251) as u8).collect();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/docs/a.bin", &data).unwrap();
+    fs.write_file("/vide.txt", b"").unwrap();
+
+    let mut buf = [0xAAu8; 4096];
+    assert_eq!(fs.read_file_to_fixed_buf("/docs/a.bin", &mut buf), Ok(2600));
+    assert_eq!(&buf[..2600], &data[..]);
+    // Rien n'est écrit après la fin du fichier
+    assert!(buf[2600..].iter().all(|&b| b == 0xAA));
+
+    let mut exact = [0u8; 2600];
+    assert_eq!(fs.read_file_to_fixed_buf("/docs/a.bin", &mut exact), Ok(2600));
+    assert_eq!(&exact[..], &data[..]);
+
+    let mut small = [0u8; 2599];
+    assert_eq!(fs.read_file_to_fixed_buf("/docs/a.bin", &mut small), Err(Fat32Error::BufferTooSmall));
+    assert_eq!(fs.read_file_to_fixed_buf("/vide.txt", &mut []), Ok(0));
+    assert_eq!(fs.read_file_to_fixed_buf("/docs", &mut buf), Err(not_found(0, "docs")));
+    assert_eq!(fs.read_file_to_fixed_buf("/absent", &mut buf), Err(not_found(0, "absent")));
+}
@@ -0,0 +1,48 @@
+// Arborescence complète en une liste plate
+mod common;
+
+use common::{formatted_fs_with, not_found};
+
+#[test]
+fn test_list_dir_recursive_to_flat() {
+    let mut fs = formatted_fs_with(2);
+    fs.create_dir("/docs").unwrap();
+    fs.create_dir("/docs/vide").unwrap();
+    fs.create_dir("/photos").unwrap();
+    fs.write_file("/z.txt", b"z").unwrap();
+    fs.write_file("/docs/b.txt", b"bb").unwrap();
+    fs.write_file("/docs/a.txt", b"a").unwrap();
+    fs.write_file("/photos/img.jpg", &[0u8; 3000]).unwrap();
+
+    let flat = fs.list_dir_recursive_to_flat(None).unwrap();
+    let paths: Vec<&str> = flat.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/DOCS/",
+            "/DOCS/A.TXT",
+            "/DOCS/B.TXT",
+            "/DOCS/VIDE/",
+            "/PHOTOS/",
+            "/PHOTOS/IMG.JPG",
+            "/Z.TXT",
+        ]
+    );
+    let (_, img) = &flat[5];
+    assert_eq!(img.file_size(), 3000);
+    assert!(flat[0].1.attributes().is_directory());
+
+    // Sous-arbre, chemins toujours absolus, aussi depuis le répertoire courant
+    let docs = fs.list_dir_recursive_to_flat(Some("/docs")).unwrap();
+    assert_eq!(docs.len(), 3);
+    assert_eq!(docs[0].0, "/DOCS/A.TXT");
+    fs.change_dir("/docs").unwrap();
+    assert_eq!(fs.list_dir_recursive_to_flat(None).unwrap(), docs);
+    assert_eq!(fs.list_dir_recursive_to_flat(Some("vide")).unwrap(), []);
+    fs.change_dir("vide").unwrap();
+    assert_eq!(fs.list_dir_recursive_to_flat(Some("..")).unwrap(), docs);
+    assert_eq!(fs.list_dir_recursive_to_flat(Some("../..")).unwrap(), flat);
+
+    let missing = fs.list_dir_recursive_to_flat(Some("/absent"));
+    assert_eq!(missing, Err(not_found(0, "absent")));
+}
@@ -0,0 +1,106 @@
+// Ordre des écritures lors d'un flush
+mod common;
+
+use common::{journal, traced_fs, Op};
+use fat32::devices::TraceDevice;
+use fat32::{BootSector, Fat32FileSystem, MemoryDevice};
+
+/// Écritures et flush du device, sans les lectures
+fn writes(device: &TraceDevice<MemoryDevice>) -> Vec<Op> {
+    journal(device).into_iter().filter(|op| !matches!(op, Op::Read(_))).collect()
+}
+
+/// Zone du disque touchée par une écriture
+#[derive(Debug, PartialEq, PartialOrd)]
+enum Region {
+    Data,
+    Fat,
+    Directory,
+    FsInfo,
+    DeviceFlush,
+}
+
+#[test]
+fn test_writes_are_deferred_until_flush() {
+    let mut fs = traced_fs();
+    assert!(!fs.is_dirty());
+
+    fs.write_file("/a.txt", &[7u8; 1500]).unwrap();
+    assert!(fs.is_dirty());
+
+    // Seules les données sont parties
+    let bs = unsafe { BootSector::from_bytes(&fs.device().inner().data()[0..512]) };
+    let data_start = bs.first_data_sector();
+    let root_sector = data_start; // cluster 2, un secteur par cluster
+    let ops = writes(fs.device());
+    assert!(!ops.is_empty());
+    assert!(ops.iter().all(|op| matches!(op, Op::Write(s) if *s > root_sector)));
+
+    fs.flush().unwrap();
+    assert!(!fs.is_dirty());
+
+    // Le contenu survit à un remontage
+    let device = fs.into_device();
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), vec![7u8; 1500]);
+}
+
+#[test]
+fn test_flush_order() {
+    let mut fs = traced_fs();
+    fs.write_file("/a.txt", &[1u8; 1500]).unwrap();
+    fs.write_file("/b.txt", &[2u8; 700]).unwrap();
+    fs.flush().unwrap();
+
+    let bs = unsafe { BootSector::from_bytes(&fs.device().inner().data()[0..512]) };
+    let fat_start = bs.first_fat_sector();
+    let data_start = bs.first_data_sector();
+    let root_sector = data_start;
+
+    let regions: Vec<Region> = writes(fs.device())
+        .into_iter()
+        .map(|op| match op {
+            Op::Flush => Region::DeviceFlush,
+            Op::Write(s) if s == bs.fs_info() as u32 => Region::FsInfo,
+            Op::Write(s) if (fat_start..data_start).contains(&s) => Region::Fat,
+            Op::Write(s) if s == root_sector => Region::Directory,
+            _ => Region::Data,
+        })
+        .collect();
+
+    // Données, puis FAT, puis répertoire, puis FSInfo, puis flush du device
+    assert!(regions.windows(2).all(|w| w[0] <= w[1]), "{:?}", regions);
+    assert_eq!(regions.first(), Some(&Region::Data));
+    assert!(regions.contains(&Region::Fat));
+    assert!(regions.contains(&Region::Directory));
+    assert!(regions.contains(&Region::FsInfo));
+    assert_eq!(regions.last(), Some(&Region::DeviceFlush));
+}
+
+#[test]
+fn test_device_flush_calls() {
+    let flushes = |fs: &Fat32FileSystem<TraceDevice<MemoryDevice>>| {
+        fs.device().summary().flushes
+    };
+
+    // Les opérations ordinaires ne flushent pas le device
+    let mut fs = traced_fs();
+    fs.write_file("/a.txt", b"a").unwrap();
+    fs.create_dir("/dir").unwrap();
+    fs.delete_file("/a.txt").unwrap();
+    assert_eq!(flushes(&fs), 0);
+
+    // Un flush par appel, après toutes les écritures, même sans rien à écrire
+    fs.flush().unwrap();
+    assert_eq!(flushes(&fs), 1);
+    assert_eq!(writes(fs.device()).last(), Some(&Op::Flush));
+    fs.device_mut().clear();
+    fs.flush().unwrap();
+    assert_eq!(writes(fs.device()), [Op::Flush]);
+
+    // unmount : un seul flush du device, en dernier
+    fs.device_mut().clear();
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert_eq!(device.summary().flushes, 1);
+    assert_eq!(writes(&device).last(), Some(&Op::Flush));
+}
@@ -0,0 +1,91 @@
+// Compteur de clusters libres de FSInfo vérifié au montage
+mod common;
+
+use common::formatted_fs;
+use fat32::{
+    BootSector, CorruptionKind, Fat32Error, Fat32FileSystem, FatCache, FatTable, MemoryDevice,
+    ReadOnlyFileSystem,
+};
+
+const FREE_COUNT: usize = 512 + 488; // secteur FSInfo (1), champ free_count
+
+fn image() -> Vec<u8> {
+    let mut fs = formatted_fs();
+    fs.write_file("/a.bin", &[1u8; 5000]).unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap().into_inner()
+}
+
+fn free_count(image: &[u8]) -> u32 {
+    u32::from_le_bytes(image[FREE_COUNT..FREE_COUNT + 4].try_into().unwrap())
+}
+
+fn with_free_count(mut image: Vec<u8>, count: u32) -> Vec<u8> {
+    image[FREE_COUNT..FREE_COUNT + 4].copy_from_slice(&count.to_le_bytes());
+    image
+}
+
+#[test]
+fn test_mount_corrects_free_count() {
+    let good = image();
+    let free = free_count(&good);
+
+    // Compteur inconnu : compté. Faux (coupure avant l'écriture de FSInfo) :
+    // vérifié seulement avec `debug_assertions`
+    let mut recorded = vec![0xFFFF_FFFF];
+    if cfg!(debug_assertions) {
+        recorded.push(free + 3);
+    }
+    for recorded in recorded {
+        let device = MemoryDevice::from_vec(with_free_count(good.clone(), recorded));
+        let fs = Fat32FileSystem::new(device).unwrap();
+        let image = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+        assert_eq!(free_count(&image), free);
+    }
+
+    // Montage en lecture seule : corrigé en mémoire, rien n'est écrit
+    let wrong = with_free_count(good.clone(), free + 3);
+    let mut fs = ReadOnlyFileSystem::new(MemoryDevice::from_vec(wrong.clone())).unwrap();
+    assert_eq!(fs.volume_info().unwrap().free_clusters, free);
+    assert_eq!(fs.into_device().into_inner(), wrong);
+    let device = MemoryDevice::from_vec(wrong.clone());
+    let fs = Fat32FileSystem::with_readonly(device).unwrap();
+    assert_eq!(fs.into_device().into_inner(), wrong);
+}
+
+#[test]
+fn test_new_strict() {
+    let good = image();
+    let free = free_count(&good);
+    let clusters = free + 6; // racine et 5 clusters de a.bin
+
+    assert!(Fat32FileSystem::new_strict(MemoryDevice::from_vec(good.clone())).is_ok());
+
+    // Écart de moins de 1 % : corrigé
+    let device = MemoryDevice::from_vec(with_free_count(good.clone(), free - clusters / 200));
+    let fs = Fat32FileSystem::new_strict(device).unwrap();
+    let image = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+    assert_eq!(free_count(&image), free);
+
+    // Au-delà : volume refusé
+    let recorded = free - clusters / 50;
+    let device = MemoryDevice::from_vec(with_free_count(good, recorded));
+    let error = Fat32Error::Corrupted(CorruptionKind::FreeCountMismatch { recorded, actual: free });
+    assert_eq!(Fat32FileSystem::new_strict(device).err(), Some(error));
+}
+
+#[test]
+fn test_free_count_from_fat() {
+    // Image démontée proprement : son compteur FSInfo est juste
+    let good = image();
+    let free = free_count(&good);
+
+    // Compteur faux sur le disque et dans le cache : la FAT fait foi
+    let mut device = MemoryDevice::from_vec(with_free_count(good, 7));
+    let bs = unsafe { BootSector::from_bytes(&device.data()[..512]) };
+    let mut cache = FatCache::new();
+    cache.set_fs_info(Some(7), None);
+    let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+    assert_eq!(fat.free_count_from_fat(), Ok(free));
+    fat.allocate_chain(3).unwrap();
+    assert_eq!(fat.free_count_from_fat(), Ok(free - 3));
+}
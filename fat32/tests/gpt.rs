@@ -0,0 +1,143 @@
+// Disque GPT avec une partition système EFI en FAT32
+use fat32::{Fat32FileSystem, Fat32Error, Gpt, Guid, MemoryDevice, PartitionDevice};
+
+const ESP_START: u32 = 40;
+const ESP_SECTORS: u32 = 69_632; // 34 MiB
+const DISK_SECTORS: u32 = ESP_START + ESP_SECTORS + 64;
+
+/// Type "données de base" Microsoft (EBD0A0A2-B9E5-4433-87C0-68B6B72699C7)
+const BASIC_DATA: [u8; 16] = [
+    0xA2, 0xA0, 0xD0, 0xEB, 0xE5, 0xB9, 0x33, 0x44, 0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7,
+];
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn gpt_entry(type_guid: [u8; 16], first: u64, last: u64, name: &str) -> [u8; 128] {
+    let mut raw = [0u8; 128];
+    raw[0..16].copy_from_slice(&type_guid);
+    raw[16] = first as u8; // identifiant quelconque mais non nul
+    raw[32..40].copy_from_slice(&first.to_le_bytes());
+    raw[40..48].copy_from_slice(&last.to_le_bytes());
+    for (i, unit) in name.encode_utf16().enumerate() {
+        raw[56 + i * 2..58 + i * 2].copy_from_slice(&unit.to_le_bytes());
+    }
+    raw
+}
+
+/// Recalculer les CRC de l'en-tête et de la table
+fn seal(disk: &mut MemoryDevice) {
+    let data = disk.data_mut();
+    let entries_crc = crc32(&data[2 * 512..34 * 512]);
+    data[512 + 88..512 + 92].copy_from_slice(&entries_crc.to_le_bytes());
+    data[512 + 16..512 + 20].fill(0);
+    let header_crc = crc32(&data[512..512 + 92]);
+    data[512 + 16..512 + 20].copy_from_slice(&header_crc.to_le_bytes());
+}
+
+/// MBR protecteur, en-tête en LBA 1, 128 entrées en LBA 2 à 33
+fn gpt_disk() -> MemoryDevice {
+    let mut disk = MemoryDevice::new(DISK_SECTORS);
+
+    let esp = PartitionDevice::new(&mut disk, ESP_START, ESP_SECTORS);
+    let mut fs = Fat32FileSystem::format(esp, ESP_SECTORS, 1).unwrap();
+    fs.write_file("/boot.efi", b"MZ").unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    let data = disk.data_mut();
+    data[446 + 4] = 0xEE;
+    data[446 + 8..446 + 12].copy_from_slice(&1u32.to_le_bytes());
+    data[446 + 12..446 + 16].copy_from_slice(&(DISK_SECTORS - 1).to_le_bytes());
+    data[510] = 0x55;
+    data[511] = 0xAA;
+
+    let header = &mut data[512..1024];
+    header[0..8].copy_from_slice(b"EFI PART");
+    header[8..12].copy_from_slice(&0x0001_0000u32.to_le_bytes());
+    header[12..16].copy_from_slice(&92u32.to_le_bytes());
+    header[24..32].copy_from_slice(&1u64.to_le_bytes());
+    header[32..40].copy_from_slice(&(DISK_SECTORS as u64 - 1).to_le_bytes());
+    header[40..48].copy_from_slice(&34u64.to_le_bytes());
+    header[48..56].copy_from_slice(&(DISK_SECTORS as u64 - 34).to_le_bytes());
+    header[56..72].copy_from_slice(&[0x11; 16]);
+    header[72..80].copy_from_slice(&2u64.to_le_bytes());
+    header[80..84].copy_from_slice(&128u32.to_le_bytes());
+    header[84..88].copy_from_slice(&128u32.to_le_bytes());
+
+    let esp_end = (ESP_START + ESP_SECTORS - 1) as u64;
+    let entries = [
+        gpt_entry(Guid::EFI_SYSTEM_PARTITION.0, ESP_START as u64, esp_end, "EFI system"),
+        gpt_entry(BASIC_DATA, esp_end + 1, esp_end + 16, "Données"),
+    ];
+    for (i, entry) in entries.iter().enumerate() {
+        let offset = 2 * 512 + i * 128;
+        data[offset..offset + 128].copy_from_slice(entry);
+    }
+    seal(&mut disk);
+    disk
+}
+
+#[test]
+fn test_gpt_esp() {
+    let mut disk = gpt_disk();
+    let gpt = Gpt::parse(&mut disk).unwrap().unwrap();
+    assert_eq!(gpt.disk_guid, Guid([0x11; 16]));
+    assert_eq!(gpt.first_usable_lba, 34);
+    assert_eq!(gpt.entries.len(), 2);
+    assert_eq!(gpt.entries[1].name, "Données");
+    assert_eq!(gpt.entries[1].sector_count(), 16);
+
+    let esp = gpt.find_esp().unwrap();
+    assert_eq!(esp.name, "EFI system");
+    assert_eq!(esp.first_lba, ESP_START as u64);
+    assert_eq!(esp.sector_count(), ESP_SECTORS as u64);
+
+    // L'index compte les entrées de la table GPT, pas le MBR protecteur
+    let mut fs = Fat32FileSystem::new_from_partition(disk, 0).unwrap();
+    assert_eq!(fs.read_file("/boot.efi").unwrap(), b"MZ");
+}
+
+#[test]
+fn test_gpt_errors_and_fallback() {
+    // En-tête modifié sans mise à jour du CRC
+    let mut disk = gpt_disk();
+    disk.data_mut()[512 + 40] = 35;
+    assert_eq!(Gpt::parse(&mut disk), Err(Fat32Error::InvalidPartition));
+
+    // Table modifiée sans mise à jour du CRC
+    let mut disk = gpt_disk();
+    disk.data_mut()[2 * 512 + 56] = b'X';
+    assert_eq!(Gpt::parse(&mut disk), Err(Fat32Error::InvalidPartition));
+    assert_eq!(
+        Fat32FileSystem::new_from_partition(disk, 0).err(),
+        Some(Fat32Error::InvalidPartition)
+    );
+
+    // Dernier LBA au maximum : lu sans débordement, partition refusée
+    let mut disk = gpt_disk();
+    disk.data_mut()[2 * 512 + 40..2 * 512 + 48].copy_from_slice(&u64::MAX.to_le_bytes());
+    seal(&mut disk);
+    let gpt = Gpt::parse(&mut disk).unwrap().unwrap();
+    assert_eq!(gpt.entries[0].sector_count(), 0);
+    assert_eq!(
+        Fat32FileSystem::new_from_partition(disk, 0).err(),
+        Some(Fat32Error::InvalidPartition)
+    );
+
+    // Sans MBR protecteur, ou sans signature : pas de GPT
+    let mut disk = gpt_disk();
+    disk.data_mut()[446 + 4] = 0x0C;
+    assert_eq!(Gpt::parse(&mut disk), Ok(None));
+    let mut disk = gpt_disk();
+    disk.data_mut()[512] = 0;
+    assert_eq!(Gpt::parse(&mut disk), Ok(None));
+    assert_eq!(Gpt::parse(&mut MemoryDevice::new(64)), Ok(None));
+}
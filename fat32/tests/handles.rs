@@ -0,0 +1,71 @@
+// Plusieurs handles de lecture ouverts en même temps
+mod common;
+
+use common::formatted_fs_with;
+
+#[test]
+fn test_interleaved_handles() {
+    let mut fs = formatted_fs_with(2);
+
+    // Deux fichiers de plusieurs clusters (1 KiB par cluster)
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let index: Vec<u8> = (0..3300u32).map(|i| (i % 13) as u8 + b'a').collect();
+    fs.write_file("/data.bin", &data).unwrap();
+    fs.write_file("/index.idx", &index).unwrap();
+
+    let mut data_handle = fs.open_file("/data.bin").unwrap();
+    let mut index_handle = fs.open_file("/index.idx").unwrap();
+
+    // Lectures alternées avec des tailles qui ne tombent pas sur les clusters
+    let mut data_read = Vec::new();
+    let mut index_read = Vec::new();
+    let mut buf = [0u8; 700];
+    loop {
+        let n1 = fs.read_handle(&mut data_handle, &mut buf).unwrap();
+        data_read.extend_from_slice(&buf[..n1]);
+        let n2 = fs.read_handle(&mut index_handle, &mut buf[..333]).unwrap();
+        index_read.extend_from_slice(&buf[..n2]);
+        if n1 == 0 && n2 == 0 {
+            break;
+        }
+    }
+
+    assert_eq!(data_read, data);
+    assert_eq!(index_read, index);
+    assert!(data_handle.is_eof() && index_handle.is_eof());
+}
+
+#[test]
+fn test_handle_keeps_snapshot() {
+    let mut fs = formatted_fs_with(2);
+    fs.write_file("/a.txt", b"hello").unwrap();
+
+    let mut handle = fs.open_file("/a.txt").unwrap();
+    fs.delete_file("/a.txt").unwrap();
+
+    // La taille reste celle de l'ouverture
+    let mut buf = [0u8; 16];
+    assert_eq!(fs.read_handle(&mut handle, &mut buf).unwrap(), 5);
+    assert!(fs.open_file("/a.txt").is_err());
+}
+
+#[test]
+fn test_compare_files() {
+    let mut fs = formatted_fs_with(2);
+    let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    let mut last_differs = data.clone();
+    *last_differs.last_mut().unwrap() ^= 1;
+
+    fs.write_file("/a.bin", &data).unwrap();
+    fs.write_file("/b.bin", &data).unwrap();
+    fs.write_file("/c.bin", &last_differs).unwrap();
+    fs.write_file("/d.bin", &data[..4999]).unwrap();
+    fs.write_file("/e.bin", b"").unwrap();
+    fs.write_file("/f.bin", b"").unwrap();
+
+    assert!(fs.compare_files("/a.bin", "/b.bin").unwrap());
+    assert!(!fs.compare_files("/a.bin", "/c.bin").unwrap());
+    assert!(!fs.compare_files("/a.bin", "/d.bin").unwrap());
+    assert!(fs.compare_files("/e.bin", "/f.bin").unwrap());
+    assert!(fs.compare_files("/a.bin", "/absent.bin").is_err());
+}
@@ -0,0 +1,100 @@
+// Relecture du volume après une modification extérieure du device (`hard_reset`)
+mod common;
+
+use common::{clean_on_disk, image_sectors, not_found};
+use fat32::devices::TraceDevice;
+use fat32::{Fat32Error, Fat32FileSystem, MemoryDevice};
+
+/// Image formatée avec `sectors_per_cluster`, contenant `name`
+///
+/// Toujours de la taille qu'il faut à des clusters de 4 secteurs, pour que
+/// les deux formats tiennent sur le même device.
+fn image(sectors_per_cluster: u8, name: &str) -> Vec<u8> {
+    let sectors = image_sectors(4);
+    let device = MemoryDevice::new(sectors);
+    let mut fs = Fat32FileSystem::format(device, sectors, sectors_per_cluster).unwrap();
+    fs.create_dir("/dir").unwrap();
+    fs.write_file(name, b"contenu").unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap().into_inner()
+}
+
+#[test]
+fn test_hard_reset_rereads_volume() {
+    let device = MemoryDevice::from_vec(image(1, "/old.txt"));
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    fs.change_dir("/dir").unwrap();
+    assert_eq!(fs.read_file("/old.txt").unwrap(), b"contenu");
+
+    // Un autre programme reformate l'image avec des clusters de 4 secteurs
+    let other = image(4, "/new.txt");
+    fs.device_mut().data_mut().copy_from_slice(&other);
+    fs.hard_reset().unwrap();
+
+    assert_eq!(fs.boot_sector().cluster_size(), 2048);
+    assert_eq!(fs.current_dir(), fs.boot_sector().root_cluster());
+    assert_eq!(fs.read_file("new.txt").unwrap(), b"contenu");
+    assert_eq!(fs.read_file("/old.txt"), Err(not_found(0, "old.txt")));
+    let info = fs.volume_info().unwrap();
+    let remount = Fat32FileSystem::new(MemoryDevice::from_vec(other)).unwrap().volume_info();
+    assert_eq!(info, remount.unwrap());
+
+    // Boot sector effacé : refusé, l'état d'avant reste utilisable
+    let saved = fs.device().data()[..512].to_vec();
+    fs.device_mut().data_mut()[..512].fill(0);
+    assert_eq!(fs.hard_reset(), Err(Fat32Error::InvalidBootSector));
+    fs.device_mut().data_mut()[..512].copy_from_slice(&saved);
+    assert_eq!(fs.read_file("/new.txt").unwrap(), b"contenu");
+}
+
+#[test]
+fn test_hard_reset_readonly_stays_clean() {
+    let device = MemoryDevice::from_vec(image(1, "/a.txt"));
+    let mut fs = Fat32FileSystem::with_readonly(device).unwrap();
+
+    // Compteur FSInfo faux sur le device : corrigé en mémoire, jamais réécrit
+    fs.device_mut().data_mut()[512 + 488..512 + 492].copy_from_slice(&7u32.to_le_bytes());
+    let before = fs.device().data().to_vec();
+    fs.hard_reset().unwrap();
+    assert!(!fs.is_dirty());
+    drop(fs.unmount().map_err(|(_, err)| err).unwrap());
+
+    let mut fs = Fat32FileSystem::with_readonly(MemoryDevice::from_vec(before.clone())).unwrap();
+    fs.hard_reset().unwrap();
+    fs.flush().unwrap();
+    assert_eq!(fs.into_device().data(), &before[..]);
+}
+
+#[test]
+fn test_hard_reset_keeps_clean_bit_restore() {
+    let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(image(1, "/a.txt"))).unwrap();
+    fs.write_file("/b.txt", b"b").unwrap();
+    fs.flush().unwrap();
+    assert!(!clean_on_disk(fs.device_mut()));
+
+    // Le bit effacé par ce montage est remis malgré la relecture
+    fs.hard_reset().unwrap();
+    fs.write_file("/c.txt", b"c").unwrap();
+    let mut device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert!(clean_on_disk(&mut device));
+}
+
+#[test]
+fn test_hard_reset_keeps_batch() {
+    let device = TraceDevice::new(MemoryDevice::from_vec(image(1, "/a.txt")));
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    fs.begin_batch();
+    fs.hard_reset().unwrap();
+    fs.device_mut().clear();
+
+    // 20 répertoires : plus que le cache de répertoires hors lot
+    for d in 0..20 {
+        fs.create_dir(&format!("/d{}", d)).unwrap();
+        fs.write_file(&format!("/d{}/f.txt", d), b"x").unwrap();
+    }
+    // Seuls les clusters des répertoires et des fichiers sont écrits, plus
+    // le deuxième cluster de la racine et le bit « propre » effacé dans les
+    // deux FAT à la première modification
+    assert_eq!(fs.device().summary().writes, 43);
+    fs.end_batch().unwrap();
+    assert_eq!(fs.read_file("/d19/f.txt").unwrap(), b"x");
+}
@@ -0,0 +1,74 @@
+// Copies entre l'hôte et l'image (feature `std`)
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::{formatted_fs, formatted_fs_with};
+use fat32::host::{self, copy_in, copy_out};
+use fat32::Fat32Error;
+
+/// Répertoire temporaire propre à un test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fat32-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_copy_in_and_out() {
+    let dir = temp_dir("copy");
+    let tree = dir.join("tree");
+    fs::create_dir_all(tree.join("sub")).unwrap();
+    let big: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(tree.join("big.bin"), &big).unwrap();
+    fs::write(tree.join("sub/note.txt"), b"hello").unwrap();
+    fs::write(tree.join("empty.txt"), b"").unwrap();
+
+    let mut fs = formatted_fs_with(2);
+
+    // Les parents manquants sont créés
+    assert_eq!(copy_in(&mut fs, &tree, "/data/in").unwrap(), 5005);
+    assert_eq!(fs.read_file("/data/in/big.bin").unwrap(), big);
+    assert_eq!(fs.read_file("/data/in/sub/note.txt").unwrap(), b"hello");
+    assert!(fs.read_file("/data/in/empty.txt").unwrap().is_empty());
+
+    let out = dir.join("big.out");
+    assert_eq!(copy_out(&mut fs, "/data/in/big.bin", &out).unwrap(), 5000);
+    assert_eq!(fs::read(&out).unwrap(), big);
+
+    // Erreurs côté image et côté hôte
+    assert!(matches!(
+        copy_out(&mut fs, "/missing.bin", &out),
+        Err(host::Error::Image(Fat32Error::NotFound { .. }))
+    ));
+    assert!(matches!(
+        copy_in(&mut fs, &dir.join("missing"), "/x.bin"),
+        Err(host::Error::Host(_))
+    ));
+    assert!(matches!(
+        copy_in(&mut fs, &tree.join("big.bin"), "/data/in/big.bin"),
+        Err(host::Error::Image(Fat32Error::AlreadyExists))
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_copy_in_rejects_symlinks() {
+    let dir = temp_dir("symlink");
+    fs::write(dir.join("target.txt"), b"data").unwrap();
+    std::os::unix::fs::symlink(dir.join("target.txt"), dir.join("link.txt")).unwrap();
+
+    let mut fs = formatted_fs();
+
+    match copy_in(&mut fs, &dir.join("link.txt"), "/link.txt") {
+        Err(host::Error::Host(err)) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput),
+        other => panic!("lien symbolique accepté : {:?}", other),
+    }
+    assert!(fs.read_file("/link.txt").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,73 +1,51 @@
 // Tests d'intégration pour FAT32
-use fat32::{BlockDevice, Fat32FileSystem, Fat32Error, Result};
+use fat32::{Fat32FileSystem, Fat32Error, MemoryDevice};
 
-/// Device de test
-struct TestDevice {
-    data: Vec<u8>,
-}
+const IMAGE_SECTORS: u32 = 69_632; // 34 MiB, assez pour 65525 clusters
 
-impl TestDevice {
-    fn new_fat32() -> Self {
-        let mut data = vec![0u8; 1024 * 512];
-        
-        // Boot sector minimal
-        data[0..3].copy_from_slice(&[0xEB, 0x58, 0x90]); // jump
-        data[3..11].copy_from_slice(b"MSWIN4.1"); // OEM
-        data[11..13].copy_from_slice(&512u16.to_le_bytes()); // bytes per sector
-        data[13] = 8; // sectors per cluster
-        data[14..16].copy_from_slice(&32u16.to_le_bytes()); // reserved
-        data[16] = 2; // num fats
-        data[36..40].copy_from_slice(&8u32.to_le_bytes()); // fat size
-        data[44..48].copy_from_slice(&2u32.to_le_bytes()); // root cluster
-        data[66] = 0x29; // signature
-        
-        Self { data }
-    }
-}
-
-impl BlockDevice for TestDevice {
-    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
-        let offset = sector as usize * 512;
-        if offset + buffer.len() > self.data.len() {
-            return Err(Fat32Error::IoError);
-        }
-        buffer.copy_from_slice(&self.data[offset..offset + buffer.len()]);
-        Ok(())
-    }
+/// Image FAT32 minimale
+fn fat32_image() -> MemoryDevice {
+    let mut data = vec![0u8; IMAGE_SECTORS as usize * 512];
 
-    fn write_sector(&mut self, _: u32, _: &[u8]) -> Result<()> {
-        Ok(())
-    }
+    // Boot sector minimal
+    data[0..3].copy_from_slice(&[0xEB, 0x58, 0x90]); // jump
+    data[3..11].copy_from_slice(b"MSWIN4.1"); // OEM
+    data[11..13].copy_from_slice(&512u16.to_le_bytes()); // bytes per sector
+    data[13] = 1; // sectors per cluster
+    data[14..16].copy_from_slice(&32u16.to_le_bytes()); // reserved
+    data[16] = 2; // num fats
+    data[32..36].copy_from_slice(&IMAGE_SECTORS.to_le_bytes()); // total sectors
+    data[36..40].copy_from_slice(&536u32.to_le_bytes()); // fat size
+    data[44..48].copy_from_slice(&2u32.to_le_bytes()); // root cluster
+    data[66] = 0x29; // signature
 
-    fn sector_size(&self) -> usize {
-        512
-    }
+    MemoryDevice::from_vec(data)
 }
 
 #[test]
 fn test_create_filesystem() {
-    let device = TestDevice::new_fat32();
+    let device = fat32_image();
     let fs = Fat32FileSystem::new(device);
     assert!(fs.is_ok());
 }
 
 #[test]
 fn test_invalid_filesystem() {
-    let device = TestDevice { data: vec![0; 1024 * 512] };
+    let device = MemoryDevice::new(1024);
     let fs = Fat32FileSystem::new(device);
     assert!(fs.is_err());
 }
 
 #[test]
 fn test_current_directory() {
-    let device = TestDevice::new_fat32();
+    let device = fat32_image();
     let fs = Fat32FileSystem::new(device).unwrap();
     assert_eq!(fs.current_dir(), 2); // root cluster
 }
 
 #[test]
 fn test_list_empty_directory() {
-    let device = TestDevice::new_fat32();
+    let device = fat32_image();
     let mut fs = Fat32FileSystem::new(device).unwrap();
     let result = fs.list_dir(None);
     // Peut être Ok(vide) ou Err selon l'état de la FAT
@@ -76,7 +54,7 @@ fn test_list_empty_directory() {
 
 #[test]
 fn test_change_directory_root() {
-    let device = TestDevice::new_fat32();
+    let device = fat32_image();
     let mut fs = Fat32FileSystem::new(device).unwrap();
     let result = fs.change_dir("/");
     // Devrait marcher ou échouer proprement
@@ -85,7 +63,7 @@ fn test_change_directory_root() {
 
 #[test]
 fn test_invalid_paths() {
-    let device = TestDevice::new_fat32();
+    let device = fat32_image();
     let mut fs = Fat32FileSystem::new(device).unwrap();
     
     // Ces chemins devraient échouer
@@ -99,9 +77,38 @@ fn test_invalid_paths() {
 
 #[test]
 fn test_read_nonexistent_file() {
-    let device = TestDevice::new_fat32();
+    let device = fat32_image();
     let mut fs = Fat32FileSystem::new(device).unwrap();
     
     let result = fs.read_file("nonexistent.txt");
     assert!(result.is_err());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_invalid_names_rejected_before_lookup() {
+    let device = fat32_image();
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+
+    let long_name = "a".repeat(300);
+    assert_eq!(fs.read_file(&long_name), Err(Fat32Error::NameTooLong));
+    assert_eq!(
+        fs.change_dir("/bad\0dir"),
+        Err(Fat32Error::InvalidName(fat32::NameError::NulByte))
+    );
+}
+
+#[test]
+fn test_into_device_remount() {
+    let device = fat32_image();
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.device().data().len(), IMAGE_SECTORS as usize * 512);
+
+    // Accès brut puis remontage avec le même device
+    fs.device_mut().data_mut()[1000] = 0xAB;
+    fs.clear_caches();
+    let device = fs.into_device();
+    assert_eq!(device.data()[1000], 0xAB);
+
+    let fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.current_dir(), 2);
+}
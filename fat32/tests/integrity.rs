@@ -0,0 +1,42 @@
+// Vérification des références de clusters
+mod common;
+
+use common::formatted_fs;
+use fat32::{Fat32FileSystem, FatCache, FatTable, MemoryDevice};
+
+const FAT_EOC: u32 = 0x0FFFFFFF;
+
+fn first_cluster(fs: &mut Fat32FileSystem<MemoryDevice>, dir: &str, name: &str) -> u32 {
+    let entries = fs.list_dir(Some(dir)).unwrap();
+    entries.iter().find(|e| e.short_name() == name).unwrap().first_cluster()
+}
+
+#[test]
+fn test_check_integrity() {
+    let mut fs = formatted_fs();
+
+    fs.create_dir("/sub").unwrap();
+    fs.write_file("/a.bin", &[1; 1500]).unwrap();
+    fs.write_file("/sub/b.bin", &[2; 1100]).unwrap();
+    assert!(fs.check_integrity().unwrap().is_clean());
+
+    let a = first_cluster(&mut fs, "/", "A.BIN");
+    let b = first_cluster(&mut fs, "/sub", "B.BIN");
+
+    // Corrompre la FAT : a.bin se prolonge dans b.bin, et un cluster perdu
+    let bs = *fs.boot_sector();
+    {
+        let mut cache = FatCache::new();
+        let mut fat = FatTable::new(fs.device_mut(), &bs, &mut cache);
+        fat.write_entry(a + 2, b).unwrap();
+        fat.write_entry(900, FAT_EOC).unwrap();
+        fat.flush().unwrap();
+    }
+    fs.clear_caches();
+
+    let report = fs.check_integrity().unwrap();
+    assert!(!report.is_clean());
+    assert_eq!(report.cross_linked, vec![a, b]);
+    assert_eq!(report.multiply_referenced, vec![b, b + 1, b + 2]);
+    assert_eq!(report.unreferenced, vec![900]);
+}
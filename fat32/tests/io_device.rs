@@ -0,0 +1,56 @@
+// Image derrière un flux quelconque (feature `std`)
+mod common;
+
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+use common::{formatted_fs, IMAGE_SECTORS};
+use fat32::devices::IoDevice;
+use fat32::{Fat32FileSystem, Fat32Error, IoOp, MemoryDevice, ReadBlockDevice, ReadOnlyFileSystem};
+
+/// Flux en lecture seule qui rend au plus 100 octets par `read`
+struct Trickle(Cursor<Vec<u8>>);
+
+impl Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(100);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+impl Seek for Trickle {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+fn image() -> Vec<u8> {
+    let mut fs = formatted_fs();
+    fs.write_file("/a.txt", b"dans un curseur").unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap().into_inner()
+}
+
+#[test]
+fn test_cursor_device() {
+    let device = IoDevice::new(Cursor::new(image())).unwrap();
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"dans un curseur");
+    fs.write_file("/b.txt", b"ecrit").unwrap();
+    let image = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner().into_inner();
+
+    let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(image)).unwrap();
+    assert_eq!(fs.read_file("/b.txt").unwrap(), b"ecrit");
+}
+
+#[test]
+fn test_read_only_stream() {
+    // Lectures partielles recomposées, montage sans `Write`
+    let device = IoDevice::new(Trickle(Cursor::new(image()))).unwrap();
+    let mut fs = ReadOnlyFileSystem::new(device).unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"dans un curseur");
+
+    // Accès au-delà de la fin du flux
+    let mut device = fs.into_device();
+    let mut buffer = [0u8; 512];
+    let io = |op, sector| Err(Fat32Error::IoError { op, sector });
+    assert_eq!(device.read_sector(IMAGE_SECTORS, &mut buffer), io(IoOp::Read, IMAGE_SECTORS));
+}
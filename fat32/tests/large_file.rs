@@ -0,0 +1,82 @@
+// Test d'un fichier qui occupe plus de 256 clusters
+use fat32::{CorruptionKind, Fat32Error, Fat32FileSystem, FatCache, FatTable, MemoryDevice};
+
+// 513 MiB : au moins 65525 clusters de 8 KiB pour FAT32
+const IMAGE_SECTORS: u32 = 513 * 1024 * 1024 / 512;
+const SECTORS_PER_CLUSTER: u8 = 16; // clusters de 8 KiB
+const CLUSTER_SIZE: usize = 8 * 1024;
+// Un peu plus de 2 MiB pour dépasser 256 clusters
+const FILE_SIZE: usize = 2 * 1024 * 1024 + 1234;
+
+/// Motif pseudo-aléatoire (xorshift) initialisé par l'index du cluster
+fn pattern(size: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(size);
+    for (index, chunk_start) in (0..size).step_by(CLUSTER_SIZE).enumerate() {
+        let mut state = (index as u32).wrapping_mul(0x9E3779B9) | 1;
+        for _ in chunk_start..(chunk_start + CLUSTER_SIZE).min(size) {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            data.push(state as u8);
+        }
+    }
+    data
+}
+
+#[test]
+fn test_large_file_roundtrip() {
+    let device = MemoryDevice::new(IMAGE_SECTORS);
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, SECTORS_PER_CLUSTER).unwrap();
+    let initial_free = fs.free_space().unwrap();
+
+    let data = pattern(FILE_SIZE);
+    fs.write_file("/big.bin", &data).unwrap();
+
+    // Relire et comparer chaque octet
+    let read = fs.read_file("/big.bin").unwrap();
+    assert_eq!(read.len(), FILE_SIZE);
+    assert!(read == data, "contenu relu différent");
+
+    // Longueur de la chaîne
+    let expected_clusters = FILE_SIZE.div_ceil(CLUSTER_SIZE) as u32;
+    assert!(expected_clusters > 256);
+
+    let entries = fs.list_dir(Some("/")).unwrap();
+    let entry = entries
+        .iter()
+        .find(|e| e.short_name() == "BIG.BIN")
+        .unwrap();
+    assert_eq!(fs.cluster_chain_length(entry.first_cluster()).unwrap(), expected_clusters);
+    assert_eq!(fs.used_clusters_by_file("/big.bin").unwrap(), expected_clusters);
+
+    // Espace libéré après suppression
+    fs.delete_file("/big.bin").unwrap();
+    let final_free = fs.free_space().unwrap();
+    assert!(initial_free.abs_diff(final_free) <= CLUSTER_SIZE as u64);
+    assert!(fs.read_file("/big.bin").is_err());
+}
+
+#[test]
+fn test_large_file_cycle() {
+    let device = MemoryDevice::new(IMAGE_SECTORS);
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, SECTORS_PER_CLUSTER).unwrap();
+    fs.write_file("/big.bin", &pattern(FILE_SIZE)).unwrap();
+    let first = fs.list_dir(Some("/")).unwrap()[0].first_cluster();
+    let bs = *fs.boot_sector();
+    let mut device = fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    // Dernier cluster renvoyé sur le premier, directement sur le disque
+    let mut cache = FatCache::new();
+    let mut fat = FatTable::new(&mut device, &bs, &mut cache);
+    let last = *fat.cluster_chain(first).unwrap().last().unwrap();
+    fat.write_entry(last, first).unwrap();
+    fat.flush().unwrap();
+
+    // La chaîne ne finit jamais : arrêt après `num_clusters()` pas
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    let is_cycle = |result: fat32::Result<u32>| {
+        matches!(result, Err(Fat32Error::Corrupted(CorruptionKind::ChainCycle { .. })))
+    };
+    assert!(is_cycle(fs.cluster_chain_length(first)));
+    assert!(is_cycle(fs.used_clusters_by_file("/big.bin")));
+}
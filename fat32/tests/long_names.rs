@@ -0,0 +1,159 @@
+// Noms longs (VFAT) associés aux entrées 8.3
+mod common;
+
+use common::formatted_fs;
+use fat32::lfn_checksum;
+
+/// Entrée de nom long numéro `order` portant `text` (13 caractères max)
+fn lfn_entry(order: u8, checksum: u8, text: &str) -> [u8; 32] {
+    let mut units: Vec<u16> = text.encode_utf16().collect();
+    if units.len() < 13 {
+        units.push(0);
+    }
+    units.resize(13, 0xFFFF);
+
+    let mut raw = [0u8; 32];
+    raw[0] = order;
+    raw[11] = 0x0F;
+    raw[13] = checksum;
+    let offsets = (1..11).step_by(2).chain((14..26).step_by(2)).chain((28..32).step_by(2));
+    for (unit, offset) in units.iter().zip(offsets) {
+        raw[offset..offset + 2].copy_from_slice(&unit.to_le_bytes());
+    }
+    raw
+}
+
+#[test]
+fn test_list_dir_with_long_names() {
+    let mut fs = formatted_fs();
+    fs.write_file("/readme.txt", b"a").unwrap();
+    fs.write_file("/notes.txt", b"b").unwrap();
+    fs.write_file("/data.bin", b"c").unwrap();
+    fs.flush().unwrap();
+
+    // Réécrire la racine : chaque entrée 8.3 précédée de ses noms longs
+    let bs = *fs.boot_sector();
+    let root = bs.first_data_sector() as usize * 512;
+    let device = fs.device_mut();
+    let short: Vec<[u8; 32]> = (0..3)
+        .map(|i| device.data()[root + i * 32..root + (i + 1) * 32].try_into().unwrap())
+        .collect();
+    let sum = |entry: &[u8; 32]| lfn_checksum(entry[..11].try_into().unwrap());
+
+    let mut deleted = lfn_entry(0x41, sum(&short[2]), "supprimé");
+    deleted[0] = 0xE5;
+    let entries = [
+        // 13 caractères pile : ni terminateur ni remplissage
+        lfn_entry(0x41, sum(&short[0]), "Lisez-moi.txt"),
+        short[0],
+        lfn_entry(0x42, sum(&short[1]), "e.txt"),
+        lfn_entry(0x01, sum(&short[1]), "Notes de réun"),
+        short[1],
+        // Nom long effacé : l'entrée 8.3 n'en a plus
+        deleted,
+        short[2],
+    ];
+    for (i, entry) in entries.iter().enumerate() {
+        device.data_mut()[root + i * 32..root + (i + 1) * 32].copy_from_slice(entry);
+    }
+    fs.clear_caches();
+
+    let listed: Vec<(String, Option<String>)> = fs
+        .list_dir_with_long_names(None)
+        .unwrap()
+        .into_iter()
+        .map(|(entry, long)| (entry.short_name(), long))
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            ("README.TXT".to_string(), Some("Lisez-moi.txt".to_string())),
+            ("NOTES.TXT".to_string(), Some("Notes de réune.txt".to_string())),
+            ("DATA.BIN".to_string(), None),
+        ]
+    );
+
+    // Les entrées de nom long ne sont pas listées comme fichiers
+    assert_eq!(fs.list_dir(None).unwrap().len(), 3);
+}
+
+#[test]
+fn test_find_entry_by_long_name() {
+    let mut fs = formatted_fs();
+    fs.write_file("/readme.txt", b"contenu").unwrap();
+    fs.create_dir("/docs").unwrap();
+    fs.flush().unwrap();
+
+    // Noms longs devant les deux entrées de la racine
+    let bs = *fs.boot_sector();
+    let root = bs.first_data_sector() as usize * 512;
+    let data = fs.device_mut().data_mut();
+    let short: Vec<[u8; 32]> = (0..2)
+        .map(|i| data[root + i * 32..root + (i + 1) * 32].try_into().unwrap())
+        .collect();
+    let sum = |entry: &[u8; 32]| lfn_checksum(entry[..11].try_into().unwrap());
+    let entries = [
+        lfn_entry(0x41, sum(&short[0]), "Lisez-moi.txt"),
+        short[0],
+        lfn_entry(0x41, sum(&short[1]), "Données"),
+        short[1],
+    ];
+    for (i, entry) in entries.iter().enumerate() {
+        data[root + i * 32..root + (i + 1) * 32].copy_from_slice(entry);
+    }
+    fs.clear_caches();
+
+    let root_cluster = bs.root_cluster();
+    let by_long = fs.find_entry_in_dir(root_cluster, "LISEZ-MOI.TXT").unwrap().unwrap();
+    assert_eq!(by_long.short_name(), "README.TXT");
+    let by_short = fs.find_entry_in_dir(root_cluster, "readme.txt").unwrap().unwrap();
+    assert_eq!(by_short, by_long);
+    assert_eq!(fs.find_entry_in_dir(root_cluster, "absent.txt").unwrap(), None);
+
+    // Les chemins acceptent aussi les noms longs
+    assert_eq!(fs.read_file("/lisez-moi.txt").unwrap(), b"contenu");
+    let docs = fs.find_entry_in_dir(root_cluster, "docs").unwrap().unwrap();
+    fs.change_dir("/DONNÉES").unwrap();
+    assert_eq!(fs.current_dir(), docs.first_cluster());
+}
+
+#[test]
+fn test_delete_removes_long_name_slots() {
+    let mut fs = formatted_fs();
+    fs.write_file("/readme.txt", b"a").unwrap();
+    fs.write_file("/notes.txt", b"b").unwrap();
+    fs.flush().unwrap();
+
+    let bs = *fs.boot_sector();
+    let root = bs.first_data_sector() as usize * 512;
+    let data = fs.device_mut().data_mut();
+    let short: Vec<[u8; 32]> = (0..2)
+        .map(|i| data[root + i * 32..root + (i + 1) * 32].try_into().unwrap())
+        .collect();
+    let sum = |entry: &[u8; 32]| lfn_checksum(entry[..11].try_into().unwrap());
+    let entries = [
+        lfn_entry(0x42, sum(&short[0]), "gue.txt"),
+        lfn_entry(0x01, sum(&short[0]), "Lisez-moi lon"),
+        short[0],
+        lfn_entry(0x41, sum(&short[1]), "Notes.txt"),
+        short[1],
+    ];
+    for (i, entry) in entries.iter().enumerate() {
+        data[root + i * 32..root + (i + 1) * 32].copy_from_slice(entry);
+    }
+    fs.clear_caches();
+
+    // Suppression par le nom long : les deux slots et l'entrée 8.3
+    fs.delete_file("/Lisez-moi longue.txt").unwrap();
+    fs.flush().unwrap();
+    let data = fs.device().data();
+    let first_bytes: Vec<u8> = (0..5).map(|i| data[root + i * 32]).collect();
+    assert_eq!(first_bytes, [0xE5, 0xE5, 0xE5, 0x41, short[1][0]]);
+
+    // Par le nom 8.3 : le nom long part aussi
+    fs.delete_file("/notes.txt").unwrap();
+    fs.flush().unwrap();
+    let data = fs.device().data();
+    assert!((0..5).all(|i| data[root + i * 32] == 0xE5));
+    assert!(fs.list_dir_with_long_names(None).unwrap().is_empty());
+}
@@ -0,0 +1,31 @@
+// Recherche du propriétaire d'un cluster
+mod common;
+
+use common::formatted_fs;
+use fat32::ClusterLookup;
+
+#[test]
+fn test_path_of_cluster() {
+    let mut fs = formatted_fs();
+
+    fs.write_file("/small.txt", b"hello").unwrap();
+    fs.write_file("/big.bin", &[0x42; 2000]).unwrap();
+
+    let entries = fs.list_dir(Some("/")).unwrap();
+    let big = entries.iter().find(|e| e.short_name() == "BIG.BIN").unwrap();
+    let first = big.first_cluster();
+
+    assert_eq!(fs.path_of_cluster(2).unwrap().as_deref(), Some("/"));
+    assert_eq!(fs.path_of_cluster(first).unwrap().as_deref(), Some("/BIG.BIN"));
+
+    // Un cluster au milieu de la chaîne n'est trouvé qu'en mode complet
+    let middle = first + 2;
+    assert_eq!(fs.path_of_cluster(middle).unwrap(), None);
+    assert_eq!(
+        fs.path_of_cluster_with(middle, ClusterLookup::FullChain).unwrap().as_deref(),
+        Some("/BIG.BIN")
+    );
+
+    // Cluster libre : personne ne le possède
+    assert_eq!(fs.path_of_cluster_with(500, ClusterLookup::FullChain).unwrap(), None);
+}
@@ -0,0 +1,88 @@
+// Image projetée en mémoire (feature `mmap`)
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::IMAGE_SECTORS;
+use fat32::devices::MmapDevice;
+use fat32::{BlockDevice, Fat32Error, IoOp, Fat32FileSystem, MemoryDevice, ReadBlockDevice};
+
+/// Fichier temporaire propre à un test
+fn temp_image(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("fat32-{}-{}.img", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+/// Mêmes opérations sur n'importe quel device
+fn exercise<D: BlockDevice>(fs: &mut Fat32FileSystem<D>) {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/docs/a.bin", &data).unwrap();
+    fs.write_file("/b.txt", b"bonjour").unwrap();
+    fs.write_file("/c.txt", b"temporaire").unwrap();
+    fs.delete_file("/c.txt").unwrap();
+    fs.flush().unwrap();
+
+    assert_eq!(fs.read_file("/docs/a.bin").unwrap(), data);
+    assert_eq!(fs.read_to_string("/b.txt").unwrap(), "bonjour");
+    assert!(matches!(fs.read_file("/c.txt"), Err(Fat32Error::NotFound { .. })));
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_mmap_matches_memory_device() {
+    let blank = MemoryDevice::new(IMAGE_SECTORS);
+    let mut expected = Fat32FileSystem::format(blank, IMAGE_SECTORS, 1).unwrap();
+    exercise(&mut expected);
+    let expected = expected.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+
+    let path = temp_image("mmap");
+    fs::write(&path, vec![0u8; IMAGE_SECTORS as usize * 512]).unwrap();
+    let device = MmapDevice::open(&path).unwrap();
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+    exercise(&mut fs);
+    drop(fs.unmount().map_err(|(_, err)| err).unwrap());
+
+    // Tout est dans le fichier, octet pour octet
+    assert_eq!(fs::read(&path).unwrap(), expected);
+    let mut fs = Fat32FileSystem::new(MmapDevice::open(&path).unwrap()).unwrap();
+    assert_eq!(fs.read_to_string("/b.txt").unwrap(), "bonjour");
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_mmap_unaligned_tail_and_bounds() {
+    let path = temp_image("mmap-tail");
+    let mut image = vec![0u8; 512 * 3 + 100];
+    image[512..1024].fill(0xAA);
+    fs::write(&path, &image).unwrap();
+
+    // Le secteur incomplet de la fin n'existe pas
+    let mut device = MmapDevice::open(&path).unwrap();
+    assert_eq!(device.sector_count(), 3);
+    assert_eq!(device.num_sectors(), 3);
+    let mut sector = [0u8; 512];
+    device.read_sector(1, &mut sector).unwrap();
+    assert_eq!(sector, [0xAA; 512]);
+    let io = |op, sector| Err(Fat32Error::IoError { op, sector });
+    assert_eq!(device.read_sector(3, &mut sector), io(IoOp::Read, 3));
+    assert_eq!(device.write_sector(3, &sector), io(IoOp::Write, 3));
+    let mut two = [0u8; 1024];
+    assert_eq!(device.read_sectors(1, 1, &mut two), Err(Fat32Error::BufferTooSmall));
+
+    device.write_sector(2, &[0x55; 512]).unwrap();
+    device.flush().unwrap();
+    drop(device);
+    let written = fs::read(&path).unwrap();
+    assert_eq!(written.len(), 512 * 3 + 100);
+    assert!(written[1024..1536].iter().all(|&b| b == 0x55));
+
+    let device = MmapDevice::open_with_sector_size(&path, 1024).unwrap();
+    assert_eq!(device.sector_count(), 1);
+    assert!(MmapDevice::open_with_sector_size(&path, 0).is_err());
+
+    let _ = fs::remove_file(&path);
+}
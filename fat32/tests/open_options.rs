@@ -0,0 +1,100 @@
+// Ouverture avec options (lecture, écriture, création...)
+mod common;
+
+use common::{formatted_fs, not_found};
+use fat32::{Fat32FileSystem, Fat32Error, MemoryDevice, OpenOptions, Result};
+
+/// Ouvrir `/a.txt` (qui contient "hello" si `exists`), écrire "XY" si le
+/// handle est en écriture, et relire le fichier
+fn run(
+    fs: &mut Fat32FileSystem<MemoryDevice>,
+    options: &OpenOptions,
+    writes: bool,
+    exists: bool,
+) -> Result<Vec<u8>> {
+    let _ = fs.delete_file("/a.txt");
+    if exists {
+        fs.write_file("/a.txt", b"hello").unwrap();
+    }
+
+    let mut file = fs.open_with("/a.txt", options)?;
+    if writes {
+        assert_eq!(fs.write_handle(&mut file, b"XY")?, 2);
+    }
+    fs.read_file("/a.txt")
+}
+
+#[test]
+fn test_open_options_combinations() {
+    let mut fs = formatted_fs();
+    let invalid: Result<&[u8]> = Err(Fat32Error::InvalidInput);
+    let not_found: Result<&[u8]> = Err(not_found(0, "a.txt"));
+    let ok = |data: &'static [u8]| -> Result<&[u8]> { Ok(data) };
+
+    // (options, écrit, résultat si le fichier existe, résultat sinon)
+    let cases = [
+        (*OpenOptions::new().read(true), false, ok(b"hello"), not_found),
+        (*OpenOptions::new().write(true), true, ok(b"XYllo"), not_found),
+        (*OpenOptions::new().write(true).create(true), true, ok(b"XYllo"), ok(b"XY")),
+        (*OpenOptions::new().write(true).truncate(true), true, ok(b"XY"), not_found),
+        (
+            *OpenOptions::new().write(true).create(true).truncate(true),
+            true,
+            ok(b"XY"),
+            ok(b"XY"),
+        ),
+        (
+            *OpenOptions::new().write(true).create_new(true),
+            true,
+            Err(Fat32Error::AlreadyExists),
+            ok(b"XY"),
+        ),
+        (*OpenOptions::new().append(true), true, ok(b"helloXY"), not_found),
+        (*OpenOptions::new().append(true).create(true), true, ok(b"helloXY"), ok(b"XY")),
+        (*OpenOptions::new().read(true).append(true), true, ok(b"helloXY"), not_found),
+        (OpenOptions::new(), false, invalid, invalid),
+        (*OpenOptions::new().create(true), false, invalid, invalid),
+        (*OpenOptions::new().read(true).truncate(true), false, invalid, invalid),
+        (*OpenOptions::new().append(true).truncate(true), false, invalid, invalid),
+    ];
+
+    for (options, writes, if_exists, if_missing) in cases {
+        let expected = if_exists.map(|data| data.to_vec());
+        assert_eq!(run(&mut fs, &options, writes, true), expected, "{:?}, fichier existant", options);
+        let expected = if_missing.map(|data| data.to_vec());
+        assert_eq!(run(&mut fs, &options, writes, false), expected, "{:?}, fichier absent", options);
+    }
+}
+
+#[test]
+fn test_write_handle_grows_file() {
+    let mut fs = formatted_fs();
+    let data: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+
+    // Écritures de tailles qui ne tombent pas sur les clusters
+    let mut file = fs.open_with("/log.bin", OpenOptions::new().write(true).create(true)).unwrap();
+    for chunk in data.chunks(700) {
+        fs.write_handle(&mut file, chunk).unwrap();
+    }
+    assert_eq!(file.size(), 3000);
+    assert_eq!(fs.read_file("/log.bin").unwrap(), data);
+
+    // Réécriture au milieu, puis ajout en fin malgré la position
+    file.set_position(1000);
+    fs.write_handle(&mut file, &[0xAA; 600]).unwrap();
+    let mut appender = fs.open_with("/log.bin", OpenOptions::new().append(true)).unwrap();
+    appender.set_position(0);
+    fs.write_handle(&mut appender, b"end").unwrap();
+
+    let mut expected = data.clone();
+    expected[1000..1600].fill(0xAA);
+    expected.extend_from_slice(b"end");
+    assert_eq!(fs.read_file("/log.bin").unwrap(), expected);
+    assert_eq!(fs.used_clusters_by_file("/log.bin").unwrap(), 6);
+
+    // Les handles refusent ce que leurs options n'autorisent pas
+    let mut reader = fs.open_file("/log.bin").unwrap();
+    assert_eq!(fs.write_handle(&mut reader, b"x"), Err(Fat32Error::InvalidInput));
+    let mut buf = [0u8; 4];
+    assert_eq!(fs.read_handle(&mut appender, &mut buf), Err(Fat32Error::InvalidInput));
+}
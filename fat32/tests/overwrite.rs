@@ -0,0 +1,57 @@
+// Réécriture d'un fichier en place (`overwrite_file`)
+mod common;
+
+use common::IMAGE_SECTORS;
+use fat32::{Fat32Error, Fat32FileSystem, FatDate, FatTime, MemoryDevice, TimeSource};
+
+/// Horloge qui avance d'un jour à chaque appel
+struct Days(std::sync::atomic::AtomicU8);
+
+impl TimeSource for Days {
+    fn now(&self) -> (FatDate, FatTime) {
+        let day = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        (FatDate::new(2024, 3, day), FatTime::new(8, 0, 0))
+    }
+}
+
+fn entry(fs: &mut Fat32FileSystem<MemoryDevice>, name: &str) -> fat32::DirectoryEntry {
+    let entries = fs.list_dir(Some("/")).unwrap();
+    *entries.iter().find(|e| e.short_name() == name).unwrap()
+}
+
+#[test]
+fn test_overwrite_file_resizes_chain() {
+    let device = MemoryDevice::new(IMAGE_SECTORS);
+    let device = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap().into_device();
+    let mut fs = Fat32FileSystem::with_time_source(device, Days(1.into())).unwrap();
+
+    // Absent : créé comme avec write_file
+    fs.overwrite_file("/data.bin", &[1u8; 1500]).unwrap();
+    let created = entry(&mut fs, "DATA.BIN");
+    let first = created.first_cluster();
+    let free = fs.volume_info().unwrap().free_clusters;
+
+    // Plus long, puis plus court : même premier cluster, clusters rendus
+    fs.overwrite_file("/data.bin", &[2u8; 2500]).unwrap();
+    assert_eq!(fs.read_file("/data.bin").unwrap(), [2u8; 2500]);
+    assert_eq!(fs.volume_info().unwrap().free_clusters, free - 2);
+    fs.overwrite_file("/data.bin", b"court").unwrap();
+    assert_eq!(fs.read_file("/data.bin").unwrap(), b"court");
+    assert_eq!(fs.volume_info().unwrap().free_clusters, free + 2);
+
+    let data = entry(&mut fs, "DATA.BIN");
+    assert_eq!(data.first_cluster(), first);
+    assert_eq!(data.creation_date(), created.creation_date());
+    assert!(data.write_date() > created.write_date());
+
+    // Vide : chaîne libérée, puis de nouveau allouée
+    fs.overwrite_file("/data.bin", &[]).unwrap();
+    assert_eq!(entry(&mut fs, "DATA.BIN").first_cluster(), 0);
+    assert_eq!(fs.volume_info().unwrap().free_clusters, free + 3);
+    fs.overwrite_file("/data.bin", &[3u8; 600]).unwrap();
+    assert_eq!(fs.read_file("/data.bin").unwrap(), [3u8; 600]);
+    assert!(fs.check_integrity().unwrap().is_clean());
+
+    fs.create_dir("/dir").unwrap();
+    assert_eq!(fs.overwrite_file("/dir", b"x"), Err(Fat32Error::InvalidPath { component: 0 }));
+}
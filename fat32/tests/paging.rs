@@ -0,0 +1,64 @@
+// Listing d'un répertoire par pages
+mod common;
+
+use common::IMAGE_SECTORS;
+use fat32::devices::TraceDevice;
+use fat32::{Fat32FileSystem, FatCache, FatTable, MemoryDevice};
+
+#[test]
+fn test_list_dir_page() {
+    let device = TraceDevice::new(MemoryDevice::new(IMAGE_SECTORS));
+    let mut fs = Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap();
+
+    // 16 entrées par cluster : ".", ".." et 40 fichiers occupent 3 clusters
+    fs.create_dir("/photos").unwrap();
+    for i in 0..40 {
+        fs.write_file(&format!("/photos/img{}.jpg", i), b"x").unwrap();
+    }
+    // Les entrées supprimées ne comptent pas dans les indices
+    for i in [3, 4, 5] {
+        fs.delete_file(&format!("/photos/img{}.jpg", i)).unwrap();
+    }
+    let all = fs.list_dir(Some("/photos")).unwrap();
+    assert_eq!(all.len(), 39);
+
+    // Les pages reconstituent exactement list_dir
+    let mut pages = Vec::new();
+    let mut start = 0;
+    loop {
+        let (page, more) = fs.list_dir_page(Some("/photos"), start, 7).unwrap();
+        start += page.len();
+        pages.extend(page);
+        if !more {
+            break;
+        }
+    }
+    assert_eq!(pages, all);
+
+    let (page, more) = fs.list_dir_page(Some("/photos"), 36, 3).unwrap();
+    assert_eq!(page, all[36..]);
+    assert!(!more);
+    let (page, more) = fs.list_dir_page(Some("/photos"), 100, 3).unwrap();
+    assert!(page.is_empty() && !more);
+
+    // Une page au début ne lit pas les clusters suivants du répertoire
+    fs.flush().unwrap();
+    let bs = *fs.boot_sector();
+    let photos = all[0].first_cluster(); // entrée "."
+    let chain = {
+        let mut cache = FatCache::new();
+        FatTable::new(fs.device_mut(), &bs, &mut cache).cluster_chain(photos).unwrap()
+    };
+    assert_eq!(chain.len(), 3);
+    fs.clear_caches();
+    fs.device_mut().clear();
+
+    let (page, more) = fs.list_dir_page(Some("/photos"), 2, 5).unwrap();
+    assert_eq!(page, all[2..7]);
+    assert!(more);
+    let tail: Vec<u32> = chain[1..]
+        .iter()
+        .map(|&c| bs.first_data_sector() + (c - 2))
+        .collect();
+    assert!(fs.device().sectors_read().iter().all(|s| !tail.contains(s)));
+}
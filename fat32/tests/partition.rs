@@ -0,0 +1,62 @@
+// Volume FAT32 dans la première partition d'un disque MBR
+use fat32::{
+    BlockDevice, Fat32FileSystem, Fat32Error, IoOp, MemoryDevice, Mbr, PartitionDevice,
+    ReadBlockDevice,
+};
+
+const PARTITION_START: u32 = 2048;
+const PARTITION_SECTORS: u32 = 69_632; // 34 MiB
+
+/// Disque partitionné : MBR, puis FAT32 en partition 1 (type 0x0C)
+fn partitioned_disk() -> MemoryDevice {
+    let mut disk = MemoryDevice::new(PARTITION_START + PARTITION_SECTORS);
+
+    let partition = PartitionDevice::new(&mut disk, PARTITION_START, PARTITION_SECTORS);
+    let mut fs = Fat32FileSystem::format(partition, PARTITION_SECTORS, 1).unwrap();
+    fs.write_file("/hello.txt", b"depuis la partition").unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    let mbr = &mut disk.data_mut()[..512];
+    let entry = &mut mbr[446..462];
+    entry[4] = 0x0C;
+    entry[8..12].copy_from_slice(&PARTITION_START.to_le_bytes());
+    entry[12..16].copy_from_slice(&PARTITION_SECTORS.to_le_bytes());
+    mbr[510] = 0x55;
+    mbr[511] = 0xAA;
+    disk
+}
+
+#[test]
+fn test_mount_first_partition() {
+    let mut disk = partitioned_disk();
+
+    // Sans la table, le secteur 0 n'est pas un secteur de boot FAT32
+    assert!(Fat32FileSystem::new(&mut disk).is_err());
+
+    let mbr = Mbr::parse(&mut disk).unwrap();
+    assert!(mbr.entries[0].is_fat32());
+    assert_eq!(mbr.entries[0].start_lba, PARTITION_START);
+    assert!(mbr.entries[1..].iter().all(|entry| entry.is_empty()));
+
+    let mut fs = Fat32FileSystem::new_from_partition(disk, 0).unwrap();
+    assert_eq!(fs.read_file("/hello.txt").unwrap(), b"depuis la partition");
+    fs.write_file("/second.txt", b"2").unwrap();
+
+    // Accès limités à la partition
+    let mut sector = [0u8; 512];
+    let device = fs.device_mut();
+    let io = |op, sector| Err(Fat32Error::IoError { op, sector });
+    let end = PARTITION_SECTORS;
+    assert_eq!(device.read_sector(end, &mut sector), io(IoOp::Read, end));
+    assert_eq!(device.write_sector(end - 1, &[0; 1024]), io(IoOp::Write, end - 1));
+
+    // Rien n'a été écrit entre la table et la partition
+    let disk = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+    assert!(disk.data()[512..PARTITION_START as usize * 512].iter().all(|&b| b == 0));
+
+    // Entrée vide, hors table, disque sans MBR
+    for (disk, index) in [(disk.clone(), 1), (disk, 4), (MemoryDevice::new(16), 0)] {
+        let result = Fat32FileSystem::new_from_partition(disk, index);
+        assert_eq!(result.err(), Some(Fat32Error::InvalidPartition));
+    }
+}
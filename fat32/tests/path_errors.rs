@@ -0,0 +1,63 @@
+// Composant en cause dans les erreurs de chemin
+mod common;
+
+use common::not_found;
+use fat32::{Fat32Error, Fat32FileSystem, MemoryDevice};
+
+fn formatted_fs() -> Fat32FileSystem<MemoryDevice> {
+    let mut fs = common::formatted_fs();
+    fs.create_dir("/logs").unwrap();
+    fs.create_dir("/logs/2024").unwrap();
+    fs.create_dir("/logs/2024/jan").unwrap();
+    fs.write_file("/logs/2024/jan/data.bin", b"x").unwrap();
+    fs
+}
+
+#[test]
+fn test_not_found_component_at_each_depth() {
+    let mut fs = formatted_fs();
+    let cases = [
+        ("/journal/2024/jan/data.bin", 0, "journal"),
+        ("/logs/2023/jan/data.bin", 1, "2023"),
+        ("/logs/2024/feb/data.bin", 2, "feb"),
+        ("/logs/2024/jan/other.bin", 3, "other.bin"),
+    ];
+    for (path, index, name) in cases {
+        let err = fs.read_file(path).unwrap_err();
+        assert_eq!(err, not_found(index, name), "{}", path);
+        assert_eq!(err.component_index(), Some(index as usize));
+        assert_eq!(err.component(path), Some(name));
+    }
+    let err = fs.read_file("/logs/2024/feb/data.bin").unwrap_err();
+    assert_eq!(err.to_string(), "Composant 3 ('feb') du chemin non trouvé");
+
+    // Les composants vides et "." ne comptent pas, ".." oui
+    let path = "logs//./2024/../2023/jan";
+    let err = fs.list_dir(Some(path)).unwrap_err();
+    assert_eq!(err, not_found(3, "2023"));
+    assert_eq!(err.component(path), Some("2023"));
+    assert_eq!(fs.change_dir("/.."), Err(not_found(0, "..")));
+
+    // Nom trop long pour l'erreur : copie coupée entre deux caractères
+    let long = format!("a{}", "é".repeat(20));
+    let err = fs.read_file(&format!("/logs/{}", long)).unwrap_err();
+    let Fat32Error::NotFound { component: 1, name } = err else { panic!("{:?}", err) };
+    assert!(name.is_truncated());
+    assert_eq!(name.as_str(), &long[..31]);
+    let message = format!("Composant 2 ('{}…') du chemin non trouvé", &long[..31]);
+    assert_eq!(err.to_string(), message);
+}
+
+#[test]
+fn test_invalid_path_component() {
+    let mut fs = formatted_fs();
+    let path = "/logs/2024/jan";
+    let err = fs.delete_file(path).unwrap_err();
+    assert_eq!(err, Fat32Error::InvalidPath { component: 2 });
+    assert_eq!(err.component(path), Some("jan"));
+
+    // Caractère sans équivalent 8.3 dans le nom créé
+    let err = fs.write_file("/logs/2024/a+b.txt", b"x").unwrap_err();
+    assert_eq!(err, Fat32Error::InvalidPath { component: 2 });
+    assert_eq!(Fat32Error::<u8>::InvalidCluster.component_index(), None);
+}
@@ -0,0 +1,125 @@
+// Montage d'une source en lecture seule
+mod common;
+
+use std::convert::Infallible;
+
+use common::{formatted_fs_with, not_found};
+use fat32::devices::{GuardMode, TraceDevice, WriteGuard};
+use fat32::{
+    Fat32Error, Fat32FileSystem, MemoryDevice, OpenOptions, ReadBlockDevice, ReadOnlyFileSystem,
+    Result,
+};
+
+/// Image en "ROM" : seule la moitié lecture du device est implémentée
+struct RomDevice(TraceDevice<MemoryDevice>);
+
+impl ReadBlockDevice for RomDevice {
+    type Error = Infallible;
+
+    fn read_sector(&mut self, sector: u32, buffer: &mut [u8]) -> Result<()> {
+        self.0.read_sector(sector, buffer)
+    }
+
+    fn sector_size(&self) -> usize {
+        self.0.sector_size()
+    }
+}
+
+fn baked_image() -> Vec<u8> {
+    let mut fs = formatted_fs_with(2);
+    fs.create_dir("/etc").unwrap();
+    fs.write_file("/etc/motd.txt", b"bonjour\nle monde\n").unwrap();
+    fs.write_file("/data.bin", &[7u8; 5000]).unwrap();
+    fs.unmount().map_err(|(_, err)| err).unwrap().into_inner()
+}
+
+#[test]
+fn test_read_only_mount() {
+    let rom = RomDevice(TraceDevice::new(MemoryDevice::from_vec(baked_image())));
+    let mut fs = ReadOnlyFileSystem::new(rom).unwrap();
+
+    assert_eq!(fs.read_file("/data.bin").unwrap(), [7u8; 5000]);
+    let names: Vec<String> = fs.list_dir(None).unwrap().iter().map(|e| e.short_name()).collect();
+    assert_eq!(names, ["ETC", "DATA.BIN"]);
+
+    fs.change_dir("/etc").unwrap();
+    let lines: Vec<String> = fs.read_lines("motd.txt").unwrap().map(|l| l.unwrap()).collect();
+    assert_eq!(lines, ["bonjour", "le monde"]);
+
+    let mut file = fs.open_file("/data.bin").unwrap();
+    let mut buf = [0u8; 100];
+    assert_eq!(fs.read_handle(&mut file, &mut buf).unwrap(), 100);
+    assert_eq!(fs.read_file("/absent"), Err(not_found(0, "absent")));
+
+    assert!(fs.check_integrity().unwrap().is_clean());
+    // Racine, /etc, motd.txt et les 5 clusters de data.bin
+    assert_eq!(fs.volume_info().unwrap().used_clusters, 8);
+
+    let device = fs.into_device();
+    assert!(device.0.summary().reads > 0);
+}
+
+#[test]
+fn test_readonly_flag() {
+    let mut fs = Fat32FileSystem::with_readonly(MemoryDevice::from_vec(baked_image())).unwrap();
+    assert!(fs.is_readonly());
+
+    // Toutes les écritures sont refusées, le device n'est pas touché
+    let before = fs.device().data().to_vec();
+    assert_eq!(fs.write_file("/new.txt", b"x"), Err(Fat32Error::ReadOnlyFilesystem));
+    assert_eq!(fs.create_dir("/dir"), Err(Fat32Error::ReadOnlyFilesystem));
+    assert_eq!(fs.delete_file("/etc/motd.txt"), Err(Fat32Error::ReadOnlyFilesystem));
+    assert_eq!(fs.touch("/etc/motd.txt"), Err(Fat32Error::ReadOnlyFilesystem));
+    let write = *OpenOptions::new().write(true);
+    assert_eq!(fs.open_with("/etc/motd.txt", &write).unwrap_err(), Fat32Error::ReadOnlyFilesystem);
+    assert_eq!(fs.read_file("/etc/motd.txt").unwrap(), b"bonjour\nle monde\n");
+    fs.flush().unwrap();
+    assert_eq!(fs.device().data(), &before[..]);
+
+    // Un handle ouvert en écriture avant le passage en lecture seule aussi
+    fs.set_readonly(false);
+    let mut file = fs.open_with("/etc/motd.txt", &write).unwrap();
+    fs.set_readonly(true);
+    assert_eq!(fs.write_handle(&mut file, b"!"), Err(Fat32Error::ReadOnlyFilesystem));
+
+    fs.set_readonly(false);
+    fs.write_file("/new.txt", b"x").unwrap();
+    assert_eq!(fs.read_file("/new.txt").unwrap(), b"x");
+}
+
+#[test]
+fn test_readonly_flag_never_writes() {
+    // Défense en profondeur : une écriture qui passerait serait refusée et notée
+    let device = WriteGuard::new(MemoryDevice::from_vec(baked_image()), GuardMode::Deny);
+    let mut fs = Fat32FileSystem::with_readonly(device).unwrap();
+
+    assert_eq!(fs.read_file("/data.bin").unwrap(), [7u8; 5000]);
+    fs.list_dir(Some("/etc")).unwrap();
+    assert!(fs.check_integrity().unwrap().is_clean());
+    assert_eq!(fs.write_file("/new.txt", b"x"), Err(Fat32Error::ReadOnlyFilesystem));
+
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert_eq!(device.writes(), []);
+}
+
+#[test]
+fn test_readonly_unmount_keeps_dirty_volume() {
+    // Volume démonté salement : bit "propre" de l'entrée 1 à zéro
+    let mut image = baked_image();
+    let fs = Fat32FileSystem::new(MemoryDevice::from_vec(image.clone())).unwrap();
+    let bs = *fs.boot_sector();
+    drop(fs.into_device());
+    for fat in 0..bs.num_fats as u32 {
+        let offset = (bs.first_fat_sector() + fat * bs.fat_size()) as usize * 512 + 7;
+        image[offset] &= !0x08;
+    }
+
+    let fs = Fat32FileSystem::with_readonly(MemoryDevice::from_vec(image.clone())).unwrap();
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    assert_eq!(device.data(), &image[..]);
+
+    let mut fs = Fat32FileSystem::with_readonly(MemoryDevice::from_vec(image.clone())).unwrap();
+    assert_eq!(fs.read_file("/etc/motd.txt").unwrap(), b"bonjour\nle monde\n");
+    fs.flush().unwrap();
+    assert_eq!(fs.into_device().data(), &image[..]);
+}
@@ -0,0 +1,46 @@
+// Réparations ciblées des entrées de répertoire
+mod common;
+
+use common::{formatted_fs, not_found};
+use fat32::{BootSector, Fat32FileSystem, MemoryDevice};
+
+/// Écraser la taille de la première entrée du répertoire racine
+fn corrupt_first_entry_size(fs: &mut Fat32FileSystem<MemoryDevice>, size: u32) {
+    fs.flush().unwrap();
+    let device = fs.device_mut();
+    let bs = unsafe { BootSector::from_bytes(&device.data_mut()[0..512]) };
+    let offset = bs.first_data_sector() as usize * 512 + 28;
+    device.data_mut()[offset..offset + 4].copy_from_slice(&size.to_le_bytes());
+    fs.clear_caches();
+}
+
+#[test]
+fn test_sync_directory_entry_size() {
+    let mut fs = formatted_fs();
+    fs.write_file("/a.bin", &[1u8; 1500]).unwrap();
+
+    // Taille cohérente : rien ne change
+    fs.sync_directory_entry_size("/a.bin").unwrap();
+    assert_eq!(fs.read_file("/a.bin").unwrap().len(), 1500);
+
+    // Taille trop grande pour la chaîne de 3 clusters
+    corrupt_first_entry_size(&mut fs, 100_000);
+    fs.sync_directory_entry_size("/a.bin").unwrap();
+    assert_eq!(fs.read_file("/a.bin").unwrap().len(), 3 * 512);
+
+    // Chaîne plus longue que la taille : clusters réservés, rien ne change
+    corrupt_first_entry_size(&mut fs, 10);
+    fs.sync_directory_entry_size("/a.bin").unwrap();
+    assert_eq!(fs.read_file("/a.bin").unwrap().len(), 10);
+
+    // Réservation sur un fichier vide : les vieux clusters restent cachés
+    fs.write_file("/old.bin", &[0xAA; 2048]).unwrap();
+    fs.delete_file("/old.bin").unwrap();
+    fs.write_file("/log.txt", b"").unwrap();
+    fs.reserve("/log.txt", 2048).unwrap();
+    fs.sync_directory_entry_size("/log.txt").unwrap();
+    assert_eq!(fs.read_file("/log.txt").unwrap(), b"");
+
+    let missing = fs.sync_directory_entry_size("/missing.bin");
+    assert_eq!(missing, Err(not_found(0, "missing.bin")));
+}
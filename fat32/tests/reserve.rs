@@ -0,0 +1,63 @@
+// Pré-allocation de clusters (reserve) et écritures dans la réservation
+mod common;
+
+use common::{formatted_fs, not_found};
+use fat32::{Fat32Error, OpenOptions};
+
+#[test]
+fn test_reserve_then_append() {
+    let mut fs = formatted_fs();
+    // Un trou dans l'espace libre : la réservation doit le sauter
+    fs.write_file("/a.bin", &[1; 512]).unwrap();
+    fs.write_file("/b.bin", &[2; 512]).unwrap();
+    fs.delete_file("/a.bin").unwrap();
+    fs.write_file("/log.txt", b"").unwrap();
+
+    let free = fs.free_space().unwrap();
+    fs.reserve("/log.txt", 10_000).unwrap();
+    assert_eq!(fs.free_space().unwrap(), free - 20 * 512);
+    assert_eq!(fs.used_clusters_by_file("/log.txt").unwrap(), 20);
+    assert_eq!(fs.read_file("/log.txt").unwrap(), b"");
+
+    // Une réservation plus petite ne change rien
+    fs.reserve("/log.txt", 4000).unwrap();
+    assert_eq!(fs.free_space().unwrap(), free - 20 * 512);
+
+    // Les ajouts avancent dans les clusters réservés
+    let record: Vec<u8> = (0..700u32).map(|i| (i % 200) as u8).collect();
+    let mut log = fs.open_with("/log.txt", OpenOptions::new().append(true)).unwrap();
+    for _ in 0..6 {
+        fs.write_handle(&mut log, &record).unwrap();
+    }
+    assert_eq!(fs.free_space().unwrap(), free - 20 * 512);
+    assert_eq!(fs.read_file("/log.txt").unwrap(), record.repeat(6));
+
+    // Chaîne contiguë : la défragmentation n'a rien à déplacer
+    assert_eq!(fs.defragment_file("/log.txt").unwrap().clusters_moved, 0);
+
+    // Au-delà de la réservation, la chaîne grandit normalement
+    fs.reserve("/log.txt", 4300).unwrap();
+    for _ in 0..10 {
+        fs.write_handle(&mut log, &record).unwrap();
+    }
+    assert_eq!(fs.read_file("/log.txt").unwrap(), record.repeat(16));
+    assert_eq!(fs.used_clusters_by_file("/log.txt").unwrap(), 22);
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_reserve_disk_full_allocates_nothing() {
+    let mut fs = formatted_fs();
+    fs.write_file("/data.bin", b"abc").unwrap();
+    let free = fs.free_space().unwrap();
+
+    assert_eq!(fs.reserve("/data.bin", free + 1024), Err(Fat32Error::DiskFull));
+    assert_eq!(fs.free_space().unwrap(), free);
+    assert_eq!(fs.used_clusters_by_file("/data.bin").unwrap(), 1);
+
+    // Tronquer libère aussi la réservation
+    fs.reserve("/data.bin", 5000).unwrap();
+    fs.open_with("/data.bin", OpenOptions::new().write(true).truncate(true)).unwrap();
+    assert_eq!(fs.free_space().unwrap(), free + 512);
+    assert_eq!(fs.reserve("/missing.bin", 10), Err(not_found(0, "missing.bin")));
+}
@@ -0,0 +1,42 @@
+// Reprise des accès en échec : une panne passagère n'interrompt plus la lecture
+mod common;
+
+use common::formatted_fs_with;
+use fat32::devices::{Fault, FaultyDevice, RetryDevice};
+use fat32::{Fat32Error, Fat32FileSystem, IoOp};
+
+#[test]
+fn test_retry_transient_failures() {
+    let mut fs = formatted_fs_with(2);
+    let data: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+    fs.write_file("/a.bin", &data).unwrap();
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    let device = RetryDevice::new(FaultyDevice::new(device), 3);
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.device().retries(), 0);
+
+    // La prochaine lecture échoue une fois
+    let reads = fs.device().inner().reads();
+    fs.device_mut().inner_mut().inject(Fault::FailRead(reads));
+    assert_eq!(fs.read_file("/a.bin").unwrap(), data);
+    assert_eq!(fs.device().retries(), 1);
+
+    // Écriture ratée une fois, puis faite
+    let writes = fs.device().inner().writes();
+    fs.device_mut().inner_mut().inject(Fault::FailWrite(writes));
+    fs.write_file("/b.txt", b"bonjour").unwrap();
+    fs.flush().unwrap();
+    assert_eq!(fs.device().retries(), 2);
+    assert_eq!(fs.read_file("/b.txt").unwrap(), b"bonjour");
+
+    // Sans reprise, la même panne fait échouer la lecture
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    let mut fs = Fat32FileSystem::new(device.into_inner()).unwrap();
+    let reads = fs.device().reads();
+    fs.device_mut().inject(Fault::FailRead(reads));
+    // L'erreur désigne la lecture ratée : le premier secteur de la racine
+    let root = fs.boot_sector().first_data_sector();
+    let error = Fat32Error::IoError { op: IoOp::Read, sector: root };
+    assert_eq!(fs.read_file("/a.bin"), Err(error));
+}
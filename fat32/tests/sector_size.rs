@@ -0,0 +1,37 @@
+// Taille de secteur du device comparée à celle du volume
+mod common;
+
+use common::formatted_fs;
+use fat32::devices::SectorTranslator;
+use fat32::{Fat32Error, Fat32FileSystem, MemoryDevice};
+
+#[test]
+fn test_sector_size_mismatch() {
+    let fs = formatted_fs();
+    let data = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+
+    // Image 512 octets/secteur vue par un device qui annonce des secteurs de 4096
+    let native = MemoryDevice::from_vec_with_sector_size(data, 4096);
+    let err = Fat32FileSystem::new(native).err().unwrap();
+    assert_eq!(err, Fat32Error::SectorSizeMismatch { device: 4096, volume: 512 });
+    assert_eq!(err.to_string(), "Secteurs de 4096 octets sur le device, 512 dans le boot sector");
+}
+
+#[test]
+fn test_mount_through_translator() {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+    let mut fs = formatted_fs();
+    fs.write_file("/a.bin", &data).unwrap();
+    let image = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner();
+
+    // Même image vue en secteurs physiques de 4096 octets
+    let physical = MemoryDevice::from_vec_with_sector_size(image, 4096);
+    let mut fs = Fat32FileSystem::new(SectorTranslator::new(physical).unwrap()).unwrap();
+    assert_eq!(fs.read_file("/a.bin").unwrap(), data);
+    fs.write_file("/b.txt", b"ecrit en 4K").unwrap();
+
+    let physical = fs.unmount().map_err(|(_, err)| err).unwrap().into_inner().unwrap();
+    let mut fs = Fat32FileSystem::new(MemoryDevice::from_vec(physical.into_inner())).unwrap();
+    assert_eq!(fs.read_file("/b.txt").unwrap(), b"ecrit en 4K");
+    assert_eq!(fs.read_file("/a.bin").unwrap(), data);
+}
@@ -0,0 +1,64 @@
+// Lecture de fichiers texte
+mod common;
+
+use common::formatted_fs;
+use fat32::{Fat32FileSystem, Fat32Error, MemoryDevice};
+
+fn lines(fs: &mut Fat32FileSystem<MemoryDevice>, path: &str) -> Vec<String> {
+    fs.read_lines(path).unwrap().map(|line| line.unwrap()).collect()
+}
+
+#[test]
+fn test_read_to_string() {
+    let mut fs = formatted_fs();
+    fs.write_file("/config.ini", "clé=valeur\n".as_bytes()).unwrap();
+    fs.write_file("/binary.bin", &[0x66, 0xFF, 0xFE]).unwrap();
+
+    assert_eq!(fs.read_to_string("/config.ini").unwrap(), "clé=valeur\n");
+    assert_eq!(fs.read_to_string("/binary.bin"), Err(Fat32Error::InvalidUtf8));
+}
+
+#[test]
+fn test_read_lines_newlines() {
+    let mut fs = formatted_fs();
+
+    // Les trois conventions, sans fin de ligne finale
+    fs.write_file("/mixed.txt", b"unix\ndos\r\nmac\rlast").unwrap();
+    assert_eq!(lines(&mut fs, "/mixed.txt"), ["unix", "dos", "mac", "last"]);
+
+    // Lignes vides et fin de ligne finale
+    fs.write_file("/empty.txt", b"").unwrap();
+    fs.write_file("/blank.txt", b"\n\r\n\ra\n").unwrap();
+    assert!(lines(&mut fs, "/empty.txt").is_empty());
+    assert_eq!(lines(&mut fs, "/blank.txt"), ["", "", "", "a"]);
+
+    // \r\n coupé entre deux clusters, ligne plus longue qu'un cluster
+    let mut data = vec![b'x'; 511];
+    data.extend_from_slice(b"\r\n");
+    data.extend(std::iter::repeat_n(b'y', 1300));
+    fs.write_file("/long.txt", &data).unwrap();
+    let read = lines(&mut fs, "/long.txt");
+    assert_eq!(read.len(), 2);
+    assert_eq!(read[0].len(), 511);
+    assert_eq!(read[1], "y".repeat(1300));
+}
+
+#[test]
+fn test_read_lines_bom_and_invalid() {
+    let mut fs = formatted_fs();
+    fs.write_file("/bom.txt", b"\xEF\xBB\xBFfirst\nsecond").unwrap();
+
+    assert_eq!(lines(&mut fs, "/bom.txt")[0], "\u{FEFF}first");
+    let stripped: Vec<String> = fs
+        .read_lines("/bom.txt")
+        .unwrap()
+        .strip_bom(true)
+        .map(|line| line.unwrap())
+        .collect();
+    assert_eq!(stripped, ["first", "second"]);
+
+    // Une ligne invalide n'arrête pas la lecture
+    fs.write_file("/bad.txt", b"ok\n\xFF\nok").unwrap();
+    let read: Vec<_> = fs.read_lines("/bad.txt").unwrap().collect();
+    assert_eq!(read, [Ok("ok".into()), Err(Fat32Error::InvalidUtf8), Ok("ok".into())]);
+}
@@ -0,0 +1,58 @@
+// Datation des entrées par une source de temps
+mod common;
+
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Arc;
+
+use common::formatted_fs;
+use fat32::{Fat32FileSystem, FatDate, FatTime, MemoryDevice, TimeSource};
+
+/// Horloge de test : le jour du mois est réglable
+struct FixedClock(Arc<AtomicU16>);
+
+impl TimeSource for FixedClock {
+    fn now(&self) -> (FatDate, FatTime) {
+        let day = self.0.load(Ordering::SeqCst) as u8;
+        (FatDate::new(2024, 5, day), FatTime::new(10, 30, 0))
+    }
+}
+
+fn entry(fs: &mut Fat32FileSystem<MemoryDevice>, name: &str) -> fat32::DirectoryEntry {
+    let entries = fs.list_dir(Some("/")).unwrap();
+    *entries.iter().find(|e| e.short_name() == name).unwrap()
+}
+
+#[test]
+fn test_entries_are_timestamped() {
+    let fs = formatted_fs();
+
+    // Sans source de temps : dates à zéro
+    let mut fs = Fat32FileSystem::new(fs.into_device()).unwrap();
+    fs.write_file("/plain.txt", b"x").unwrap();
+    assert_eq!(entry(&mut fs, "PLAIN.TXT").write_date(), FatDate(0));
+
+    let day = Arc::new(AtomicU16::new(1));
+    let mut fs = Fat32FileSystem::with_time_source(fs.into_device(), FixedClock(day.clone())).unwrap();
+    fs.write_file("/a.txt", b"data").unwrap();
+    fs.create_dir("/dir").unwrap();
+
+    let a = entry(&mut fs, "A.TXT");
+    assert_eq!(a.creation_date(), FatDate::new(2024, 5, 1));
+    assert_eq!(a.write_date(), FatDate::new(2024, 5, 1));
+    assert_eq!(a.write_time(), FatTime::new(10, 30, 0));
+    assert_eq!(entry(&mut fs, "DIR").creation_date(), FatDate::new(2024, 5, 1));
+
+    // touch met à jour la modification mais pas la création
+    day.store(9, Ordering::SeqCst);
+    fs.touch("/a.txt").unwrap();
+    let a = entry(&mut fs, "A.TXT");
+    assert_eq!(a.creation_date(), FatDate::new(2024, 5, 1));
+    assert_eq!(a.write_date(), FatDate::new(2024, 5, 9));
+    assert_eq!(a.last_access_date(), FatDate::new(2024, 5, 9));
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"data");
+
+    // touch crée un fichier vide s'il n'existe pas
+    fs.touch("/new.txt").unwrap();
+    assert_eq!(entry(&mut fs, "NEW.TXT").creation_date(), FatDate::new(2024, 5, 9));
+    assert!(fs.read_file("/new.txt").unwrap().is_empty());
+}
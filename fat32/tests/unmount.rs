@@ -0,0 +1,73 @@
+// Fin de vie du système de fichiers : unmount et Drop
+mod common;
+
+use common::{clean_on_disk, IMAGE_SECTORS};
+use fat32::{Fat32FileSystem, Fat32Error, MemoryDevice};
+
+/// Volume vide sur un device emprunté, dont l'image reste accessible après
+/// la destruction du système de fichiers
+fn formatted_on(device: &mut MemoryDevice) -> Fat32FileSystem<&mut MemoryDevice> {
+    Fat32FileSystem::format(device, IMAGE_SECTORS, 1).unwrap()
+}
+
+#[test]
+fn test_drop_flushes_pending_writes() {
+    let mut image = MemoryDevice::new(IMAGE_SECTORS);
+    let mut fs = formatted_on(&mut image);
+    fs.write_file("/kept.txt", b"sur le disque").unwrap();
+    assert!(fs.is_dirty());
+    drop(fs);
+
+    let mut fs = Fat32FileSystem::new(&mut image).unwrap();
+    assert_eq!(fs.read_file("/kept.txt").unwrap(), b"sur le disque");
+}
+
+#[test]
+fn test_unmount_retry_and_clean_bit() {
+    let mut image = MemoryDevice::new(IMAGE_SECTORS);
+    let Ok(_) = formatted_on(&mut image).unmount() else { panic!("unmount après format") };
+    assert!(clean_on_disk(&mut image));
+
+    // La lecture ne touche pas au bit ; la première écriture l'efface sur
+    // le disque avant même un flush
+    let mut fs = Fat32FileSystem::new(&mut image).unwrap();
+    fs.list_dir(Some("/")).unwrap();
+    assert!(clean_on_disk(fs.device_mut()));
+    fs.write_file("/a.txt", b"a").unwrap();
+    assert!(!clean_on_disk(fs.device_mut()));
+
+    fs.device_mut().set_read_only(true);
+    let mut fs = match fs.unmount() {
+        Ok(_) => panic!("unmount sur un device en panne"),
+        Err((fs, err)) => {
+            assert_eq!(err, Fat32Error::ReadOnly);
+            fs
+        }
+    };
+    assert!(fs.is_dirty());
+    assert!(!clean_on_disk(fs.device_mut()));
+
+    fs.device_mut().set_read_only(false);
+    let Ok(device) = fs.unmount() else { panic!("second unmount") };
+    assert!(clean_on_disk(device));
+
+    let mut fs = Fat32FileSystem::new(&mut image).unwrap();
+    assert_eq!(fs.read_file("/a.txt").unwrap(), b"a");
+}
+
+#[test]
+fn test_crashed_volume_stays_unclean() {
+    let mut image = MemoryDevice::new(IMAGE_SECTORS);
+    let mut fs = formatted_on(&mut image);
+    fs.write_file("/a.txt", b"a").unwrap();
+    fs.flush().unwrap();
+    // Coupure : le système de fichiers disparaît sans unmount ni Drop
+    core::mem::forget(fs);
+    assert!(!clean_on_disk(&mut image));
+
+    // Le montage suivant ne remet pas le bit : la vérification reste à faire
+    let mut fs = Fat32FileSystem::new(&mut image).unwrap();
+    fs.write_file("/b.txt", b"b").unwrap();
+    let Ok(device) = fs.unmount() else { panic!("unmount") };
+    assert!(!clean_on_disk(device));
+}
@@ -0,0 +1,66 @@
+// Informations du volume en un appel
+mod common;
+
+use common::{formatted_fs_with, image_sectors};
+use fat32::{Fat32Error, Fat32FileSystem};
+
+#[test]
+fn test_volume_info() {
+    let mut fs = formatted_fs_with(2);
+    let info = fs.volume_info().unwrap();
+
+    assert_eq!(info.label, "NO NAME");
+    assert_eq!(info.oem_name, "MSWIN4.1");
+    assert_eq!(info.serial, 0x1234_5678);
+    assert_eq!(info.total_sectors, image_sectors(2));
+    assert_eq!(info.cluster_size, 1024);
+    assert_eq!(info.fs_version, 0);
+    assert_eq!(info.root_cluster, 2);
+    assert_eq!(info.fs_info_sector, 1);
+    assert_eq!(info.backup_boot_sector, 6);
+    // Seule la racine occupe un cluster
+    assert_eq!(info.used_clusters, 1);
+    assert_eq!(info.free_clusters as u64 * 1024, fs.free_space().unwrap());
+
+    // 3000 octets = 3 clusters de plus
+    fs.write_file("/a.bin", &[1u8; 3000]).unwrap();
+    let after = fs.volume_info().unwrap();
+    assert_eq!(after.used_clusters, 4);
+    assert_eq!(after.free_clusters, info.free_clusters - 3);
+}
+
+#[test]
+fn test_rename_volume() {
+    let mut fs = formatted_fs_with(2);
+    fs.write_file("/a.txt", b"a").unwrap();
+    fs.rename_volume("Mes Photos").unwrap();
+    assert_eq!(fs.volume_info().unwrap().label, "MES PHOTOS");
+    // L'entrée « volume » n'apparaît pas dans les listings
+    assert_eq!(fs.list_dir(None).unwrap().len(), 1);
+
+    assert_eq!(fs.rename_volume("TROP LONG NOM"), Err(Fat32Error::NameTooLong));
+    assert!(matches!(fs.rename_volume("A*B"), Err(Fat32Error::InvalidName(_))));
+
+    // Boot sector, copie de secours et entrée de la racine sur le disque
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    let data = device.data();
+    assert_eq!(&data[71..82], b"MES PHOTOS ");
+    assert_eq!(&data[6 * 512 + 71..6 * 512 + 82], b"MES PHOTOS ");
+    assert_eq!(data[0..3], [0xEB, 0x58, 0x90]);
+
+    let mut fs = Fat32FileSystem::new(device).unwrap();
+    assert_eq!(fs.volume_info().unwrap().label, "MES PHOTOS");
+    fs.rename_volume("DATA").unwrap();
+    assert!(fs.check_integrity().unwrap().is_clean());
+
+    // Nom vide : l'entrée disparaît
+    fs.rename_volume("").unwrap();
+    assert_eq!(fs.volume_info().unwrap().label, "NO NAME");
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+    let root = {
+        let bs = unsafe { fat32::BootSector::from_bytes(device.data()) };
+        bs.first_data_sector() as usize * 512
+    };
+    let entries = &device.data()[root..root + 512];
+    assert!(entries.chunks(32).all(|e| e[11] & 0x08 == 0 || e[0] == 0xE5));
+}
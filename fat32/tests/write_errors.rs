@@ -0,0 +1,61 @@
+// Erreurs précises des opérations d'écriture
+mod common;
+
+use common::{formatted_fs, not_found};
+use fat32::{Fat32Error, Fat32FileSystem};
+
+#[test]
+fn test_already_exists() {
+    let mut fs = formatted_fs();
+    fs.create_dir("/docs").unwrap();
+    fs.write_file("/a.txt", b"x").unwrap();
+    assert_eq!(fs.create_dir("/docs"), Err(Fat32Error::AlreadyExists));
+    assert_eq!(fs.create_dir("/a.txt"), Err(Fat32Error::AlreadyExists));
+}
+
+#[test]
+fn test_remove_dir() {
+    let mut fs = formatted_fs();
+    let free = fs.free_space().unwrap();
+    fs.create_dir("/docs").unwrap();
+    fs.create_dir("/docs/old").unwrap();
+    fs.write_file("/docs/a.txt", b"x").unwrap();
+
+    assert_eq!(fs.remove_dir("/docs"), Err(Fat32Error::DirectoryNotEmpty));
+    assert_eq!(fs.remove_dir("/docs/a.txt"), Err(Fat32Error::NotADirectory));
+    assert_eq!(fs.remove_dir("/docs/none"), Err(not_found(1, "none")));
+    fs.change_dir("/docs/old").unwrap();
+    assert_eq!(fs.remove_dir("/docs/old"), Err(Fat32Error::InvalidPath { component: 1 }));
+    fs.change_dir("/").unwrap();
+
+    // Vidé, il disparaît avec ses clusters
+    fs.remove_dir("/docs/old").unwrap();
+    fs.delete_file("/docs/a.txt").unwrap();
+    fs.remove_dir("/docs").unwrap();
+    assert_eq!(fs.list_dir(Some("/docs")).unwrap_err(), not_found(0, "docs"));
+    assert_eq!(fs.free_space().unwrap(), free);
+    assert!(fs.check_integrity().unwrap().is_clean());
+}
+
+#[test]
+fn test_read_only_filesystem() {
+    let mut fs = formatted_fs();
+    fs.create_dir("/docs").unwrap();
+    let device = fs.unmount().map_err(|(_, err)| err).unwrap();
+
+    let mut fs = Fat32FileSystem::with_readonly(device).unwrap();
+    assert_eq!(fs.remove_dir("/docs"), Err(Fat32Error::ReadOnlyFilesystem));
+    assert_eq!(fs.write_file("/a.txt", b"x"), Err(Fat32Error::ReadOnlyFilesystem));
+    assert_eq!(fs.list_dir(Some("/docs")).unwrap().len(), 2);
+}
+
+#[test]
+fn test_name_too_long() {
+    let mut fs = formatted_fs();
+    // Pas de nom 8.3 possible
+    assert_eq!(fs.write_file("/rapport2024.txt", b"x"), Err(Fat32Error::NameTooLong));
+    assert_eq!(fs.create_dir("/a.json"), Err(Fat32Error::NameTooLong));
+    // Au-delà de la limite des noms longs
+    let long_name = format!("/{}", "a".repeat(256));
+    assert_eq!(fs.write_file(&long_name, b"x"), Err(Fat32Error::NameTooLong));
+}